@@ -0,0 +1,383 @@
+#![forbid(rust_2018_compatibility, deprecated_in_future)]
+#![deny(rust_2021_compatibility, unsafe_code, warnings, clippy::pedantic)]
+
+/// Version of the ABI, incremented on every incompatible change to the
+/// provided functions.
+pub const VERSION: u32 = 1;
+
+/// Name of the custom section through which modules declare the version of
+/// the ABI they target, encoded as a little-endian 32-bit integer.
+pub const VERSION_SECTION: &str = "sdk_abi_version";
+
+/// Name under which modules export their default entry point.
+pub const ENTRY_EXPORT: &str = "entry";
+
+/// Prefix of the names under which modules export named handlers, followed by
+/// the handler's name.
+pub const HANDLER_EXPORT_PREFIX: &str = "entry::";
+
+/// Type of a host function's parameter or result.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Type {
+    I32,
+    I64,
+    /// Pointer-sized integer, being `i32` or `i64` depending on the pointer
+    /// width of the importing module.
+    Usize,
+}
+
+/// WebAssembly value type of a host function's parameter or result, as
+/// imported by a module.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ValueType {
+    I32,
+    I64,
+}
+
+/// Pointer width of a module, selecting which variant of pointer-sized
+/// functions it imports.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PointerWidth {
+    Bits32,
+    Bits64,
+}
+
+impl PointerWidth {
+    pub const ALL: [Self; 2] = [Self::Bits32, Self::Bits64];
+
+    /// Suffix of the names of pointer-sized functions' variants.
+    #[must_use]
+    pub const fn suffix(self) -> &'static str {
+        match self {
+            Self::Bits32 => "~32",
+            Self::Bits64 => "~64",
+        }
+    }
+
+    #[must_use]
+    pub const fn resolve(self, r#type: Type) -> ValueType {
+        match (r#type, self) {
+            (Type::I32, _) | (Type::Usize, Self::Bits32) => ValueType::I32,
+            (Type::I64, _) | (Type::Usize, Self::Bits64) => ValueType::I64,
+        }
+    }
+}
+
+/// Function provided by the runtime to modules.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Function {
+    pub module: &'static str,
+    pub name: &'static str,
+    pub params: &'static [Type],
+    pub results: &'static [Type],
+}
+
+impl Function {
+    /// Returns whether the function is provided in a variant for each pointer
+    /// width, which is the case when it takes or returns pointer-sized
+    /// integers.
+    #[must_use]
+    pub const fn is_pointer_sized(&self) -> bool {
+        contains_usize(self.params) || contains_usize(self.results)
+    }
+
+    /// Finds function provided under provided module and name, without the
+    /// pointer width suffix.
+    #[must_use]
+    pub const fn find(module: &str, name: &str) -> Option<&'static Self> {
+        let mut index: usize = 0;
+
+        while index < FUNCTIONS.len() {
+            let function: &Function = &FUNCTIONS[index];
+
+            if str_eq(function.module, module) && str_eq(function.name, name) {
+                return Some(function);
+            }
+
+            index += 1;
+        }
+
+        None
+    }
+
+    /// Panics unless function is provided under provided module and name with
+    /// provided signature. Evaluated in constants by modules, turning
+    /// mismatched declarations into compilation errors.
+    ///
+    /// # Panics
+    /// Panics when no such function is provided, when it is pointer-sized
+    /// while it is not declared as such or vice versa, or when its parameters
+    /// or results don't match.
+    pub const fn assert_provided(
+        module: &str,
+        name: &str,
+        pointer_sized: bool,
+        params: &[Type],
+        results: &[Type],
+    ) {
+        let Some(function) = Self::find(module, name) else {
+            panic!("Declared function is not provided by the runtime!");
+        };
+
+        assert!(
+            function.is_pointer_sized() == pointer_sized,
+            "Declared function's pointer-sizedness doesn't match!"
+        );
+
+        assert!(
+            types_eq(function.params, params),
+            "Declared function's parameters don't match!"
+        );
+
+        assert!(
+            types_eq(function.results, results),
+            "Declared function's results don't match!"
+        );
+    }
+
+    /// Returns the function as imported by a module of provided pointer width.
+    #[must_use]
+    pub fn import(&self, width: PointerWidth) -> Import {
+        let resolve = |types: &[Type]| -> Vec<ValueType> {
+            types
+                .iter()
+                .map(|&r#type: &Type| width.resolve(r#type))
+                .collect()
+        };
+
+        Import {
+            module: self.module,
+            name: if self.is_pointer_sized() {
+                format!("{}{}", self.name, width.suffix())
+            } else {
+                String::from(self.name)
+            },
+            params: resolve(self.params),
+            results: resolve(self.results),
+        }
+    }
+}
+
+const fn contains_usize(types: &[Type]) -> bool {
+    let mut index: usize = 0;
+
+    while index < types.len() {
+        if matches!(types[index], Type::Usize) {
+            return true;
+        }
+
+        index += 1;
+    }
+
+    false
+}
+
+const fn types_eq(left: &[Type], right: &[Type]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+
+    let mut index: usize = 0;
+
+    while index < left.len() {
+        if left[index] as u8 != right[index] as u8 {
+            return false;
+        }
+
+        index += 1;
+    }
+
+    true
+}
+
+const fn str_eq(left: &str, right: &str) -> bool {
+    let (left, right): (&[u8], &[u8]) = (left.as_bytes(), right.as_bytes());
+
+    if left.len() != right.len() {
+        return false;
+    }
+
+    let mut index: usize = 0;
+
+    while index < left.len() {
+        if left[index] != right[index] {
+            return false;
+        }
+
+        index += 1;
+    }
+
+    true
+}
+
+/// Host function as imported by a module of particular pointer width.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Import {
+    pub module: &'static str,
+    pub name: String,
+    pub params: Vec<ValueType>,
+    pub results: Vec<ValueType>,
+}
+
+/// Returns imports of all functions provided to modules of provided pointer
+/// width.
+pub fn imports(width: PointerWidth) -> impl Iterator<Item = Import> {
+    FUNCTIONS
+        .iter()
+        .map(move |function: &Function| function.import(width))
+}
+
+/// Finds function imported under provided module and name, as imported by a
+/// module of the pointer width selected by the name.
+#[must_use]
+pub fn find_import(module: &str, name: &str) -> Option<Import> {
+    PointerWidth::ALL
+        .into_iter()
+        .flat_map(imports)
+        .find(|import: &Import| import.module == module && import.name == name)
+}
+
+macro_rules! functions {
+    ($($module: literal {
+        $($name: literal($($param: ident),*) $(-> $result: ident)?;)+
+    })+) => {
+        &[$($(Function {
+            module: $module,
+            name: $name,
+            params: &[$(Type::$param),*],
+            results: &[$(Type::$result)?],
+        },)+)+]
+    };
+}
+
+/// Every function provided by the runtime to modules. Pointer-sized
+/// functions are listed without the pointer width suffix.
+pub const FUNCTIONS: &[Function] = functions! {
+    "sdk::context" {
+        "sender_username_length"() -> I64;
+        "sender_username"(Usize, Usize, I64) -> Usize;
+    }
+    "sdk::crypto" {
+        "ed25519_public_key"(Usize, Usize, Usize) -> I32;
+        "ed25519_sign"(Usize, Usize, Usize, Usize, Usize) -> I32;
+        "ed25519_verify"(Usize, Usize, Usize, Usize, Usize) -> I32;
+        "hmac_sha256"(Usize, Usize, Usize, Usize, Usize) -> I32;
+        "aes256_gcm_encrypt"(Usize, Usize, Usize, Usize, Usize, Usize, Usize, Usize) -> I32;
+        "aes256_gcm_decrypt"(Usize, Usize, Usize, Usize, Usize, Usize, Usize, Usize) -> I32;
+        "sha256"(Usize, Usize, Usize);
+        "sha512"(Usize, Usize, Usize);
+    }
+    "sdk::debug" {
+        "debug_str"(Usize, Usize);
+    }
+    "sdk::invoke" {
+        "invoke"(Usize, Usize, Usize, Usize) -> I64;
+        "invoke_error"() -> I32;
+        "response_is_error"(I64) -> I32;
+        "response_status_code"(I64) -> I32;
+        "response_data_length"(I64) -> I64;
+        "read_response_data"(I64, Usize, Usize) -> Usize;
+        "drop_response"(I64);
+    }
+    "sdk::io" {
+        "receive_request_data_id"() -> I64;
+        "request_data_length"(I64) -> I64;
+        "read_request_data"(I64, Usize, Usize) -> Usize;
+        "set_response_is_error"();
+        "set_response_status_code"(I32);
+        "add_response_header"(Usize, Usize, Usize, Usize);
+        "start_response_stream"();
+        "write_response_data"(Usize, Usize);
+        "request_meta_length"(I32) -> I64;
+        "request_meta"(I32, Usize, Usize) -> Usize;
+        "request_headers_count"() -> I64;
+        "request_header_name_length"(I64) -> I64;
+        "request_header_name"(I64, Usize, Usize) -> Usize;
+        "request_header_value_length"(I64) -> I64;
+        "request_header_value"(I64, Usize, Usize) -> Usize;
+    }
+    "sdk::kv" {
+        "get"(Usize, Usize) -> I64;
+        "value_length"(I64) -> I64;
+        "read_value"(I64, Usize, Usize) -> Usize;
+        "drop_value"(I64);
+        "put"(Usize, Usize, Usize, Usize);
+        "delete"(Usize, Usize) -> I32;
+        "compare_and_swap"(Usize, Usize, Usize, Usize, Usize, Usize) -> I32;
+        "list_keys"(Usize, Usize) -> I64;
+        "keys_count"(I64) -> I64;
+        "key_length"(I64, I64) -> I64;
+        "read_key"(I64, I64, Usize, Usize) -> Usize;
+        "drop_keys"(I64);
+    }
+    "sdk::log" {
+        "log"(I32, Usize, Usize, Usize, Usize, Usize, Usize);
+    }
+    "sdk::net" {
+        "send_request"(Usize) -> I64;
+        "open_request"(Usize) -> I64;
+        "write_request_body"(I64, Usize, Usize);
+        "finish_request"(I64) -> I64;
+        "drop_request"(I64);
+        "request_error"() -> I32;
+        "response_status_code"(I64) -> I32;
+        "response_headers_count"(I64) -> I64;
+        "response_header_name_length"(I64, I64) -> I64;
+        "response_header_name"(I64, I64, Usize, Usize) -> Usize;
+        "response_header_value_length"(I64, I64) -> I64;
+        "response_header_value"(I64, I64, Usize, Usize) -> Usize;
+        "response_data_length"(I64) -> I64;
+        "response_data"(I64, Usize, Usize) -> Usize;
+        "drop_some_response_data"(I64, I64);
+        "drop_response"(I64);
+    }
+    "sdk::panic" {
+        "panic"(Usize, Usize);
+    }
+    "sdk::random" {
+        "fill"(Usize, Usize);
+    }
+    "sdk::time" {
+        "wall_clock"() -> I64;
+        "monotonic"() -> I64;
+    }
+    "sdk::vault" {
+        "fetch_secret"(Usize, Usize) -> I64;
+        "secret_length"(I64) -> I64;
+        "read_secret"(I64, Usize, Usize) -> Usize;
+        "drop_secret"(I64);
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, fs::read_to_string, path::Path};
+
+    use super::{Function, FUNCTIONS, VERSION_SECTION};
+
+    #[test]
+    fn guest_declares_abi_version() {
+        let source: String =
+            read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("../lambda-sdk/src/lib.rs"))
+                .unwrap();
+
+        assert!(
+            source.contains(&format!(r#"link_section = "{VERSION_SECTION}""#)),
+            "Guest doesn't declare ABI version in the expected section!"
+        );
+    }
+
+    #[test]
+    fn functions_are_unique() {
+        let mut functions: BTreeMap<(&str, &str), &Function> = BTreeMap::new();
+
+        for function in FUNCTIONS {
+            assert!(
+                functions
+                    .insert((function.module, function.name), function)
+                    .is_none(),
+                "{function:?} is defined more than once!"
+            );
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::{
+    fs::{create_dir_all, read, remove_file, rename, write},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process,
+};
+
+use anyhow::{Context as _, Error as AnyError, Result as AnyResult};
+use data_encoding::HEXLOWER;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+use wasmtime::Engine;
+
+use crate::{StaticModuleVerificationError, VerifiedModule};
+
+/// Directory of compiled modules, keyed by the hash of a module's binary and
+/// the hash of the engine's configuration fingerprint, so engines configured
+/// differently can share the directory. Entries which are nonetheless
+/// incompatible with the engine are rejected when loading and replaced. Entries
+/// are loaded as native code, thus the directory must only be writable by the
+/// runtime.
+#[derive(Debug, Clone)]
+pub struct ModuleCache {
+    directory: PathBuf,
+    engine_key: String,
+}
+
+impl ModuleCache {
+    /// Creates cache in provided directory, creating it when necessary.
+    /// Fingerprint has to change whenever the engine's configuration does.
+    /// # Errors
+    /// Error will occur when directory can't be created.
+    pub fn new(directory: PathBuf, engine_fingerprint: &str) -> AnyResult<Self> {
+        create_dir_all(&directory).with_context(|| {
+            format!(
+                "Failed to create module cache directory {}!",
+                directory.display()
+            )
+        })?;
+
+        Ok(Self {
+            directory,
+            engine_key: HEXLOWER.encode(&Sha256::digest(engine_fingerprint)),
+        })
+    }
+
+    /// Loads module compiled from provided binary, compiling and storing it
+    /// when no entry exists. Entries failing to load are removed and replaced.
+    /// Failure to store compiled module is only logged.
+    /// # Errors
+    /// Error will occur when module fails to compile or verification fails.
+    pub fn load_or_compile(
+        &self,
+        engine: &Engine,
+        binary: &[u8],
+    ) -> Result<VerifiedModule, StaticModuleVerificationError> {
+        let path: PathBuf = self.entry_path(binary);
+
+        match read(&path) {
+            Ok(entry) => match VerifiedModule::deserialize(engine, &entry) {
+                Ok(module) => return Ok(module),
+                Err(error) => {
+                    let error: AnyError = error.into();
+
+                    warn!(
+                        path = %path.display(),
+                        "Discarding invalid compiled module cache entry! Error: {error:#}",
+                    );
+
+                    if let Err(error) = remove_file(&path) {
+                        warn!(
+                            path = %path.display(),
+                            "Failed to remove invalid compiled module cache entry! Error: {error}",
+                        );
+                    }
+                }
+            },
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => {
+                warn!(
+                    path = %path.display(),
+                    "Failed to read compiled module cache entry! Error: {error}",
+                );
+            }
+        }
+
+        let module: VerifiedModule = VerifiedModule::new(engine, binary)?;
+
+        if let Err(error) = Self::store(&module, &path) {
+            warn!(
+                path = %path.display(),
+                "Failed to store compiled module in cache! Error: {error:#}",
+            );
+        }
+
+        Ok(module)
+    }
+
+    fn entry_path(&self, binary: &[u8]) -> PathBuf {
+        let binary_key: String = HEXLOWER.encode(&Sha256::digest(binary));
+
+        self.directory
+            .join(format!("{binary_key}.{}.cwasm", self.engine_key))
+    }
+
+    /// Writes entry to a temporary file first, so concurrently loading
+    /// processes never observe partially written entries.
+    fn store(module: &VerifiedModule, path: &Path) -> AnyResult<()> {
+        let entry: Vec<u8> = module.serialize()?;
+
+        let temporary_path: PathBuf = path.with_extension(format!("{}.tmp", process::id()));
+
+        if let Err(error) =
+            write(&temporary_path, entry).and_then(|()| rename(&temporary_path, path))
+        {
+            // Removal is best effort, as the file might not exist.
+            drop(remove_file(&temporary_path));
+
+            return Err(error.into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env::temp_dir,
+        fs::{read, remove_dir_all, write},
+        path::PathBuf,
+        process,
+    };
+
+    use anyhow::Result as AnyResult;
+    use wasmtime::{Config, Engine};
+
+    use crate::{tests::module_binary, StaticModuleVerificationError, VerifiedModule};
+
+    use super::ModuleCache;
+
+    #[test]
+    fn corrupt_entries_are_discarded_and_recompiled() -> AnyResult<()> {
+        let directory: PathBuf = temp_dir().join(format!("lambda-rt-cache-test.{}", process::id()));
+
+        let cache: ModuleCache = ModuleCache::new(directory.clone(), "default")?;
+
+        let engine: Engine = Engine::default();
+
+        let binary: Vec<u8> =
+            module_binary(r#"(module (memory (export "memory") 1) (func (export "entry")))"#);
+
+        cache.load_or_compile(&engine, &binary)?;
+
+        let path: PathBuf = cache.entry_path(&binary);
+
+        let mut entry: Vec<u8> = read(&path)?;
+
+        assert!(VerifiedModule::deserialize(&engine, &entry).is_ok());
+
+        if let Some(byte) = entry.last_mut() {
+            *byte ^= 0xFF;
+        }
+
+        write(&path, &entry)?;
+
+        let module: Result<VerifiedModule, StaticModuleVerificationError> =
+            cache.load_or_compile(&engine, &binary);
+
+        let stored: AnyResult<Vec<u8>> = read(&path).map_err(Into::into);
+
+        remove_dir_all(&directory)?;
+
+        assert!(module?.has_default_entry_point());
+        assert!(VerifiedModule::deserialize(&engine, &stored?).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn differently_configured_engines_keep_separate_entries() -> AnyResult<()> {
+        let directory: PathBuf =
+            temp_dir().join(format!("lambda-rt-cache-engines-test.{}", process::id()));
+
+        let epochs_engine: Engine = Engine::new(Config::new().epoch_interruption(true))?;
+
+        let fuel_engine: Engine = Engine::new(Config::new().consume_fuel(true))?;
+
+        let epochs_cache: ModuleCache = ModuleCache::new(directory.clone(), "epochs")?;
+
+        let fuel_cache: ModuleCache = ModuleCache::new(directory.clone(), "fuel")?;
+
+        let binary: Vec<u8> =
+            module_binary(r#"(module (memory (export "memory") 1) (func (export "entry")))"#);
+
+        epochs_cache.load_or_compile(&epochs_engine, &binary)?;
+        fuel_cache.load_or_compile(&fuel_engine, &binary)?;
+
+        let epochs_path: PathBuf = epochs_cache.entry_path(&binary);
+
+        let fuel_path: PathBuf = fuel_cache.entry_path(&binary);
+
+        let epochs_entry: AnyResult<Vec<u8>> = read(&epochs_path).map_err(Into::into);
+
+        let fuel_entry: AnyResult<Vec<u8>> = read(&fuel_path).map_err(Into::into);
+
+        remove_dir_all(&directory)?;
+
+        let (epochs_entry, fuel_entry): (Vec<u8>, Vec<u8>) = (epochs_entry?, fuel_entry?);
+
+        assert_ne!(epochs_path, fuel_path);
+        assert!(VerifiedModule::deserialize(&epochs_engine, &epochs_entry).is_ok());
+        assert!(VerifiedModule::deserialize(&fuel_engine, &fuel_entry).is_ok());
+
+        // Sharing an entry would have each engine discard the other's.
+        assert!(VerifiedModule::deserialize(&fuel_engine, &epochs_entry).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,425 @@
+use std::str::FromStr;
+
+use aes_gcm::{
+    aead::{Aead, Payload},
+    Aes256Gcm, KeyInit, Nonce,
+};
+use anyhow::{anyhow, Error as AnyError};
+use ed25519_dalek::{Signature, Signer, SigningKey};
+use hkdf::hmac::{Hmac, Mac};
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+pub const ED25519_PUBLIC_KEY_LENGTH: usize = 32;
+
+pub const ED25519_SIGNATURE_LENGTH: usize = 64;
+
+pub const HMAC_SHA256_LENGTH: usize = 32;
+
+pub const AES256_GCM_NONCE_LENGTH: usize = 12;
+
+/// Length of the authentication tag appended to ciphertexts.
+pub const AES256_GCM_TAG_LENGTH: usize = 16;
+
+/// Algorithm a vault key is bound to, which is the only one it can be used
+/// with.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum KeyAlgorithm {
+    /// Keys are stored as their 32-byte seed.
+    Ed25519,
+    HmacSha256,
+    /// Keys are 32 bytes long.
+    Aes256Gcm,
+}
+
+impl KeyAlgorithm {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Ed25519 => "ed25519",
+            Self::HmacSha256 => "hmac_sha256",
+            Self::Aes256Gcm => "aes256_gcm",
+        }
+    }
+}
+
+impl FromStr for KeyAlgorithm {
+    type Err = AnyError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        [Self::Ed25519, Self::HmacSha256, Self::Aes256Gcm]
+            .into_iter()
+            .find(|algorithm: &Self| algorithm.as_str() == name)
+            .ok_or_else(|| anyhow!(r#"Unknown key algorithm "{name}"!"#))
+    }
+}
+
+/// Key stored in the vault apart from secrets, which is only ever used by the
+/// host on modules' behalf.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VaultKey {
+    pub algorithm: KeyAlgorithm,
+    pub material: Zeroizing<Vec<u8>>,
+}
+
+impl VaultKey {
+    /// Returns key's material when it is bound to provided algorithm.
+    fn material_for(&self, algorithm: KeyAlgorithm) -> Result<&[u8], CryptoError> {
+        if self.algorithm == algorithm {
+            Ok(&self.material)
+        } else {
+            Err(CryptoError::InvalidKey)
+        }
+    }
+}
+
+/// Reason for a cryptographic operation on a vault key failing. Discriminants
+/// are reported to modules as error codes.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, thiserror::Error)]
+#[repr(u32)]
+pub enum CryptoError {
+    #[error("No key with such identifier exists!")]
+    NoSuchKey = 1,
+    #[error("Key is not valid for the requested algorithm!")]
+    InvalidKey = 2,
+    #[error("Signature or ciphertext failed verification!")]
+    VerificationFailed = 3,
+    #[error("Data exceeds the maximum length supported by the algorithm!")]
+    DataTooLong = 4,
+}
+
+fn ed25519_signing_key(key: &VaultKey) -> Result<SigningKey, CryptoError> {
+    let seed: Zeroizing<[u8; 32]> = Zeroizing::new(
+        key.material_for(KeyAlgorithm::Ed25519)?
+            .try_into()
+            .map_err(|_| CryptoError::InvalidKey)?,
+    );
+
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+pub(crate) fn ed25519_public_key(
+    key: &VaultKey,
+) -> Result<[u8; ED25519_PUBLIC_KEY_LENGTH], CryptoError> {
+    ed25519_signing_key(key).map(|signing_key: SigningKey| signing_key.verifying_key().to_bytes())
+}
+
+pub(crate) fn ed25519_sign(
+    key: &VaultKey,
+    message: &[u8],
+) -> Result<[u8; ED25519_SIGNATURE_LENGTH], CryptoError> {
+    ed25519_signing_key(key).map(|signing_key: SigningKey| signing_key.sign(message).to_bytes())
+}
+
+/// Verifies signature against the public key of provided key.
+pub(crate) fn ed25519_verify(
+    key: &VaultKey,
+    message: &[u8],
+    signature: &[u8; ED25519_SIGNATURE_LENGTH],
+) -> Result<(), CryptoError> {
+    ed25519_signing_key(key)?
+        .verifying_key()
+        .verify_strict(message, &Signature::from_bytes(signature))
+        .map_err(|_| CryptoError::VerificationFailed)
+}
+
+pub(crate) fn hmac_sha256(
+    key: &VaultKey,
+    message: &[u8],
+) -> Result<[u8; HMAC_SHA256_LENGTH], CryptoError> {
+    let mut mac: Hmac<Sha256> =
+        <Hmac<Sha256> as Mac>::new_from_slice(key.material_for(KeyAlgorithm::HmacSha256)?)
+            .map_err(|_| CryptoError::InvalidKey)?;
+
+    mac.update(message);
+
+    Ok(mac.finalize().into_bytes().into())
+}
+
+fn aes256_gcm(key: &VaultKey) -> Result<Aes256Gcm, CryptoError> {
+    Aes256Gcm::new_from_slice(key.material_for(KeyAlgorithm::Aes256Gcm)?)
+        .map_err(|_| CryptoError::InvalidKey)
+}
+
+/// Returns ciphertext followed by the authentication tag.
+pub(crate) fn aes256_gcm_encrypt(
+    key: &VaultKey,
+    nonce: &[u8; AES256_GCM_NONCE_LENGTH],
+    associated_data: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    aes256_gcm(key)?
+        .encrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: plaintext,
+                aad: associated_data,
+            },
+        )
+        .map_err(|_| CryptoError::DataTooLong)
+}
+
+/// Expects ciphertext followed by the authentication tag.
+pub(crate) fn aes256_gcm_decrypt(
+    key: &VaultKey,
+    nonce: &[u8; AES256_GCM_NONCE_LENGTH],
+    associated_data: &[u8],
+    ciphertext: &[u8],
+) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    aes256_gcm(key)?
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: associated_data,
+            },
+        )
+        .map(Zeroizing::new)
+        .map_err(|_| CryptoError::VerificationFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use data_encoding::HEXLOWER;
+    use zeroize::Zeroizing;
+
+    use super::{CryptoError, KeyAlgorithm, VaultKey};
+
+    fn hex(data: &str) -> Vec<u8> {
+        HEXLOWER.decode(data.as_bytes()).unwrap()
+    }
+
+    fn key(algorithm: KeyAlgorithm, material: &str) -> VaultKey {
+        VaultKey {
+            algorithm,
+            material: Zeroizing::new(hex(material)),
+        }
+    }
+
+    /// Test vectors of RFC 8032, section 7.1.
+    const ED25519_VECTORS: [(&str, &str, &str, &str); 2] = [
+        (
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        ),
+        (
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        ),
+    ];
+
+    #[test]
+    fn ed25519_matches_rfc_8032() {
+        for (seed, public_key, message, signature) in ED25519_VECTORS {
+            let key: VaultKey = key(KeyAlgorithm::Ed25519, seed);
+
+            let message: Vec<u8> = hex(message);
+
+            assert_eq!(
+                super::ed25519_public_key(&key).unwrap().to_vec(),
+                hex(public_key)
+            );
+
+            let produced: [u8; 64] = super::ed25519_sign(&key, &message).unwrap();
+
+            assert_eq!(produced.to_vec(), hex(signature));
+
+            assert_eq!(super::ed25519_verify(&key, &message, &produced), Ok(()));
+        }
+    }
+
+    #[test]
+    fn ed25519_rejects_tampered_signatures() {
+        let (seed, _, message, _) = ED25519_VECTORS[1];
+
+        let key: VaultKey = key(KeyAlgorithm::Ed25519, seed);
+
+        let mut signature: [u8; 64] = super::ed25519_sign(&key, &hex(message)).unwrap();
+
+        assert_eq!(
+            super::ed25519_verify(&key, b"other message", &signature),
+            Err(CryptoError::VerificationFailed)
+        );
+
+        signature[0] ^= 1;
+
+        assert_eq!(
+            super::ed25519_verify(&key, &hex(message), &signature),
+            Err(CryptoError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn ed25519_requires_32_byte_seed() {
+        assert_eq!(
+            super::ed25519_sign(&key(KeyAlgorithm::Ed25519, "00"), b""),
+            Err(CryptoError::InvalidKey)
+        );
+    }
+
+    /// Test cases 1 and 2 of RFC 4231, section 4.
+    #[test]
+    fn hmac_sha256_matches_rfc_4231() {
+        for (key_material, data, mac) in [
+            (
+                "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+                b"Hi There".as_slice(),
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            ),
+            (
+                "4a656665",
+                b"what do ya want for nothing?".as_slice(),
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            ),
+        ] {
+            assert_eq!(
+                super::hmac_sha256(&key(KeyAlgorithm::HmacSha256, key_material), data)
+                    .unwrap()
+                    .to_vec(),
+                hex(mac)
+            );
+        }
+    }
+
+    /// Test cases 13, 14 and 16 of the GCM specification submitted to NIST.
+    const AES256_GCM_VECTORS: [(&str, &str, &str, &str, &str); 3] = [
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "000000000000000000000000",
+            "",
+            "",
+            "530f8afbc74536b9a963b4f1c4cb738b",
+        ),
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "000000000000000000000000",
+            "",
+            "00000000000000000000000000000000",
+            "cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919",
+        ),
+        (
+            "feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308",
+            "cafebabefacedbaddecaf888",
+            "feedfacedeadbeeffeedfacedeadbeefabaddad2",
+            "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+            "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f66276fc6ece0f4e1768cddf8853bb2d551b",
+        ),
+    ];
+
+    #[test]
+    fn aes256_gcm_matches_nist_vectors() {
+        for (key_material, nonce, associated_data, plaintext, ciphertext) in AES256_GCM_VECTORS {
+            let key: VaultKey = key(KeyAlgorithm::Aes256Gcm, key_material);
+
+            let nonce: [u8; 12] = hex(nonce).try_into().unwrap();
+
+            let associated_data: Vec<u8> = hex(associated_data);
+
+            assert_eq!(
+                super::aes256_gcm_encrypt(&key, &nonce, &associated_data, &hex(plaintext)),
+                Ok(hex(ciphertext))
+            );
+
+            assert_eq!(
+                super::aes256_gcm_decrypt(&key, &nonce, &associated_data, &hex(ciphertext))
+                    .unwrap()
+                    .to_vec(),
+                hex(plaintext)
+            );
+        }
+    }
+
+    #[test]
+    fn aes256_gcm_rejects_tampering() {
+        let (key_material, nonce, associated_data, _, ciphertext) = AES256_GCM_VECTORS[2];
+
+        let key: VaultKey = key(KeyAlgorithm::Aes256Gcm, key_material);
+
+        let nonce: [u8; 12] = hex(nonce).try_into().unwrap();
+
+        let associated_data: Vec<u8> = hex(associated_data);
+
+        let ciphertext: Vec<u8> = hex(ciphertext);
+
+        let decrypt = |nonce: &[u8; 12], associated_data: &[u8], ciphertext: &[u8]| {
+            super::aes256_gcm_decrypt(&key, nonce, associated_data, ciphertext)
+                .map(|plaintext: Zeroizing<Vec<u8>>| plaintext.to_vec())
+        };
+
+        for index in [0, ciphertext.len() - 1] {
+            let mut tampered: Vec<u8> = ciphertext.clone();
+
+            tampered[index] ^= 1;
+
+            assert_eq!(
+                decrypt(&nonce, &associated_data, &tampered),
+                Err(CryptoError::VerificationFailed)
+            );
+        }
+
+        let mut tampered_nonce: [u8; 12] = nonce;
+
+        tampered_nonce[0] ^= 1;
+
+        assert_eq!(
+            decrypt(&tampered_nonce, &associated_data, &ciphertext),
+            Err(CryptoError::VerificationFailed)
+        );
+
+        assert_eq!(
+            decrypt(&nonce, b"", &ciphertext),
+            Err(CryptoError::VerificationFailed)
+        );
+
+        assert_eq!(
+            decrypt(&nonce, &associated_data, &ciphertext[..15]),
+            Err(CryptoError::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn keys_are_bound_to_their_algorithm() {
+        let material: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+        let ed25519: VaultKey = key(KeyAlgorithm::Ed25519, material);
+
+        let hmac_sha256: VaultKey = key(KeyAlgorithm::HmacSha256, material);
+
+        let aes256_gcm: VaultKey = key(KeyAlgorithm::Aes256Gcm, material);
+
+        assert_eq!(
+            super::hmac_sha256(&ed25519, b""),
+            Err(CryptoError::InvalidKey)
+        );
+
+        assert_eq!(
+            super::aes256_gcm_encrypt(&hmac_sha256, &[0; 12], b"", b""),
+            Err(CryptoError::InvalidKey)
+        );
+
+        assert_eq!(
+            super::ed25519_sign(&aes256_gcm, b""),
+            Err(CryptoError::InvalidKey)
+        );
+    }
+
+    #[test]
+    fn key_algorithm_names_round_trip() {
+        for algorithm in [
+            KeyAlgorithm::Ed25519,
+            KeyAlgorithm::HmacSha256,
+            KeyAlgorithm::Aes256Gcm,
+        ] {
+            assert_eq!(
+                algorithm.as_str().parse::<KeyAlgorithm>().unwrap(),
+                algorithm
+            );
+        }
+
+        assert!("sha256".parse::<KeyAlgorithm>().is_err());
+    }
+}
@@ -0,0 +1,273 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use reqwest::{Method, Url};
+use tokio::net::lookup_host;
+
+/// Outbound traffic policy of a module.
+///
+/// Lists set to [`None`] don't restrict the respective property. Host patterns
+/// are either exact host names, `*.` prefixed wildcards matching any subdomain,
+/// or a single `*` matching any host. Loopback, link-local, private and other
+/// non-globally routable destinations are denied unless explicitly allowed.
+/// Redirects are not followed, thus every hop is checked as a new request.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EgressPolicy {
+    pub allowed_hosts: Option<Vec<String>>,
+    pub allowed_schemes: Option<Vec<String>>,
+    pub allowed_methods: Option<Vec<String>>,
+    pub allowed_ports: Option<Vec<u16>>,
+    pub allow_private_destinations: bool,
+}
+
+impl EgressPolicy {
+    /// Checks whether request with provided method to provided URL is allowed.
+    /// Host names are resolved to verify they don't point to a private
+    /// destination, in which case the addresses they resolved to are returned,
+    /// as the request has to be sent to them only.
+    /// # Errors
+    /// Error will occur when request is denied or its host name can't be
+    /// resolved.
+    pub async fn check(
+        &self,
+        method: &Method,
+        url: &Url,
+    ) -> Result<Option<PinnedAddresses>, EgressDenial> {
+        if !allowed_by(self.allowed_schemes.as_deref(), |scheme: &String| {
+            scheme.eq_ignore_ascii_case(url.scheme())
+        }) {
+            return Err(EgressDenial::Scheme);
+        }
+
+        if !allowed_by(self.allowed_methods.as_deref(), |allowed: &String| {
+            allowed.eq_ignore_ascii_case(method.as_str())
+        }) {
+            return Err(EgressDenial::Method);
+        }
+
+        let Some(host) = url.host_str() else {
+            return Err(EgressDenial::Host);
+        };
+
+        if !allowed_by(self.allowed_hosts.as_deref(), |pattern: &String| {
+            host_matches(pattern, host)
+        }) {
+            return Err(EgressDenial::Host);
+        }
+
+        let Some(port) = url.port_or_known_default() else {
+            return Err(EgressDenial::Port);
+        };
+
+        if !allowed_by(self.allowed_ports.as_deref(), |allowed: &u16| {
+            *allowed == port
+        }) {
+            return Err(EgressDenial::Port);
+        }
+
+        if self.allow_private_destinations {
+            return Ok(None);
+        }
+
+        let host: &str = host.trim_start_matches('[').trim_end_matches(']');
+
+        if let Ok(address) = host.parse::<IpAddr>() {
+            return if is_public_address(address) {
+                Ok(None)
+            } else {
+                Err(EgressDenial::PrivateDestination)
+            };
+        }
+
+        if host.eq_ignore_ascii_case("localhost")
+            || host.to_ascii_lowercase().ends_with(".localhost")
+        {
+            return Err(EgressDenial::PrivateDestination);
+        }
+
+        let addresses: Vec<SocketAddr> = lookup_host((host, port))
+            .await
+            .map_err(|_| EgressDenial::UnresolvableHost)?
+            .collect();
+
+        if addresses.is_empty() {
+            Err(EgressDenial::UnresolvableHost)
+        } else if addresses
+            .iter()
+            .all(|address: &SocketAddr| is_public_address(address.ip()))
+        {
+            Ok(Some(PinnedAddresses {
+                host: String::from(host),
+                addresses,
+            }))
+        } else {
+            Err(EgressDenial::PrivateDestination)
+        }
+    }
+}
+
+impl Default for EgressPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_hosts: None,
+            allowed_schemes: Some(vec![String::from("http"), String::from("https")]),
+            allowed_methods: None,
+            allowed_ports: None,
+            allow_private_destinations: false,
+        }
+    }
+}
+
+/// Addresses a host name resolved to while checking a request against an
+/// egress policy. The request has to be sent to these addresses only, as
+/// resolving the name again might yield different, unchecked ones.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PinnedAddresses {
+    pub host: String,
+    pub addresses: Vec<SocketAddr>,
+}
+
+/// Reason for refusing to send an outbound request. Discriminants are reported
+/// to modules as error codes.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, thiserror::Error)]
+#[repr(u32)]
+pub enum EgressDenial {
+    #[error("Destination host is not allowed by egress policy!")]
+    Host = 1,
+    #[error("URL scheme is not allowed by egress policy!")]
+    Scheme = 2,
+    #[error("Request method is not allowed by egress policy!")]
+    Method = 3,
+    #[error("Destination port is not allowed by egress policy!")]
+    Port = 4,
+    #[error("Private and loopback destinations are not allowed by egress policy!")]
+    PrivateDestination = 5,
+    /// Not a policy denial, but refused the same way before sending.
+    #[error("Too many responses are kept open at the same time!")]
+    TooManyResponses = 6,
+    #[error("Destination host name couldn't be resolved!")]
+    UnresolvableHost = 7,
+}
+
+fn allowed_by<T, F>(allowed: Option<&[T]>, predicate: F) -> bool
+where
+    F: FnMut(&T) -> bool,
+{
+    match allowed {
+        None => true,
+        Some(allowed) => allowed.iter().any(predicate),
+    }
+}
+
+fn host_matches(pattern: &str, host: &str) -> bool {
+    if pattern == "*" {
+        true
+    } else if let Some(domain) = pattern.strip_prefix("*.") {
+        host.len() > domain.len() + 1
+            && host[host.len() - domain.len()..].eq_ignore_ascii_case(domain)
+            && host.as_bytes()[host.len() - domain.len() - 1] == b'.'
+    } else {
+        pattern.eq_ignore_ascii_case(host)
+    }
+}
+
+fn is_public_address(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => is_public_ipv4_address(address),
+        // Covers both IPv4-mapped and deprecated IPv4-compatible addresses.
+        IpAddr::V6(address) => address
+            .to_ipv4()
+            .map_or_else(|| is_public_ipv6_address(address), is_public_ipv4_address),
+    }
+}
+
+fn is_public_ipv4_address(address: Ipv4Addr) -> bool {
+    let [first, second, third, _]: [u8; 4] = address.octets();
+
+    !(address.is_private()
+        || address.is_loopback()
+        || address.is_link_local()
+        || address.is_unspecified()
+        || address.is_broadcast()
+        || address.is_documentation()
+        || address.is_multicast()
+        // Shared address space (RFC 6598).
+        || (first == 100 && (second & 0b1100_0000) == 64)
+        // IETF protocol assignments (RFC 6890).
+        || (first == 192 && second == 0 && third == 0)
+        // Benchmarking (RFC 2544).
+        || (first == 198 && (second & 0b1111_1110) == 18)
+        // "This" network and reserved for future use ranges.
+        || first == 0
+        || first >= 240)
+}
+
+fn is_public_ipv6_address(address: Ipv6Addr) -> bool {
+    let [first_segment, second_segment, ..]: [u16; 8] = address.segments();
+
+    !(address.is_loopback()
+        || address.is_unspecified()
+        || address.is_multicast()
+        // Unique local addresses.
+        || (first_segment & 0xfe00) == 0xfc00
+        // Link-local unicast addresses.
+        || (first_segment & 0xffc0) == 0xfe80
+        // NAT64 (RFC 6052 and RFC 8215), which embed IPv4 addresses.
+        || (first_segment == 0x64 && second_segment == 0xff9b)
+        // 6to4 (RFC 3056), which embeds IPv4 addresses.
+        || first_segment == 0x2002
+        // Documentation (RFC 3849).
+        || (first_segment == 0x2001 && second_segment == 0xdb8))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    #[test]
+    fn non_public_addresses_are_denied() {
+        for address in [
+            "10.0.0.1",
+            "100.64.0.1",
+            "127.0.0.1",
+            "169.254.169.254",
+            "172.16.0.1",
+            "192.0.0.8",
+            "192.168.1.1",
+            "198.18.0.1",
+            "198.19.255.255",
+            "240.0.0.1",
+            "::",
+            "::1",
+            "::127.0.0.1",
+            "::ffff:10.0.0.1",
+            "::ffff:169.254.169.254",
+            "64:ff9b::a00:1",
+            "64:ff9b:1::a00:1",
+            "2002:a00:1::",
+            "2001:db8::1",
+            "fc00::1",
+            "fe80::1",
+        ] {
+            assert!(
+                !super::is_public_address(address.parse::<IpAddr>().unwrap()),
+                "{address} is considered public!",
+            );
+        }
+    }
+
+    #[test]
+    fn public_addresses_are_allowed() {
+        for address in [
+            "1.1.1.1",
+            "93.184.216.34",
+            "198.20.0.1",
+            "::ffff:1.1.1.1",
+            "2606:4700:4700::1111",
+        ] {
+            assert!(
+                super::is_public_address(address.parse::<IpAddr>().unwrap()),
+                "{address} is not considered public!",
+            );
+        }
+    }
+}
@@ -0,0 +1,35 @@
+/// Invocation of a module by another module.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Invocation {
+    pub module_id: String,
+    pub data: Vec<u8>,
+    /// Username of the invoking request's sender, propagated to the invoked
+    /// module.
+    pub sender: Option<String>,
+    /// Identifiers of the modules which led to this invocation, outermost
+    /// first, ending with the invoking module.
+    pub call_chain: Vec<String>,
+}
+
+/// Reason for failing an invocation. Discriminants are reported to modules as
+/// error codes.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, thiserror::Error)]
+#[repr(u32)]
+pub enum InvokeError {
+    #[error("No module with such identifier is configured!")]
+    UnknownModule = 1,
+    #[error("Invoked module is already part of the call chain!")]
+    CycleDetected = 2,
+    #[error("Call chain exceeds maximum call depth!")]
+    CallDepthExceeded = 3,
+    #[error("Invoked module failed to produce a response!")]
+    Failed = 4,
+    #[error("Invoked module exhausted its CPU budget!")]
+    CpuBudgetExhausted = 5,
+    #[error("Invoked module's response exceeds maximum length!")]
+    ResponseTooLarge = 6,
+    #[error("Invoking request has no sender to propagate!")]
+    NoSender = 7,
+    #[error("Invoked module doesn't export default entry point!")]
+    NoDefaultEntryPoint = 8,
+}
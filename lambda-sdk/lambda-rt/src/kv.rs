@@ -0,0 +1,92 @@
+use std::{
+    collections::BTreeMap,
+    future::{ready, Ready},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use anyhow::Result as AnyResult;
+
+use crate::KvProvider;
+
+type Entries = BTreeMap<(String, Vec<u8>), Vec<u8>>;
+
+/// In-memory key-value provider. Clones share the same entries, which are
+/// lost once the last clone is dropped.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryKv {
+    entries: Arc<Mutex<Entries>>,
+}
+
+impl MemoryKv {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entries(&self) -> MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl KvProvider for MemoryKv {
+    type Result<'r, T>
+        = Ready<AnyResult<T>>
+    where
+        T: Send + 'r;
+
+    fn get(&mut self, namespace: String, key: Vec<u8>) -> Self::Result<'_, Option<Vec<u8>>> {
+        ready(Ok(self.entries().get(&(namespace, key)).cloned()))
+    }
+
+    fn put(&mut self, namespace: String, key: Vec<u8>, value: Vec<u8>) -> Self::Result<'_, ()> {
+        self.entries().insert((namespace, key), value);
+
+        ready(Ok(()))
+    }
+
+    fn delete(&mut self, namespace: String, key: Vec<u8>) -> Self::Result<'_, bool> {
+        ready(Ok(self.entries().remove(&(namespace, key)).is_some()))
+    }
+
+    fn compare_and_swap(
+        &mut self,
+        namespace: String,
+        key: Vec<u8>,
+        expected: Option<Vec<u8>>,
+        new: Vec<u8>,
+    ) -> Self::Result<'_, bool> {
+        let mut entries: MutexGuard<'_, Entries> = self.entries();
+
+        let key: (String, Vec<u8>) = (namespace, key);
+
+        ready(Ok(if entries.get(&key) == expected.as_ref() {
+            entries.insert(key, new);
+
+            true
+        } else {
+            false
+        }))
+    }
+
+    fn list_keys(
+        &mut self,
+        namespace: String,
+        prefix: Vec<u8>,
+        limit: usize,
+    ) -> Self::Result<'_, Vec<Vec<u8>>> {
+        ready(Ok(self
+            .entries()
+            .range((namespace.clone(), prefix.clone())..)
+            .map(
+                |((entry_namespace, key), _): (&(String, Vec<u8>), &Vec<u8>)| {
+                    (entry_namespace, key)
+                },
+            )
+            .take_while(|&(entry_namespace, key): &(&String, &Vec<u8>)| {
+                *entry_namespace == namespace && key.starts_with(&prefix)
+            })
+            .take(limit)
+            .map(|(_, key): (&String, &Vec<u8>)| key.clone())
+            .collect()))
+    }
+}
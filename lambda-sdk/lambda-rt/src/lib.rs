@@ -1,20 +1,84 @@
 #![forbid(rust_2018_compatibility, deprecated_in_future)]
 #![deny(rust_2021_compatibility, unsafe_code, warnings, clippy::pedantic)]
 
-use std::{future::Future, mem::take, num::NonZeroU64};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fmt::{Debug, Formatter, Result as FmtResult},
+    future::Future,
+    mem::take,
+    num::NonZeroU64,
+    pin::Pin,
+    task::{Context as TaskContext, Poll},
+    time::Instant,
+};
 
-use anyhow::{anyhow, bail, Result as AnyResult};
-use wasmtime::{ExternType, Instance, Linker, Module, Store, TypedFunc};
+use anyhow::{anyhow, bail, Context as _, Error as AnyError, Result as AnyResult};
+use futures_core::Stream;
+use lambda_abi::{
+    find_import, Import as AbiImport, ValueType, ENTRY_EXPORT, HANDLER_EXPORT_PREFIX,
+};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Body as NetworkBody, Request as NetworkRequest,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{
+    spawn,
+    sync::{
+        mpsc::{channel as mpsc_channel, Receiver as MpscReceiver, Sender as MpscSender},
+        oneshot::Sender as OneshotSender,
+    },
+    task::JoinHandle,
+};
+use tracing::Level;
+use wasmparser::{BinaryReaderError, Parser, Payload};
+use wasmtime::{
+    CallHook, Engine, ExportType, ExternType, ImportType, Instance, Linker, Memory, Module, Store,
+    StoreLimits, StoreLimitsBuilder, Trap, TypedFunc, ValType,
+};
 use zeroize::Zeroizing;
 
-use self::sdk_rt::link_rt;
-
+use self::{
+    crypto::VaultKey,
+    egress::{EgressPolicy, PinnedAddresses},
+    invoke::{Invocation, InvokeError},
+    log::LogPolicy,
+    metrics::{ExecutionMetrics, MetricsKeeper},
+    record::{CallInputs, RecordConfig, Tape},
+    sdk_rt::link_rt,
+    wasi::{WasiConfig, WASI_MODULES},
+};
+#[cfg(feature = "wasi")]
+use self::{log::LogRecord, wasi::WasiState};
+
+pub mod cache;
+pub mod crypto;
+pub mod egress;
+pub mod invoke;
+pub mod kv;
+pub mod log;
+pub mod metrics;
+pub mod network;
+pub mod record;
 mod sdk_rt;
+pub mod wasi;
 
 #[derive(Debug, thiserror::Error)]
 #[error("Panicked!")]
 pub(crate) struct PanickedError;
 
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum ExecutionError {
+    #[error("Module exhausted its CPU budget!")]
+    CpuBudgetExhausted,
+    #[error(r#"Module doesn't export handler "{0}"!"#)]
+    UnknownHandler(String),
+    #[error("Module doesn't export default entry point!")]
+    NoDefaultEntryPoint,
+}
+
 pub trait User: Send {
     fn username(&self) -> &str;
 }
@@ -22,7 +86,74 @@ pub trait User: Send {
 pub trait VaultProvider: Send + Sync + 'static {
     type Result<'r>: Future<Output = AnyResult<Option<Zeroizing<Vec<u8>>>>> + Send + 'r;
 
+    type KeyResult<'r>: Future<Output = AnyResult<Option<VaultKey>>> + Send + 'r;
+
     fn fetch_secret(&mut self, identifier: String) -> Self::Result<'_>;
+
+    /// Fetches key which cryptographic operations are performed with on the
+    /// host. Keys must be stored apart from secrets, as [`Self::fetch_secret`]
+    /// would otherwise expose them to modules.
+    fn fetch_key(&mut self, identifier: String) -> Self::KeyResult<'_>;
+}
+
+/// Provider of modules' persistent key-value storage. Every operation is
+/// scoped to a namespace, which is distinct for each module, and providers
+/// must not let operations observe entries from other namespaces.
+pub trait KvProvider: Send + Sync + 'static {
+    type Result<'r, T>: Future<Output = AnyResult<T>> + Send + 'r
+    where
+        T: Send + 'r;
+
+    fn get(&mut self, namespace: String, key: Vec<u8>) -> Self::Result<'_, Option<Vec<u8>>>;
+
+    fn put(&mut self, namespace: String, key: Vec<u8>, value: Vec<u8>) -> Self::Result<'_, ()>;
+
+    /// Returns whether an entry was deleted.
+    fn delete(&mut self, namespace: String, key: Vec<u8>) -> Self::Result<'_, bool>;
+
+    /// Sets entry's value to `new` only if its current value equals
+    /// `expected`, with [`None`] expecting the entry not to exist. Returns
+    /// whether the value was set.
+    fn compare_and_swap(
+        &mut self,
+        namespace: String,
+        key: Vec<u8>,
+        expected: Option<Vec<u8>>,
+        new: Vec<u8>,
+    ) -> Self::Result<'_, bool>;
+
+    /// Returns up to `limit` keys starting with provided prefix, in ascending
+    /// order.
+    fn list_keys(
+        &mut self,
+        namespace: String,
+        prefix: Vec<u8>,
+        limit: usize,
+    ) -> Self::Result<'_, Vec<Vec<u8>>>;
+}
+
+/// Provider through which modules' outbound requests are sent. Providers are
+/// cloned when a request with a streamed body is sent in the background.
+pub trait NetworkProvider: Clone + Send + Sync + 'static {
+    type Result<'r>: Future<Output = AnyResult<NetworkResponse>> + Send + 'r;
+
+    /// Sends request, which has to connect only to the pinned addresses when
+    /// they are provided. Redirects must not be followed, as their targets
+    /// aren't checked against the module's egress policy.
+    fn send_request(
+        &mut self,
+        request: NetworkRequest,
+        pinned_addresses: Option<PinnedAddresses>,
+    ) -> Self::Result<'_>;
+}
+
+/// Provider through which modules invoke other modules. Errors denote
+/// failures of the provider itself, while failures of the invocation are
+/// reported to the invoking module.
+pub trait InvokeProvider: Send + Sync + 'static {
+    type Result<'r>: Future<Output = AnyResult<Result<Response, InvokeError>>> + Send + 'r;
+
+    fn invoke(&mut self, invocation: Invocation) -> Self::Result<'_>;
 }
 
 pub trait Context: Send + 'static {
@@ -30,15 +161,27 @@ pub trait Context: Send + 'static {
 
     type Vault: VaultProvider;
 
+    type Network: NetworkProvider;
+
+    type Kv: KvProvider;
+
+    type Invoker: InvokeProvider;
+
     type User: User;
 
-    fn with_vault_and_context(vault: Self::Vault, context: Self::ConstructorContext) -> Self
+    fn with_providers_and_context(
+        vault: Self::Vault,
+        network: Self::Network,
+        kv: Self::Kv,
+        invoker: Self::Invoker,
+        context: Self::ConstructorContext,
+    ) -> Self
     where
         Self: Sized;
 
-    fn sdk(&self) -> &SdkEnv<Self::Vault>;
+    fn sdk(&self) -> &SdkEnv<Self::Vault, Self::Network, Self::Kv, Self::Invoker>;
 
-    fn sdk_mut(&mut self) -> &mut SdkEnv<Self::Vault>;
+    fn sdk_mut(&mut self) -> &mut SdkEnv<Self::Vault, Self::Network, Self::Kv, Self::Invoker>;
 
     fn sender(&self) -> Option<&Self::User>;
 
@@ -54,6 +197,9 @@ where
 {
     linker: Linker<Ctx>,
     vault: Ctx::Vault,
+    network: Ctx::Network,
+    kv: Ctx::Kv,
+    invoker: Ctx::Invoker,
 }
 
 impl<Ctx> LinkerWithSdk<Ctx>
@@ -64,10 +210,22 @@ where
     /// sets cross-module call handler.
     /// # Errors
     /// Error may occur when linking SDK to [`wasmtime`]'s [`Linker`].
-    pub fn new(mut linker: Linker<Ctx>, vault: Ctx::Vault) -> AnyResult<Self> {
+    pub fn new(
+        mut linker: Linker<Ctx>,
+        vault: Ctx::Vault,
+        network: Ctx::Network,
+        kv: Ctx::Kv,
+        invoker: Ctx::Invoker,
+    ) -> AnyResult<Self> {
         link_rt(&mut linker)?;
 
-        Ok(Self { linker, vault })
+        Ok(Self {
+            linker,
+            vault,
+            network,
+            kv,
+            invoker,
+        })
     }
 }
 
@@ -89,6 +247,90 @@ where
     }
 }
 
+/// Epoch deadline used when no deadline is requested. Far enough in the future
+/// to never be reached, while leaving headroom for the engine's current epoch.
+const UNLIMITED_EPOCH_DEADLINE: u64 = u64::MAX >> 1;
+
+/// CPU budget granted to a single execution of a module's entry point.
+///
+/// Fuel budgets require the engine to be configured with fuel consumption,
+/// while epoch deadlines require epoch interruption to be enabled and the
+/// engine's epoch to be incremented periodically by the host.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum CpuBudget {
+    #[default]
+    Unlimited,
+    Fuel(NonZeroU64),
+    EpochDeadline(NonZeroU64),
+}
+
+/// Resource limits applied to each instance through a [`wasmtime`] resource
+/// limiter. Unset limits fall back to [`wasmtime`]'s defaults.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct InstanceLimits {
+    pub max_memory_bytes: Option<usize>,
+    pub max_table_elements: Option<u32>,
+    pub max_instances: Option<usize>,
+}
+
+impl From<InstanceLimits> for StoreLimits {
+    fn from(limits: InstanceLimits) -> Self {
+        let mut builder: StoreLimitsBuilder = StoreLimitsBuilder::new();
+
+        if let Some(max_memory_bytes) = limits.max_memory_bytes {
+            builder = builder.memory_size(max_memory_bytes);
+        }
+
+        if let Some(max_table_elements) = limits.max_table_elements {
+            builder = builder.table_elements(max_table_elements);
+        }
+
+        if let Some(max_instances) = limits.max_instances {
+            builder = builder.instances(max_instances);
+        }
+
+        builder.build()
+    }
+}
+
+/// Per-instance configuration, usually derived from a module's configuration.
+#[derive(Debug, Clone, Default)]
+pub struct InstanceConfig {
+    pub limits: InstanceLimits,
+    pub egress_policy: EgressPolicy,
+    pub log_policy: LogPolicy,
+    /// Identifier of the module, scoping the instance's key-value storage and
+    /// tagging its log records.
+    pub module_id: String,
+    /// WASI configuration, with modules importing WASI being rejected when
+    /// [`None`].
+    pub wasi: Option<WasiConfig>,
+    /// Recording of executions, which are not recorded when [`None`].
+    pub record: Option<RecordConfig>,
+}
+
+struct Limiter {
+    limits: InstanceLimits,
+    store_limits: StoreLimits,
+}
+
+impl Limiter {
+    fn new(limits: InstanceLimits) -> Self {
+        Self {
+            limits,
+            store_limits: limits.into(),
+        }
+    }
+}
+
+impl Debug for Limiter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("Limiter")
+            .field("limits", &self.limits)
+            .finish_non_exhaustive()
+    }
+}
+
 const INIT_ID: NonZeroU64 = if let Some(id) = NonZeroU64::new(1) {
     id
 } else {
@@ -98,6 +340,8 @@ const INIT_ID: NonZeroU64 = if let Some(id) = NonZeroU64::new(1) {
 #[derive(Debug, Eq, PartialEq, Default)]
 struct ModuleResponse {
     is_error: bool,
+    status_code: Option<u16>,
+    headers: Vec<(String, Vec<u8>)>,
     data: Vec<u8>,
 }
 
@@ -105,46 +349,293 @@ impl ModuleResponse {
     pub const fn new() -> Self {
         Self {
             is_error: false,
+            status_code: None,
+            headers: Vec::new(),
             data: Vec::new(),
         }
     }
 }
 
+/// Metadata of the inbound request a module is executed for.
+#[derive(Debug, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub struct RequestMeta {
+    pub method: String,
+    pub path: String,
+    pub route: String,
+    pub query: String,
+    pub headers: Vec<(String, Vec<u8>)>,
+    /// Identifiers of the modules which invoked the request, outermost first.
+    /// Empty for requests which didn't originate from a module.
+    pub call_chain: Vec<String>,
+}
+
 #[derive(Debug, Eq, PartialEq, Default)]
-struct NetworkResponse {
+pub struct NetworkResponse {
     status_code: u16,
+    headers: Vec<(HeaderName, HeaderValue)>,
     data: Vec<u8>,
 }
 
 impl NetworkResponse {
+    #[must_use]
     pub const fn new(status_code: u16, data: Vec<u8>) -> Self {
-        Self { status_code, data }
+        Self {
+            status_code,
+            headers: Vec::new(),
+            data,
+        }
+    }
+
+    #[must_use]
+    pub fn with_headers(mut self, headers: &HeaderMap) -> Self {
+        self.headers = headers
+            .iter()
+            .map(|(name, value): (&HeaderName, &HeaderValue)| (name.clone(), value.clone()))
+            .collect();
+
+        self
     }
 
+    #[must_use]
     pub const fn status_code(&self) -> u16 {
         self.status_code
     }
 
+    #[must_use]
+    pub fn headers(&self) -> &[(HeaderName, HeaderValue)] {
+        &self.headers
+    }
+
+    #[must_use]
     pub const fn data(&self) -> &Vec<u8> {
         &self.data
     }
 
-    pub fn data_mut(&mut self) -> &mut Vec<u8> {
+    pub(crate) fn data_mut(&mut self) -> &mut Vec<u8> {
         &mut self.data
     }
 }
 
+/// Outbound request whose body is still being written by the module, while
+/// being sent in the background.
+#[derive(Debug)]
+struct OutboundRequest {
+    body_sender: MpscSender<Vec<u8>>,
+    response: JoinHandle<AnyResult<NetworkResponse>>,
+    /// Inputs under which the response is recorded once it is received.
+    recorded_call: Option<CallInputs>,
+}
+
+/// Stream of body chunks of an [`OutboundRequest`], ending once the module
+/// finishes the request.
+struct BodyStream(MpscReceiver<Vec<u8>>);
+
+impl Stream for BodyStream {
+    type Item = Result<Vec<u8>, Infallible>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.0
+            .poll_recv(cx)
+            .map(|chunk: Option<Vec<u8>>| chunk.map(Ok))
+    }
+}
+
 #[derive(Debug)]
-struct Network {
+struct NetworkKeeper<Network>
+where
+    Network: NetworkProvider,
+{
+    network: Network,
+    egress_policy: EgressPolicy,
+    last_error: u32,
+    request_id: NonZeroU64,
+    requests: HashMap<NonZeroU64, OutboundRequest>,
     response_id: NonZeroU64,
-    response: Option<NetworkResponse>,
+    responses: HashMap<NonZeroU64, NetworkResponse>,
 }
 
-impl Network {
-    pub const fn new() -> Self {
+impl<Network> NetworkKeeper<Network>
+where
+    Network: NetworkProvider,
+{
+    /// Maximum number of responses a module can keep open at the same time,
+    /// including ones to requests which are still being sent.
+    pub const MAX_RESPONSES: usize = 16;
+
+    /// Maximum number of body chunks buffered before writes wait for the
+    /// network provider.
+    pub const BODY_CHUNKS_BUFFER: usize = 4;
+
+    pub fn new(network: Network) -> Self {
         Self {
+            network,
+            egress_policy: EgressPolicy::default(),
+            last_error: 0,
+            request_id: INIT_ID,
+            requests: HashMap::new(),
             response_id: INIT_ID,
-            response: None,
+            responses: HashMap::new(),
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        Self::MAX_RESPONSES <= self.requests.len() + self.responses.len()
+    }
+
+    /// Stores response under a newly allocated ID, skipping over IDs which are
+    /// still in use. Returns [`None`] when the response table is full.
+    pub fn insert_response(&mut self, response: NetworkResponse) -> Option<NonZeroU64> {
+        if self.is_full() {
+            return None;
+        }
+
+        let id: NonZeroU64 = next_free_id(&mut self.response_id, &self.responses);
+
+        let maybe_response: Option<NetworkResponse> = self.responses.insert(id, response);
+
+        debug_assert!(maybe_response.is_none(), "Response ID repetition!");
+
+        Some(id)
+    }
+
+    pub fn response(&self, id: u64) -> AnyResult<&NetworkResponse> {
+        NonZeroU64::new(id)
+            .and_then(|id: NonZeroU64| self.responses.get(&id))
+            .ok_or_else(|| anyhow!("No response with such ID exists!"))
+    }
+
+    pub fn response_mut(&mut self, id: u64) -> AnyResult<&mut NetworkResponse> {
+        NonZeroU64::new(id)
+            .and_then(|id: NonZeroU64| self.responses.get_mut(&id))
+            .ok_or_else(|| anyhow!("No response with such ID exists!"))
+    }
+
+    pub fn remove_response(&mut self, id: u64) -> AnyResult<NetworkResponse> {
+        NonZeroU64::new(id)
+            .and_then(|id: NonZeroU64| self.responses.remove(&id))
+            .ok_or_else(|| anyhow!("No response with such ID exists!"))
+    }
+
+    /// Starts sending request in the background, with its body streamed from
+    /// chunks written afterwards. Returns [`None`] when the response table is
+    /// full.
+    pub fn open_request(
+        &mut self,
+        mut request: NetworkRequest,
+        pinned_addresses: Option<PinnedAddresses>,
+        initial_chunk: Vec<u8>,
+        recorded_call: Option<CallInputs>,
+    ) -> Option<NonZeroU64> {
+        if self.is_full() {
+            return None;
+        }
+
+        let (body_sender, body_receiver): (MpscSender<Vec<u8>>, MpscReceiver<Vec<u8>>) =
+            Self::body_channel(initial_chunk);
+
+        *request.body_mut() = Some(NetworkBody::wrap_stream(BodyStream(body_receiver)));
+
+        let mut network: Network = self.network.clone();
+
+        Some(self.insert_request(OutboundRequest {
+            body_sender,
+            response: spawn(async move { network.send_request(request, pinned_addresses).await }),
+            recorded_call,
+        }))
+    }
+
+    /// Opens request whose response is replayed from a recording, discarding
+    /// its body as it is written. Returns [`None`] when the response table is
+    /// full.
+    pub fn open_replayed_request(
+        &mut self,
+        initial_chunk: Vec<u8>,
+        response: AnyResult<NetworkResponse>,
+    ) -> Option<NonZeroU64> {
+        if self.is_full() {
+            return None;
+        }
+
+        let (body_sender, mut body_receiver): (MpscSender<Vec<u8>>, MpscReceiver<Vec<u8>>) =
+            Self::body_channel(initial_chunk);
+
+        Some(self.insert_request(OutboundRequest {
+            body_sender,
+            response: spawn(async move {
+                while body_receiver.recv().await.is_some() {}
+
+                response
+            }),
+            recorded_call: None,
+        }))
+    }
+
+    fn body_channel(initial_chunk: Vec<u8>) -> (MpscSender<Vec<u8>>, MpscReceiver<Vec<u8>>) {
+        let (body_sender, body_receiver): (MpscSender<Vec<u8>>, MpscReceiver<Vec<u8>>) =
+            mpsc_channel(Self::BODY_CHUNKS_BUFFER);
+
+        if !initial_chunk.is_empty() {
+            // Channel is freshly created, thus it has spare capacity.
+            let _: Result<(), _> = body_sender.try_send(initial_chunk);
+        }
+
+        (body_sender, body_receiver)
+    }
+
+    fn insert_request(&mut self, request: OutboundRequest) -> NonZeroU64 {
+        let id: NonZeroU64 = next_free_id(&mut self.request_id, &self.requests);
+
+        let maybe_request: Option<OutboundRequest> = self.requests.insert(id, request);
+
+        debug_assert!(maybe_request.is_none(), "Request ID repetition!");
+
+        id
+    }
+
+    pub fn request_body_sender(&self, id: u64) -> AnyResult<MpscSender<Vec<u8>>> {
+        NonZeroU64::new(id)
+            .and_then(|id: NonZeroU64| self.requests.get(&id))
+            .map(|request: &OutboundRequest| request.body_sender.clone())
+            .ok_or_else(|| anyhow!("No request with such ID exists!"))
+    }
+
+    /// Ends request's body and returns handle to the task sending it, along
+    /// with inputs under which its response has to be recorded.
+    pub fn finish_request(
+        &mut self,
+        id: u64,
+    ) -> AnyResult<(JoinHandle<AnyResult<NetworkResponse>>, Option<CallInputs>)> {
+        NonZeroU64::new(id)
+            .and_then(|id: NonZeroU64| self.requests.remove(&id))
+            .map(|request: OutboundRequest| (request.response, request.recorded_call))
+            .ok_or_else(|| anyhow!("No request with such ID exists!"))
+    }
+
+    pub fn abort_request(&mut self, id: u64) -> AnyResult<()> {
+        self.finish_request(id).map(
+            |(response, _): (JoinHandle<AnyResult<NetworkResponse>>, Option<CallInputs>)| {
+                response.abort();
+            },
+        )
+    }
+
+    /// Drops all responses and aborts all requests still being sent.
+    pub fn clear(&mut self) {
+        self.requests
+            .drain()
+            .for_each(|(_, request): (NonZeroU64, OutboundRequest)| request.response.abort());
+
+        self.responses.clear();
+    }
+}
+
+/// Advances rolling ID counter to the next ID which is not in use.
+fn next_free_id<T>(id: &mut NonZeroU64, in_use: &HashMap<NonZeroU64, T>) -> NonZeroU64 {
+    loop {
+        *id = id.checked_add(1).unwrap_or(INIT_ID);
+
+        if !in_use.contains_key(id) {
+            break *id;
         }
     }
 }
@@ -172,6 +663,131 @@ where
     }
 }
 
+#[derive(Debug)]
+struct KvKeeper<Kv>
+where
+    Kv: KvProvider,
+{
+    kv: Kv,
+    namespace: String,
+    value_id: NonZeroU64,
+    value: Option<Vec<u8>>,
+    keys_id: NonZeroU64,
+    keys: Option<Vec<Vec<u8>>>,
+}
+
+impl<Kv> KvKeeper<Kv>
+where
+    Kv: KvProvider,
+{
+    pub const MAX_KEY_LENGTH: usize = 1 << 10;
+
+    pub const MAX_VALUE_LENGTH: usize = 1 << 20;
+
+    pub const MAX_LISTED_KEYS: usize = 1 << 10;
+
+    pub const fn new(kv: Kv) -> Self {
+        Self {
+            kv,
+            namespace: String::new(),
+            value_id: INIT_ID,
+            value: None,
+            keys_id: INIT_ID,
+            keys: None,
+        }
+    }
+
+    /// Releases value and keys left held by the module.
+    pub fn clear(&mut self) {
+        self.value = None;
+
+        self.keys = None;
+    }
+}
+
+#[derive(Debug, Default)]
+struct LogKeeper {
+    policy: LogPolicy,
+    records: u32,
+    bytes: usize,
+    dropped: u32,
+}
+
+impl LogKeeper {
+    /// Accounts for a record of provided level and length, returning whether
+    /// it should be emitted.
+    fn admit(&mut self, level: Level, length: usize) -> bool {
+        if self.policy.max_level < level {
+            return false;
+        }
+
+        match self.bytes.checked_add(length) {
+            Some(bytes)
+                if self.records < self.policy.max_records_per_request
+                    && bytes <= self.policy.max_bytes_per_request =>
+            {
+                self.records += 1;
+
+                self.bytes = bytes;
+
+                true
+            }
+            _ => {
+                self.dropped = self.dropped.saturating_add(1);
+
+                false
+            }
+        }
+    }
+
+    /// Resets per-request accounting, returning number of dropped records.
+    fn reset(&mut self) -> u32 {
+        self.records = 0;
+
+        self.bytes = 0;
+
+        take(&mut self.dropped)
+    }
+}
+
+/// Response of an invoked module, with its body collected.
+#[derive(Debug)]
+struct InvokedResponse {
+    is_error: bool,
+    status_code: Option<u16>,
+    data: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct InvokeKeeper<Invoker>
+where
+    Invoker: InvokeProvider,
+{
+    invoker: Invoker,
+    response_id: NonZeroU64,
+    response: Option<InvokedResponse>,
+    last_error: u32,
+}
+
+impl<Invoker> InvokeKeeper<Invoker>
+where
+    Invoker: InvokeProvider,
+{
+    /// Maximum number of modules in a call chain, including the invoking one.
+    pub const MAX_CALL_DEPTH: usize = 8;
+
+    pub const MAX_RESPONSE_LENGTH: usize = 16 << 20;
+
+    pub const fn new(invoker: Invoker) -> Self {
+        Self {
+            invoker,
+            response_id: INIT_ID,
+            response: None,
+            last_error: 0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct SdkUser {
     username: String,
@@ -190,29 +806,138 @@ impl User for SdkUser {
 }
 
 #[derive(Debug)]
-pub struct SdkEnv<Vault>
+pub struct SdkEnv<Vault, Network, Kv, Invoker>
 where
     Vault: VaultProvider,
+    Network: NetworkProvider,
+    Kv: KvProvider,
+    Invoker: InvokeProvider,
 {
     request_reader_id: NonZeroU64,
     request: Vec<u8>,
+    request_meta: RequestMeta,
     response: ModuleResponse,
-    network: Network,
+    response_sender: Option<ResponseSender>,
+    response_stream: Option<MpscSender<Vec<u8>>>,
+    network_keeper: NetworkKeeper<Network>,
     vault_keeper: VaultKeeper<Vault>,
+    kv_keeper: KvKeeper<Kv>,
+    log_keeper: LogKeeper,
+    invoke_keeper: InvokeKeeper<Invoker>,
+    module_id: String,
+    clock_origin: Instant,
+    tape: Option<Tape>,
+    metrics_keeper: MetricsKeeper,
+    #[cfg(feature = "wasi")]
+    wasi: WasiState,
+    limiter: Limiter,
 }
 
-impl<Vault> SdkEnv<Vault>
+impl<Vault, Network, Kv, Invoker> SdkEnv<Vault, Network, Kv, Invoker>
 where
     Vault: VaultProvider,
+    Network: NetworkProvider,
+    Kv: KvProvider,
+    Invoker: InvokeProvider,
 {
-    pub const fn new(vault: Vault) -> Self {
+    /// Maximum number of streamed response chunks buffered before writes wait
+    /// for the client.
+    pub const RESPONSE_CHUNKS_BUFFER: usize = 16;
+
+    pub fn new(vault: Vault, network: Network, kv: Kv, invoker: Invoker) -> Self {
         Self {
             request_reader_id: INIT_ID,
             request: Vec::new(),
+            request_meta: RequestMeta::default(),
             response: ModuleResponse::new(),
-            network: Network::new(),
+            response_sender: None,
+            response_stream: None,
+            network_keeper: NetworkKeeper::new(network),
             vault_keeper: VaultKeeper::new(vault),
+            kv_keeper: KvKeeper::new(kv),
+            log_keeper: LogKeeper::default(),
+            invoke_keeper: InvokeKeeper::new(invoker),
+            module_id: String::new(),
+            clock_origin: Instant::now(),
+            tape: None,
+            metrics_keeper: MetricsKeeper::default(),
+            #[cfg(feature = "wasi")]
+            wasi: WasiState::empty(),
+            limiter: Limiter::new(InstanceLimits::default()),
+        }
+    }
+
+    pub fn set_limits(&mut self, limits: InstanceLimits) {
+        self.limiter = Limiter::new(limits);
+    }
+
+    pub fn set_egress_policy(&mut self, egress_policy: EgressPolicy) {
+        self.network_keeper.egress_policy = egress_policy;
+    }
+
+    pub fn set_log_policy(&mut self, log_policy: LogPolicy) {
+        self.log_keeper.policy = log_policy;
+    }
+
+    /// Sets identifier of the module, which scopes its key-value storage,
+    /// tags its log records and is recorded in call chains of modules it
+    /// invokes.
+    pub fn set_module_id(&mut self, module_id: String) {
+        self.kv_keeper.namespace.clone_from(&module_id);
+
+        self.module_id = module_id;
+    }
+
+    /// Enables recording of executions, along with host calls made by the
+    /// module during them.
+    pub fn set_record_config(&mut self, config: RecordConfig) {
+        self.tape = Some(Tape::recording(config));
+    }
+
+    /// Enables WASI, with output of the module being bounded by the per-request
+    /// byte limit of the log policy, which thus has to be set beforehand.
+    /// # Errors
+    /// Error will occur when configured environment variables are malformed.
+    #[cfg(feature = "wasi")]
+    pub fn set_wasi(&mut self, config: &WasiConfig) -> AnyResult<()> {
+        self.wasi = WasiState::new(config, self.log_keeper.policy.max_bytes_per_request)?;
+
+        Ok(())
+    }
+
+    /// Enables WASI, which isn't supported as the runtime is built without it.
+    /// # Errors
+    /// Error will always occur.
+    #[cfg(not(feature = "wasi"))]
+    pub fn set_wasi(&mut self, _: &WasiConfig) -> AnyResult<()> {
+        bail!("Runtime is built without WASI support!")
+    }
+
+    /// Takes lines written to standard output and standard error, admitted
+    /// under the log policy, as records with respective levels and targets.
+    #[cfg(feature = "wasi")]
+    fn take_wasi_output(&mut self) -> Vec<(Level, &'static str, String)> {
+        let mut records: Vec<(Level, &'static str, String)> = Vec::new();
+
+        for (level, target, (output, overflowed)) in [
+            (Level::INFO, "stdout", self.wasi.stdout.take()),
+            (Level::WARN, "stderr", self.wasi.stderr.take()),
+        ] {
+            if overflowed {
+                self.log_keeper.dropped = self.log_keeper.dropped.saturating_add(1);
+            }
+
+            records.extend(
+                output
+                    .split(|&byte: &u8| byte == b'\n')
+                    .map(|line: &[u8]| line.strip_suffix(b"\r").unwrap_or(line))
+                    .filter(|line: &&[u8]| !line.is_empty())
+                    .filter(|line: &&[u8]| self.log_keeper.admit(level, line.len()))
+                    .map(|line: &[u8]| (level, target, String::from_utf8_lossy(line).into_owned())),
+            );
         }
+
+        records
     }
 
     pub fn set_request_data(&mut self, data: Vec<u8>) {
@@ -223,57 +948,131 @@ where
     pub fn clear_request_data(&mut self) {
         self.request = vec![];
     }
+
+    pub fn set_request_meta(&mut self, meta: RequestMeta) {
+        self.request_meta = meta;
+    }
+
+    pub fn clear_request_meta(&mut self) {
+        self.request_meta = RequestMeta::default();
+    }
+
+    /// Returns whether request-scoped state, which modules are expected to
+    /// release before returning, is still held.
+    fn holds_request_state(&self) -> bool {
+        self.vault_keeper.secret.is_some()
+    }
+
+    /// Sends response's status code, headers and data written so far, after
+    /// which data is forwarded to the client as it is written.
+    fn start_response_stream(&mut self) -> AnyResult<()> {
+        if self.response_stream.is_some() {
+            bail!("Response is already being streamed!");
+        }
+
+        let Some(response_sender) = self.response_sender.take() else {
+            bail!("No response is expected to be sent!");
+        };
+
+        let (chunk_sender, chunk_receiver): (MpscSender<Vec<u8>>, MpscReceiver<Vec<u8>>) =
+            mpsc_channel(Self::RESPONSE_CHUNKS_BUFFER);
+
+        let response: ModuleResponse = take(&mut self.response);
+
+        if !response.data.is_empty() {
+            // Channel is freshly created, thus it has spare capacity.
+            let _: Result<(), _> = chunk_sender.try_send(response.data);
+        }
+
+        // Client might have already disconnected, in which case written data
+        // is simply discarded.
+        let _: Result<(), _> = response_sender.send(Ok(Response {
+            is_error: response.is_error,
+            status_code: response.status_code,
+            headers: response.headers,
+            body: ResponseBody::Streamed(chunk_receiver),
+        }));
+
+        self.response_stream = Some(chunk_sender);
+
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
-pub struct SdkContext<Vault>
+pub struct SdkContext<Vault, Network, Kv, Invoker>
 where
     Vault: VaultProvider,
+    Network: NetworkProvider,
+    Kv: KvProvider,
+    Invoker: InvokeProvider,
 {
-    env: SdkEnv<Vault>,
+    env: SdkEnv<Vault, Network, Kv, Invoker>,
     sender: Option<SdkUser>,
 }
 
-impl<Vault> SdkContext<Vault>
+impl<Vault, Network, Kv, Invoker> SdkContext<Vault, Network, Kv, Invoker>
 where
     Vault: VaultProvider,
+    Network: NetworkProvider,
+    Kv: KvProvider,
+    Invoker: InvokeProvider,
 {
-    pub const fn new(env: SdkEnv<Vault>) -> Self {
+    pub const fn new(env: SdkEnv<Vault, Network, Kv, Invoker>) -> Self {
         Self { env, sender: None }
     }
 }
 
-impl<Vault> From<SdkEnv<Vault>> for SdkContext<Vault>
+impl<Vault, Network, Kv, Invoker> From<SdkEnv<Vault, Network, Kv, Invoker>>
+    for SdkContext<Vault, Network, Kv, Invoker>
 where
     Vault: VaultProvider,
+    Network: NetworkProvider,
+    Kv: KvProvider,
+    Invoker: InvokeProvider,
 {
-    fn from(env: SdkEnv<Vault>) -> Self {
+    fn from(env: SdkEnv<Vault, Network, Kv, Invoker>) -> Self {
         Self::new(env)
     }
 }
 
-impl<Vault> Context for SdkContext<Vault>
+impl<Vault, Network, Kv, Invoker> Context for SdkContext<Vault, Network, Kv, Invoker>
 where
     Vault: VaultProvider + Sync,
+    Network: NetworkProvider,
+    Kv: KvProvider,
+    Invoker: InvokeProvider,
 {
     type ConstructorContext = ();
 
     type Vault = Vault;
 
+    type Network = Network;
+
+    type Kv = Kv;
+
+    type Invoker = Invoker;
+
     type User = SdkUser;
 
-    fn with_vault_and_context(vault: Vault, (): ()) -> Self
+    fn with_providers_and_context(
+        vault: Vault,
+        network: Network,
+        kv: Kv,
+        invoker: Invoker,
+        (): (),
+    ) -> Self
     where
         Self: Sized,
     {
-        Self::new(SdkEnv::new(vault))
+        Self::new(SdkEnv::new(vault, network, kv, invoker))
     }
 
-    fn sdk(&self) -> &SdkEnv<Vault> {
+    fn sdk(&self) -> &SdkEnv<Vault, Network, Kv, Invoker> {
         &self.env
     }
 
-    fn sdk_mut(&mut self) -> &mut SdkEnv<Vault> {
+    fn sdk_mut(&mut self) -> &mut SdkEnv<Vault, Network, Kv, Invoker> {
         &mut self.env
     }
 
@@ -290,55 +1089,291 @@ where
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum Response {
-    Success(Vec<u8>),
-    Error(Vec<u8>),
+/// Response produced by a module. Status code is [`None`] when the module
+/// didn't set one explicitly, leaving the choice to the embedder based on
+/// whether the response is an error.
+#[derive(Debug)]
+pub struct Response {
+    pub is_error: bool,
+    pub status_code: Option<u16>,
+    pub headers: Vec<(String, Vec<u8>)>,
+    pub body: ResponseBody,
+}
+
+#[derive(Debug)]
+pub enum ResponseBody {
+    Buffered(Vec<u8>),
+    /// Chunks written by the module after it started streaming the response.
+    /// Channel is closed once the module returns.
+    Streamed(MpscReceiver<Vec<u8>>),
 }
 
+pub type ResponseSender = OneshotSender<AnyResult<Response>>;
+
+/// Length of the header preceding compiled code of serialized modules, made
+/// of the targeted SDK ABI version and the code's SHA-256 checksum.
+const SERIALIZED_HEADER_LENGTH: usize = 4 + 32;
+
 #[derive(Clone)]
 pub struct VerifiedModule(Module);
 
 impl VerifiedModule {
-    /// Verifies that module exports all required symbols and also verifies
-    /// their type.
+    /// Compiles module and verifies that it targets a compatible version of
+    /// the SDK ABI, that it imports only functions provided by the runtime
+    /// and that it exports all required symbols of expected types.
     /// # Errors
-    /// Error will occur when module doesn't export all required symbols and/or
-    /// their type doesn't match expected one.
-    pub fn new(module: Module) -> AnyResult<Self> {
-        if !matches!(
-            module.get_export("memory").ok_or_else(|| anyhow!(
-                r#"Module doesn't contain any memory exported as "memory"!"#
-            ))?,
-            ExternType::Memory(_)
-        ) {
-            bail!(r#"Exported symbol "memory" is not a memory!"#);
-        }
-
-        let Some(entry): Option<ExternType> = module.get_export("entry") else {
-            bail!(r#"Module doesn't contain entry point exported as "entry"!"#)
-        };
+    /// Error will occur when module fails to compile or any of the checks
+    /// fails.
+    pub fn new(engine: &Engine, binary: &[u8]) -> Result<Self, StaticModuleVerificationError> {
+        let abi_version: u32 = Self::abi_version(binary)?;
+
+        Module::new(engine, binary)
+            .map_err(StaticModuleVerificationError::InvalidModule)
+            .and_then(|module: Module| Self::verify(module, abi_version))
+    }
 
-        let ExternType::Func(func) = entry else {
-            bail!(r#"Exported symbol "memory" is not a memory!"#)
-        };
+    /// Serializes compiled module, prefixed by the version of the SDK ABI it
+    /// targets and a checksum of the compiled code.
+    /// # Errors
+    /// Error will occur when engine fails to serialize the module.
+    pub fn serialize(&self) -> AnyResult<Vec<u8>> {
+        let artifact: Vec<u8> = self.0.serialize()?;
+
+        let mut serialized: Vec<u8> = Vec::with_capacity(SERIALIZED_HEADER_LENGTH + artifact.len());
 
-        if func.params().count() != 0 {
-            bail!("Entry point takes parameters as opposed to not taking any!")
+        serialized.extend_from_slice(&lambda_abi::VERSION.to_le_bytes());
+
+        serialized.extend_from_slice(&Sha256::digest(&artifact));
+
+        serialized.extend_from_slice(&artifact);
+
+        Ok(serialized)
+    }
+
+    /// Loads module serialized by [`Self::serialize`] and verifies it the same
+    /// way as [`Self::new`] does. Serialized modules are loaded as native
+    /// code, thus have to come from a trusted source, as the checksum only
+    /// guards against corruption.
+    /// # Errors
+    /// Error will occur when serialized module is corrupted, was compiled by
+    /// an engine with incompatible configuration or any of the checks fails.
+    pub fn deserialize(
+        engine: &Engine,
+        serialized: &[u8],
+    ) -> Result<Self, StaticModuleVerificationError> {
+        if serialized.len() < SERIALIZED_HEADER_LENGTH {
+            return Err(StaticModuleVerificationError::InvalidSerializedModule(
+                anyhow!("Serialized module is truncated!"),
+            ));
+        }
+
+        let (abi_version, serialized): (&[u8], &[u8]) = serialized.split_at(4);
+
+        let (checksum, artifact): (&[u8], &[u8]) = serialized.split_at(32);
+
+        if Sha256::digest(artifact).as_slice() != checksum {
+            return Err(StaticModuleVerificationError::InvalidSerializedModule(
+                anyhow!("Serialized module's checksum doesn't match its contents!"),
+            ));
+        }
+
+        let mut abi_version_bytes: [u8; 4] = [0; 4];
+
+        abi_version_bytes.copy_from_slice(abi_version);
+
+        // ALLOW: Required by "wasmtime" API to load compiled modules.
+        #[allow(unsafe_code)]
+        // SAFETY: Artifact is intact output of `serialize`, which is trusted
+        // as documented. Artifacts compiled by engines with incompatible
+        // configuration are rejected by `deserialize`.
+        let module: Module = unsafe { Module::deserialize(engine, artifact) }
+            .map_err(StaticModuleVerificationError::InvalidSerializedModule)?;
+
+        Self::verify(module, u32::from_le_bytes(abi_version_bytes))
+    }
+
+    fn verify(module: Module, abi_version: u32) -> Result<Self, StaticModuleVerificationError> {
+        if abi_version != lambda_abi::VERSION {
+            return Err(StaticModuleVerificationError::IncompatibleAbiVersion(
+                abi_version,
+            ));
+        }
+
+        module
+            .imports()
+            .try_for_each(|import: ImportType<'_>| Self::verify_import(&import))?;
+
+        match module.get_export("memory") {
+            None => return Err(StaticModuleVerificationError::NoMemoryExport),
+            Some(ExternType::Memory(_)) => {}
+            Some(_) => return Err(StaticModuleVerificationError::InvalidMemoryExport),
+        }
+
+        let mut entry_points: usize = 0;
+
+        for export in module.exports() {
+            if !is_entry_point(export.name()) {
+                continue;
+            }
+
+            let ExternType::Func(func) = export.ty() else {
+                return Err(StaticModuleVerificationError::InvalidEntryPoint(
+                    String::from(export.name()),
+                ));
+            };
+
+            if func.params().count() != 0 {
+                return Err(StaticModuleVerificationError::EntryPointTakesParameters(
+                    String::from(export.name()),
+                ));
+            }
+
+            if func.results().count() != 0 {
+                return Err(StaticModuleVerificationError::EntryPointReturnsValue(
+                    String::from(export.name()),
+                ));
+            }
+
+            entry_points += 1;
         }
 
-        if func.results().count() != 0 {
-            bail!("Entry point returns value as opposed to not returning any!")
+        if entry_points == 0 {
+            return Err(StaticModuleVerificationError::NoEntryPoint);
         }
 
         Ok(Self(module))
     }
+
+    /// Returns whether module exports the default entry point.
+    #[must_use]
+    pub fn has_default_entry_point(&self) -> bool {
+        self.0.get_export(ENTRY_EXPORT).is_some()
+    }
+
+    /// Returns names of the handlers exported by the module, besides the
+    /// default entry point.
+    pub fn handlers(&self) -> impl Iterator<Item = &str> {
+        self.0
+            .exports()
+            .filter_map(|export: ExportType<'_>| handler_name(export.name()))
+    }
+
+    fn abi_version(binary: &[u8]) -> Result<u32, StaticModuleVerificationError> {
+        for payload in Parser::new(0).parse_all(binary) {
+            let payload: Payload<'_> = payload.map_err(|error: BinaryReaderError| {
+                StaticModuleVerificationError::InvalidModule(error.into())
+            })?;
+
+            if let Payload::CustomSection(section) = payload {
+                if section.name() == lambda_abi::VERSION_SECTION {
+                    return section
+                        .data()
+                        .try_into()
+                        .map(u32::from_le_bytes)
+                        .map_err(|_| StaticModuleVerificationError::MalformedAbiVersion);
+                }
+            }
+        }
+
+        Err(StaticModuleVerificationError::NoAbiVersion)
+    }
+
+    /// Verifies that imported function is provided by the runtime with the
+    /// same signature. Imports of WASI are verified on instantiation instead,
+    /// as it is enabled per module.
+    fn verify_import(import: &ImportType<'_>) -> Result<(), StaticModuleVerificationError> {
+        if WASI_MODULES.contains(&import.module()) {
+            return Ok(());
+        }
+
+        let Some(provided): Option<AbiImport> = find_import(import.module(), import.name()) else {
+            return Err(StaticModuleVerificationError::UnknownImport {
+                module: String::from(import.module()),
+                name: String::from(import.name()),
+            });
+        };
+
+        let value_types = |types: &mut dyn Iterator<Item = ValType>| -> Option<Vec<ValueType>> {
+            types
+                .map(|r#type: ValType| match r#type {
+                    ValType::I32 => Some(ValueType::I32),
+                    ValType::I64 => Some(ValueType::I64),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        match import.ty() {
+            ExternType::Func(func)
+                if value_types(&mut func.params()).as_ref() == Some(&provided.params)
+                    && value_types(&mut func.results()).as_ref() == Some(&provided.results) =>
+            {
+                Ok(())
+            }
+            _ => Err(StaticModuleVerificationError::ImportSignatureMismatch {
+                module: String::from(import.module()),
+                name: String::from(import.name()),
+            }),
+        }
+    }
 }
 
+#[derive(Debug, thiserror::Error)]
 pub enum StaticModuleVerificationError {
+    #[error("Module is not a valid WebAssembly module!")]
+    InvalidModule(#[source] AnyError),
+    #[error("Serialized module is corrupted or incompatible with the engine!")]
+    InvalidSerializedModule(#[source] AnyError),
+    #[error("Module doesn't declare the version of SDK ABI it targets!")]
+    NoAbiVersion,
+    #[error("Module declares malformed version of SDK ABI!")]
+    MalformedAbiVersion,
+    #[error(
+        "Module targets version {0} of SDK ABI, while runtime provides version {}!",
+        lambda_abi::VERSION
+    )]
+    IncompatibleAbiVersion(u32),
+    #[error(r#"Module imports "{name}" from "{module}", which isn't provided!"#)]
+    UnknownImport { module: String, name: String },
+    #[error(
+        r#"Module imports "{name}" from "{module}" with signature different from the provided one!"#
+    )]
+    ImportSignatureMismatch { module: String, name: String },
+    #[error(r#"Module doesn't contain any memory exported as "memory"!"#)]
     NoMemoryExport,
+    #[error(r#"Exported symbol "memory" is not a memory!"#)]
     InvalidMemoryExport,
+    #[error(r#"Module doesn't export entry point as "entry" or any handler as "entry::<name>"!"#)]
     NoEntryPoint,
+    #[error(r#"Exported entry point "{0}" is not a function!"#)]
+    InvalidEntryPoint(String),
+    #[error(r#"Entry point "{0}" takes parameters as opposed to not taking any!"#)]
+    EntryPointTakesParameters(String),
+    #[error(r#"Entry point "{0}" returns value as opposed to not returning any!"#)]
+    EntryPointReturnsValue(String),
+}
+
+/// Returns whether export is the default entry point or a named handler,
+/// whose name can't be empty.
+fn is_entry_point(export_name: &str) -> bool {
+    export_name == ENTRY_EXPORT || handler_name(export_name).is_some()
+}
+
+/// Returns name of the handler provided export is, if any.
+fn handler_name(export_name: &str) -> Option<&str> {
+    export_name
+        .strip_prefix(HANDLER_EXPORT_PREFIX)
+        .filter(|handler: &&str| !handler.is_empty())
+}
+
+/// State of an instance after execution. Instances become tainted when
+/// execution traps, including on a module's panic, or when the module returns
+/// while still holding request-scoped state, such as an unreleased secret.
+/// Tainted instances must not be reused.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum InstanceState {
+    Clean,
+    Tainted,
 }
 
 #[must_use]
@@ -347,7 +1382,11 @@ where
     Ctx: Context,
 {
     store: Store<Ctx>,
-    entry: TypedFunc<(), ()>,
+    /// Entry points by the name they are exported under.
+    entry_points: HashMap<String, TypedFunc<(), ()>>,
+    memory: Memory,
+    state: InstanceState,
+    metrics: ExecutionMetrics,
 }
 
 impl<Ctx> SdkInstance<Ctx>
@@ -362,14 +1401,25 @@ where
         linker: &LinkerWithSdk<Ctx>,
         module: &VerifiedModule,
         constructor_context: Ctx::ConstructorContext,
+        config: &InstanceConfig,
     ) -> AnyResult<Self>
     where
         Ctx::Vault: Clone,
+        Ctx::Network: Clone,
+        Ctx::Kv: Clone,
+        Ctx::Invoker: Clone,
     {
         Self::internal_new(
             &linker.linker,
-            Ctx::with_vault_and_context(linker.vault.clone(), constructor_context),
+            Ctx::with_providers_and_context(
+                linker.vault.clone(),
+                linker.network.clone(),
+                linker.kv.clone(),
+                linker.invoker.clone(),
+                constructor_context,
+            ),
             &module.0,
+            config,
         )
         .await
     }
@@ -382,31 +1432,164 @@ where
         linker: LinkerWithSdk<Ctx>,
         module: &VerifiedModule,
         constructor_context: Ctx::ConstructorContext,
+        config: &InstanceConfig,
     ) -> AnyResult<Self> {
         Self::internal_new(
             &linker.linker,
-            Ctx::with_vault_and_context(linker.vault, constructor_context),
+            Ctx::with_providers_and_context(
+                linker.vault,
+                linker.network,
+                linker.kv,
+                linker.invoker,
+                constructor_context,
+            ),
             &module.0,
+            config,
         )
         .await
     }
 
-    async fn internal_new(linker: &Linker<Ctx>, context: Ctx, module: &Module) -> AnyResult<Self> {
+    async fn internal_new(
+        linker: &Linker<Ctx>,
+        mut context: Ctx,
+        module: &Module,
+        config: &InstanceConfig,
+    ) -> AnyResult<Self> {
+        context.sdk_mut().set_limits(config.limits);
+
+        context
+            .sdk_mut()
+            .set_egress_policy(config.egress_policy.clone());
+
+        context.sdk_mut().set_log_policy(config.log_policy);
+
+        context.sdk_mut().set_module_id(config.module_id.clone());
+
+        if let Some(record) = &config.record {
+            context.sdk_mut().set_record_config(record.clone());
+        }
+
+        if module
+            .imports()
+            .any(|import: ImportType<'_>| WASI_MODULES.contains(&import.module()))
+        {
+            let Some(wasi): Option<&WasiConfig> = config.wasi.as_ref() else {
+                bail!("Module imports WASI, which isn't enabled for it!");
+            };
+
+            context.sdk_mut().set_wasi(wasi)?;
+        }
+
         let mut store: Store<Ctx> = Store::new(linker.engine(), context);
 
+        store.limiter(|context: &mut Ctx| &mut context.sdk_mut().limiter.store_limits);
+
+        store.call_hook(|context: &mut Ctx, hook: CallHook| {
+            context.sdk_mut().metrics_keeper.on_call(hook);
+
+            Ok(())
+        });
+
         let instance: Instance = linker.instantiate_async(&mut store, module).await?;
 
-        let Ok(entry): AnyResult<TypedFunc<(), ()>> = instance.get_typed_func(&mut store, "entry") else {
+        let mut entry_points: HashMap<String, TypedFunc<(), ()>> = HashMap::new();
+
+        for export in module.exports() {
+            if !is_entry_point(export.name()) {
+                continue;
+            }
+
+            let Ok(entry): AnyResult<TypedFunc<(), ()>> =
+                instance.get_typed_func(&mut store, export.name())
+            else {
+                unreachable!()
+            };
+
+            entry_points.insert(String::from(export.name()), entry);
+        }
+
+        let Some(memory): Option<Memory> = instance.get_memory(&mut store, "memory") else {
             unreachable!()
         };
 
-        Ok(Self { store, entry })
+        Ok(Self {
+            store,
+            entry_points,
+            memory,
+            state: InstanceState::Clean,
+            metrics: ExecutionMetrics::default(),
+        })
     }
 
+    /// Executes provided handler of the module, or its default entry point
+    /// when [`None`], with provided request data, metadata and sender.
+    /// Response is sent through provided sender once the module returns, or
+    /// as soon as it starts streaming the response.
+    ///
+    /// Errors from execution traps for reasons other than a module's panic
+    /// are sent in place of the response. Running out of CPU budget is
+    /// reported as [`ExecutionError::CpuBudgetExhausted`], while handlers the
+    /// module doesn't export are reported as
+    /// [`ExecutionError::UnknownHandler`].
+    ///
+    /// Returns state of the instance, which must be discarded when tainted.
+    /// Metrics of the execution are available through [`Self::metrics`]
+    /// afterwards, even when it fails.
+    /// # Errors
+    /// Error will occur when execution fails after the response started
+    /// streaming, in which case the instance is tainted.
     pub async fn execute(
         &mut self,
+        handler: Option<&str>,
+        data: Vec<u8>,
+        meta: RequestMeta,
+        sender: Option<Ctx::User>,
+        cpu_budget: CpuBudget,
+        response_sender: ResponseSender,
+    ) -> AnyResult<InstanceState> {
+        self.store.data_mut().sdk_mut().response_sender = Some(response_sender);
+
+        let result: AnyResult<Response> = self
+            .execute_entry(handler, data, meta, sender, cpu_budget)
+            .await;
+
+        let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+            self.store.data_mut().sdk_mut();
+
+        // Closes response's body when it is being streamed.
+        sdk.response_stream = None;
+
+        if let Some(response_sender) = sdk.response_sender.take() {
+            // Client might have already disconnected, in which case the
+            // response is simply discarded.
+            let _: Result<(), _> = response_sender.send(result);
+
+            Ok(self.state)
+        } else {
+            result
+                .map(|_: Response| self.state)
+                .context("Execution failed after response started streaming!")
+        }
+    }
+
+    #[must_use]
+    pub const fn state(&self) -> InstanceState {
+        self.state
+    }
+
+    /// Returns metrics of the last execution.
+    #[must_use]
+    pub const fn metrics(&self) -> &ExecutionMetrics {
+        &self.metrics
+    }
+
+    async fn execute_entry(
+        &mut self,
+        handler: Option<&str>,
         data: Vec<u8>,
+        meta: RequestMeta,
         sender: Option<Ctx::User>,
+        cpu_budget: CpuBudget,
     ) -> AnyResult<Response> {
         debug_assert_eq!(
             &self.store.data().sdk().response,
@@ -414,32 +1597,483 @@ where
             "Response field is dirty before execution of request!"
         );
 
+        // Metrics of the previous execution mustn't be reported for one which
+        // failed before running.
+        self.metrics = ExecutionMetrics::default();
+
+        let entry: TypedFunc<(), ()> = self.entry_point(handler)?;
+
+        let start: Instant = Instant::now();
+
+        self.set_cpu_budget(cpu_budget)?;
+
+        let initial_fuel_consumed: Option<u64> = self.store.fuel_consumed();
+
+        let initial_memory_bytes: usize = self.memory.data_size(&self.store);
+
+        let bytes_in: u64 = data.len().try_into().unwrap_or(u64::MAX);
+
         let context: &mut Ctx = self.store.data_mut();
 
+        let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> = context.sdk_mut();
+
+        if let Some(tape) = &mut sdk.tape {
+            tape.start(
+                &sdk.module_id,
+                handler,
+                &data,
+                &meta,
+                sender.as_ref().map(User::username),
+            );
+        }
+
         context.sdk_mut().set_request_data(data);
 
+        context.sdk_mut().set_request_meta(meta);
+
         if let Some(sender) = sender {
             context.set_sender(sender);
         }
 
-        if let Err(error) = self.entry.call_async(&mut self.store, ()).await {
-            let _: PanickedError = error.downcast()?;
-        }
+        // Discards anything collected while instantiating or between
+        // executions.
+        context.sdk_mut().metrics_keeper = MetricsKeeper::default();
+
+        let result: AnyResult<()> = entry.call_async(&mut self.store, ()).await;
+
+        let peak_memory_bytes: usize = self.memory.data_size(&self.store);
+
+        self.metrics =
+            ExecutionMetrics {
+                wall_time: start.elapsed(),
+                fuel_consumed: self.store.fuel_consumed().zip(initial_fuel_consumed).map(
+                    |(fuel_consumed, initial): (u64, u64)| fuel_consumed.saturating_sub(initial),
+                ),
+                peak_memory_bytes,
+                memory_growth_bytes: peak_memory_bytes.saturating_sub(initial_memory_bytes),
+                bytes_in,
+                ..self.store.data_mut().sdk_mut().metrics_keeper.take()
+            };
 
         let context: &mut Ctx = self.store.data_mut();
 
+        if result.is_err() || context.sdk().holds_request_state() {
+            self.state = InstanceState::Tainted;
+        }
+
         context.sdk_mut().clear_request_data();
 
+        context.sdk_mut().clear_request_meta();
+
+        // Requests and responses left open by a trapped or careless module
+        // would otherwise occupy the table of a pooled instance indefinitely.
+        context.sdk_mut().network_keeper.clear();
+
+        context.sdk_mut().kv_keeper.clear();
+
+        context.sdk_mut().invoke_keeper.response = None;
+
+        if let Some(tape) = &mut context.sdk_mut().tape {
+            tape.finish();
+        }
+
+        #[cfg(feature = "wasi")]
+        for (level, target, message) in context.sdk_mut().take_wasi_output() {
+            log::emit(
+                level,
+                &LogRecord {
+                    module_id: &context.sdk().module_id,
+                    username: context.sender().map(User::username),
+                    target,
+                    message: &message,
+                    fields: &[],
+                },
+            );
+        }
+
+        let dropped_log_records: u32 = context.sdk_mut().log_keeper.reset();
+
+        if dropped_log_records != 0 {
+            log::emit_dropped(
+                &context.sdk().module_id,
+                context.sender().map(User::username),
+                dropped_log_records,
+            );
+        }
+
         context.clear_sender();
 
         debug_assert!(context.sender().is_none());
 
         let response: ModuleResponse = take(&mut self.store.data_mut().sdk_mut().response);
 
-        Ok(if response.is_error {
-            Response::Error
-        } else {
-            Response::Success
-        }(response.data))
+        if let Err(error) = result {
+            if matches!(
+                error.downcast_ref::<Trap>(),
+                Some(Trap::OutOfFuel | Trap::Interrupt)
+            ) {
+                return Err(ExecutionError::CpuBudgetExhausted.into());
+            }
+
+            let _: PanickedError = error.downcast()?;
+        }
+
+        Ok(Response {
+            is_error: response.is_error,
+            status_code: response.status_code,
+            headers: response.headers,
+            body: ResponseBody::Buffered(response.data),
+        })
+    }
+
+    fn entry_point(&self, handler: Option<&str>) -> Result<TypedFunc<(), ()>, ExecutionError> {
+        let export: String = handler.map_or_else(
+            || String::from(ENTRY_EXPORT),
+            |handler: &str| format!("{HANDLER_EXPORT_PREFIX}{handler}"),
+        );
+
+        self.entry_points.get(&export).copied().ok_or_else(|| {
+            handler.map_or(ExecutionError::NoDefaultEntryPoint, |handler: &str| {
+                ExecutionError::UnknownHandler(String::from(handler))
+            })
+        })
+    }
+
+    fn set_cpu_budget(&mut self, cpu_budget: CpuBudget) -> AnyResult<()> {
+        if self.store.fuel_consumed().is_some() {
+            let remaining: u64 = self.store.consume_fuel(0)?;
+
+            self.store.consume_fuel(remaining)?;
+
+            self.store
+                .add_fuel(if let CpuBudget::Fuel(fuel) = cpu_budget {
+                    fuel.get()
+                } else {
+                    u64::MAX
+                })?;
+        }
+
+        self.store
+            .set_epoch_deadline(if let CpuBudget::EpochDeadline(ticks) = cpu_budget {
+                ticks.get()
+            } else {
+                UNLIMITED_EPOCH_DEADLINE
+            });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::{ready, Ready},
+        num::NonZeroU64,
+    };
+
+    use anyhow::{anyhow, Error as AnyError, Result as AnyResult};
+    use reqwest::Request as NetworkRequest;
+    use tokio::sync::oneshot::{channel as oneshot_channel, Receiver as OneshotReceiver};
+    use tracing::{level_filters::LevelFilter, Level};
+    use wasmtime::{Config, Engine, Instance, Linker, Module, Store, WasmParams, WasmResults};
+    use zeroize::Zeroizing;
+
+    use super::{
+        crypto::VaultKey,
+        egress::PinnedAddresses,
+        invoke::{Invocation, InvokeError},
+        is_entry_point,
+        kv::MemoryKv,
+        log::LogPolicy,
+        sdk_rt::link_rt,
+        Context, CpuBudget, ExecutionError, InstanceConfig, InstanceState, InvokeProvider,
+        LinkerWithSdk, LogKeeper, NetworkProvider, NetworkResponse, RequestMeta, Response,
+        ResponseSender, SdkContext, SdkEnv, SdkInstance, VaultProvider, VerifiedModule,
+    };
+
+    /// Provider failing every operation, for tests which don't exercise it.
+    #[derive(Clone)]
+    pub(crate) struct Unavailable;
+
+    impl VaultProvider for Unavailable {
+        type Result<'r> = Ready<AnyResult<Option<Zeroizing<Vec<u8>>>>>;
+
+        type KeyResult<'r> = Ready<AnyResult<Option<VaultKey>>>;
+
+        fn fetch_secret(&mut self, _: String) -> Self::Result<'_> {
+            ready(Err(anyhow!("Unavailable!")))
+        }
+
+        fn fetch_key(&mut self, _: String) -> Self::KeyResult<'_> {
+            ready(Err(anyhow!("Unavailable!")))
+        }
+    }
+
+    impl NetworkProvider for Unavailable {
+        type Result<'r> = Ready<AnyResult<NetworkResponse>>;
+
+        fn send_request(
+            &mut self,
+            _: NetworkRequest,
+            _: Option<PinnedAddresses>,
+        ) -> Self::Result<'_> {
+            ready(Err(anyhow!("Unavailable!")))
+        }
+    }
+
+    impl InvokeProvider for Unavailable {
+        type Result<'r> = Ready<AnyResult<Result<Response, InvokeError>>>;
+
+        fn invoke(&mut self, _: Invocation) -> Self::Result<'_> {
+            ready(Err(anyhow!("Unavailable!")))
+        }
+    }
+
+    pub(crate) type TestContext = SdkContext<Unavailable, Unavailable, MemoryKv, Unavailable>;
+
+    /// Compiles module from its text format, marking it with the current ABI
+    /// version.
+    pub(crate) fn module_binary(wat: &str) -> Vec<u8> {
+        let mut binary: Vec<u8> = wat::parse_str(wat).expect("Test module is malformed!");
+
+        let name: &[u8] = lambda_abi::VERSION_SECTION.as_bytes();
+
+        let version: [u8; 4] = lambda_abi::VERSION.to_le_bytes();
+
+        // Lengths fit into single byte LEB128 encodings.
+        let length_of = |length: usize| -> u8 {
+            u8::try_from(length)
+                .ok()
+                .filter(|&length: &u8| length < 0x80)
+                .expect("Custom section is too long!")
+        };
+
+        binary.extend([
+            0,
+            length_of(1 + name.len() + version.len()),
+            length_of(name.len()),
+        ]);
+        binary.extend_from_slice(name);
+        binary.extend_from_slice(&version);
+
+        binary
+    }
+
+    /// Instance of a module linked directly against the host functions, with
+    /// provided environment installed, whose exports are called by tests.
+    pub(crate) struct Guest<Ctx>
+    where
+        Ctx: Context,
+    {
+        store: Store<Ctx>,
+        instance: Instance,
+    }
+
+    impl<Ctx> Guest<Ctx>
+    where
+        Ctx: Context + From<SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker>>,
+    {
+        pub(crate) async fn new(
+            env: SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker>,
+            wat: &str,
+        ) -> AnyResult<Self> {
+            let engine: Engine = Engine::new(Config::new().async_support(true))?;
+
+            let mut linker: Linker<Ctx> = Linker::new(&engine);
+
+            link_rt(&mut linker)?;
+
+            let mut store: Store<Ctx> = Store::new(&engine, Ctx::from(env));
+
+            let module: Module = Module::new(&engine, wat::parse_str(wat)?)?;
+
+            let instance: Instance = linker.instantiate_async(&mut store, &module).await?;
+
+            Ok(Self { store, instance })
+        }
+
+        pub(crate) async fn call<Params, Results>(
+            &mut self,
+            name: &str,
+            params: Params,
+        ) -> AnyResult<Results>
+        where
+            Params: WasmParams,
+            Results: WasmResults,
+        {
+            self.instance
+                .get_typed_func::<Params, Results>(&mut self.store, name)?
+                .call_async(&mut self.store, params)
+                .await
+        }
+    }
+
+    #[tokio::test]
+    async fn endless_loops_exhaust_fuel_budget() -> AnyResult<()> {
+        let engine: Engine = Engine::new(Config::new().async_support(true).consume_fuel(true))?;
+
+        let linker: LinkerWithSdk<TestContext> = LinkerWithSdk::new(
+            Linker::new(&engine),
+            Unavailable,
+            Unavailable,
+            MemoryKv::new(),
+            Unavailable,
+        )?;
+
+        let module: VerifiedModule = VerifiedModule::new(
+            &engine,
+            &module_binary(
+                r#"(module
+                    (memory (export "memory") 1)
+                    (func (export "entry") (loop $spin (br $spin))))"#,
+            ),
+        )?;
+
+        let mut instance: SdkInstance<TestContext> =
+            SdkInstance::new(&linker, &module, (), &InstanceConfig::default()).await?;
+
+        let (response_sender, response_receiver): (
+            ResponseSender,
+            OneshotReceiver<AnyResult<Response>>,
+        ) = oneshot_channel();
+
+        let state: InstanceState = instance
+            .execute(
+                None,
+                Vec::new(),
+                RequestMeta::default(),
+                None,
+                CpuBudget::Fuel(NonZeroU64::new(10_000).unwrap()),
+                response_sender,
+            )
+            .await?;
+
+        assert_eq!(state, InstanceState::Tainted);
+
+        let error: AnyError = response_receiver
+            .await?
+            .expect_err("Endless loop returned!");
+
+        assert!(matches!(
+            error.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::CpuBudgetExhausted)
+        ));
+
+        assert!(instance.metrics().fuel_consumed >= Some(10_000));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn peak_memory_is_reported_after_earlier_growth() -> AnyResult<()> {
+        const PAGE_BYTES: usize = 64 << 10;
+
+        let engine: Engine = Engine::new(Config::new().async_support(true))?;
+
+        let linker: LinkerWithSdk<TestContext> = LinkerWithSdk::new(
+            Linker::new(&engine),
+            Unavailable,
+            Unavailable,
+            MemoryKv::new(),
+            Unavailable,
+        )?;
+
+        // Grows memory by a page on the first execution only.
+        let module: VerifiedModule = VerifiedModule::new(
+            &engine,
+            &module_binary(
+                r#"(module
+                    (memory (export "memory") 1)
+                    (func (export "entry")
+                        (if (i32.eq (memory.size) (i32.const 1))
+                            (then (drop (memory.grow (i32.const 1)))))))"#,
+            ),
+        )?;
+
+        let mut instance: SdkInstance<TestContext> =
+            SdkInstance::new(&linker, &module, (), &InstanceConfig::default()).await?;
+
+        for growth_bytes in [PAGE_BYTES, 0] {
+            let (response_sender, _): (ResponseSender, OneshotReceiver<AnyResult<Response>>) =
+                oneshot_channel();
+
+            instance
+                .execute(
+                    None,
+                    Vec::new(),
+                    RequestMeta::default(),
+                    None,
+                    CpuBudget::Unlimited,
+                    response_sender,
+                )
+                .await?;
+
+            assert_eq!(instance.metrics().peak_memory_bytes, 2 * PAGE_BYTES);
+            assert_eq!(instance.metrics().memory_growth_bytes, growth_bytes);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn entry_points_are_recognized() {
+        assert!(is_entry_point("entry"));
+        assert!(is_entry_point("entry::create"));
+        assert!(!is_entry_point("entry::"));
+        assert!(!is_entry_point("entries"));
+        assert!(!is_entry_point("memory"));
+    }
+
+    fn log_keeper(max_records_per_request: u32, max_bytes_per_request: usize) -> LogKeeper {
+        LogKeeper {
+            policy: LogPolicy {
+                max_level: LevelFilter::INFO,
+                max_records_per_request,
+                max_bytes_per_request,
+            },
+            ..LogKeeper::default()
+        }
+    }
+
+    #[test]
+    fn log_keeper_filters_levels() {
+        let mut log_keeper: LogKeeper = log_keeper(16, 1024);
+
+        assert!(log_keeper.admit(Level::ERROR, 1));
+        assert!(log_keeper.admit(Level::INFO, 1));
+        assert!(!log_keeper.admit(Level::DEBUG, 1));
+        assert!(!log_keeper.admit(Level::TRACE, 1));
+
+        // Filtered records aren't counted as dropped.
+        assert_eq!(log_keeper.reset(), 0);
+    }
+
+    #[test]
+    fn log_keeper_caps_record_count() {
+        let mut log_keeper: LogKeeper = log_keeper(2, 1024);
+
+        assert!(log_keeper.admit(Level::INFO, 1));
+        assert!(log_keeper.admit(Level::INFO, 1));
+        assert!(!log_keeper.admit(Level::INFO, 1));
+        assert!(!log_keeper.admit(Level::ERROR, 0));
+
+        assert_eq!(log_keeper.reset(), 2);
+
+        assert!(log_keeper.admit(Level::INFO, 1));
+    }
+
+    #[test]
+    fn log_keeper_caps_bytes() {
+        let mut log_keeper: LogKeeper = log_keeper(16, 10);
+
+        assert!(log_keeper.admit(Level::INFO, 6));
+        assert!(!log_keeper.admit(Level::INFO, 5));
+        assert!(log_keeper.admit(Level::INFO, 4));
+        assert!(!log_keeper.admit(Level::INFO, 1));
+        assert!(!log_keeper.admit(Level::INFO, usize::MAX));
+
+        assert_eq!(log_keeper.reset(), 3);
+
+        assert!(log_keeper.admit(Level::INFO, 10));
     }
 }
@@ -0,0 +1,83 @@
+use tracing::{event, level_filters::LevelFilter, Level};
+
+/// Target under which modules' log records are emitted.
+pub const TARGET: &str = "lambda::module";
+
+/// Logging policy of a module. Records above the maximum level are discarded,
+/// as are records exceeding the per-request limits.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct LogPolicy {
+    pub max_level: LevelFilter,
+    pub max_records_per_request: u32,
+    pub max_bytes_per_request: usize,
+}
+
+impl Default for LogPolicy {
+    fn default() -> Self {
+        Self {
+            max_level: LevelFilter::INFO,
+            max_records_per_request: 128,
+            max_bytes_per_request: 64 << 10,
+        }
+    }
+}
+
+/// Converts level reported by modules, ordered from most to least severe
+/// starting at one.
+pub(crate) const fn level_from_code(code: u32) -> Option<Level> {
+    match code {
+        1 => Some(Level::ERROR),
+        2 => Some(Level::WARN),
+        3 => Some(Level::INFO),
+        4 => Some(Level::DEBUG),
+        5 => Some(Level::TRACE),
+        _ => None,
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct LogRecord<'r> {
+    pub module_id: &'r str,
+    pub username: Option<&'r str>,
+    pub target: &'r str,
+    pub message: &'r str,
+    pub fields: &'r [(String, String)],
+}
+
+pub(crate) fn emit(level: Level, record: &LogRecord<'_>) {
+    macro_rules! emit {
+        ($($level: ident)+, $fallback_level: ident) => {
+            match level {
+                $(
+                    Level::$level => emit!(@event $level),
+                )+
+                _ => emit!(@event $fallback_level),
+            }
+        };
+        (@event $level: ident) => {
+            event!(
+                target: TARGET,
+                Level::$level,
+                module = record.module_id,
+                user = record.username,
+                module_target = record.target,
+                fields = ?record.fields,
+                "{}",
+                record.message,
+            )
+        };
+    }
+
+    emit!(ERROR WARN INFO DEBUG, TRACE);
+}
+
+pub(crate) fn emit_dropped(module_id: &str, username: Option<&str>, dropped: u32) {
+    event!(
+        target: TARGET,
+        Level::WARN,
+        module = module_id,
+        user = username,
+        dropped,
+        "Module exceeded its per-request log limits; records were dropped!",
+    );
+}
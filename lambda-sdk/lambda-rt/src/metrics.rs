@@ -0,0 +1,94 @@
+use std::{
+    collections::BTreeMap,
+    mem::take,
+    time::{Duration, Instant},
+};
+
+use wasmtime::CallHook;
+
+/// Module host calls are attributed to when no SDK module claimed them, which
+/// only happens for WASI's functions.
+pub const WASI_HOST_MODULE: &str = "wasi";
+
+/// Metrics of a single execution of a module's entry point.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ExecutionMetrics {
+    /// Time from setting up the execution until the module returned.
+    pub wall_time: Duration,
+    /// Time spent in host calls, by module the called functions are imported
+    /// from, e.g. `sdk::kv`.
+    pub host_time: BTreeMap<&'static str, Duration>,
+    /// Fuel consumed, which is [`None`] unless the engine consumes fuel.
+    pub fuel_consumed: Option<u64>,
+    /// Size of the instance's linear memory once the execution ended, which
+    /// is its peak size, as linear memory never shrinks.
+    pub peak_memory_bytes: usize,
+    /// Number of bytes the instance's linear memory grew by during the
+    /// execution.
+    pub memory_growth_bytes: usize,
+    /// Length of the request's data.
+    pub bytes_in: u64,
+    /// Length of the response's data, whether buffered or streamed.
+    pub bytes_out: u64,
+    /// Number of outbound network requests sent or opened.
+    pub outbound_requests: u64,
+}
+
+impl ExecutionMetrics {
+    /// Total time spent in host calls.
+    #[must_use]
+    pub fn total_host_time(&self) -> Duration {
+        self.host_time.values().sum()
+    }
+}
+
+/// Collects metrics reported by host functions and call hooks during an
+/// execution.
+#[derive(Debug, Default)]
+pub(crate) struct MetricsKeeper {
+    metrics: ExecutionMetrics,
+    host_module: Option<&'static str>,
+    host_call_start: Option<Instant>,
+}
+
+impl MetricsKeeper {
+    pub(crate) fn attribute_host_call(&mut self, module: &'static str) {
+        self.host_module = Some(module);
+    }
+
+    pub(crate) fn on_call(&mut self, hook: CallHook) {
+        match hook {
+            CallHook::CallingHost => self.host_call_start = Some(Instant::now()),
+            CallHook::ReturningFromHost => {
+                if let Some(start) = self.host_call_start.take() {
+                    *self
+                        .metrics
+                        .host_time
+                        .entry(self.host_module.take().unwrap_or(WASI_HOST_MODULE))
+                        .or_default() += start.elapsed();
+                }
+            }
+            CallHook::CallingWasm | CallHook::ReturningFromWasm => {}
+        }
+    }
+
+    pub(crate) fn add_bytes_out(&mut self, length: usize) {
+        self.metrics.bytes_out = self
+            .metrics
+            .bytes_out
+            .saturating_add(length.try_into().unwrap_or(u64::MAX));
+    }
+
+    pub(crate) fn count_outbound_request(&mut self) {
+        self.metrics.outbound_requests += 1;
+    }
+
+    /// Takes metrics collected so far, starting collection anew.
+    pub(crate) fn take(&mut self) -> ExecutionMetrics {
+        self.host_module = None;
+
+        self.host_call_start = None;
+
+        take(&mut self.metrics)
+    }
+}
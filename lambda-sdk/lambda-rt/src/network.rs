@@ -0,0 +1,219 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    error::Error as StdError,
+    future::{ready, Future},
+    mem::take,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use anyhow::{anyhow, Context as _, Result as AnyResult};
+use hyper::client::connect::dns::Name;
+use reqwest::{
+    dns::{Addrs, Resolve, Resolving},
+    header::HeaderMap,
+    redirect::Policy as RedirectPolicy,
+    Body, Client, ClientBuilder, Method, Request, Response, Url,
+};
+
+use crate::{egress::PinnedAddresses, NetworkProvider, NetworkResponse};
+
+/// Network provider sending requests through shared [`reqwest`] clients.
+/// Requests to host names pinned by the egress check are sent through a
+/// client which resolves names only to the addresses pinned for them, so
+/// connections are kept alive and reused either way.
+#[derive(Debug, Clone)]
+pub struct ReqwestNetwork {
+    client: Client,
+    pinned_client: Client,
+    pinned_resolver: Arc<PinnedResolver>,
+}
+
+impl ReqwestNetwork {
+    /// # Errors
+    /// Error will occur when the clients can't be built, e.g. when the TLS
+    /// backend fails to initialize.
+    pub fn new() -> AnyResult<Self> {
+        let pinned_resolver: Arc<PinnedResolver> = Arc::default();
+
+        Ok(Self {
+            client: client_builder()
+                .build()
+                .context("Failed to build network client!")?,
+            pinned_client: client_builder()
+                .dns_resolver(pinned_resolver.clone())
+                .build()
+                .context("Failed to build network client for pinned addresses!")?,
+            pinned_resolver,
+        })
+    }
+}
+
+/// Clients must not follow redirects, as their targets aren't checked against
+/// modules' egress policies.
+fn client_builder() -> ClientBuilder {
+    Client::builder().redirect(RedirectPolicy::none())
+}
+
+/// Resolver answering only with the addresses most recently pinned for a host
+/// name, never resolving names itself. Every pinned address passed the egress
+/// check, thus so did those of connections kept alive by the client.
+#[derive(Debug, Default)]
+struct PinnedResolver {
+    addresses: Mutex<HashMap<String, Vec<SocketAddr>>>,
+}
+
+impl PinnedResolver {
+    /// Maximum number of host names pinned at once. Pins are only needed until
+    /// connecting, thus all of them are dropped once the limit is reached.
+    const MAX_PINNED_HOSTS: usize = 1024;
+
+    fn pin(&self, pinned_addresses: PinnedAddresses) {
+        let mut addresses: MutexGuard<'_, HashMap<String, Vec<SocketAddr>>> = self.addresses();
+
+        if Self::MAX_PINNED_HOSTS <= addresses.len()
+            && !addresses.contains_key(&pinned_addresses.host)
+        {
+            addresses.clear();
+        }
+
+        addresses.insert(pinned_addresses.host, pinned_addresses.addresses);
+    }
+
+    fn addresses(&self) -> MutexGuard<'_, HashMap<String, Vec<SocketAddr>>> {
+        self.addresses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Resolve for PinnedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let result: Result<Addrs, Box<dyn StdError + Send + Sync>> =
+            match self.addresses().get(name.as_str()) {
+                Some(addresses) => Ok(Box::new(addresses.clone().into_iter())),
+                None => Err(format!(r#"No addresses are pinned for host "{name}"!"#).into()),
+            };
+
+        Box::pin(ready(result))
+    }
+}
+
+impl NetworkProvider for ReqwestNetwork {
+    type Result<'r> = Pin<Box<dyn Future<Output = AnyResult<NetworkResponse>> + Send + 'r>>;
+
+    fn send_request(
+        &mut self,
+        request: Request,
+        pinned_addresses: Option<PinnedAddresses>,
+    ) -> Self::Result<'_> {
+        Box::pin(async move {
+            let client: &Client = match pinned_addresses {
+                None => &self.client,
+                Some(pinned_addresses) => {
+                    self.pinned_resolver.pin(pinned_addresses);
+
+                    &self.pinned_client
+                }
+            };
+
+            let response: Response = client.execute(request).await?;
+
+            let status_code: u16 = response.status().as_u16();
+
+            let headers: HeaderMap = response.headers().clone();
+
+            Ok(NetworkResponse::new(
+                status_code,
+                response
+                    .bytes()
+                    .await
+                    .context("Failed to fetch network response's data!")?
+                    .into(),
+            )
+            .with_headers(&headers))
+        })
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+}
+
+impl From<Request> for RecordedRequest {
+    fn from(request: Request) -> Self {
+        Self {
+            method: request.method().clone(),
+            url: request.url().clone(),
+            headers: request.headers().clone(),
+            body: request.body().and_then(Body::as_bytes).map(<[u8]>::to_vec),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Script {
+    responses: VecDeque<NetworkResponse>,
+    requests: Vec<RecordedRequest>,
+}
+
+/// In-memory network provider which answers requests with scripted responses,
+/// in order, and records every request it receives. Clones share the same
+/// script, so a handle can be kept for inspection after passing it to the
+/// linker.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedNetwork {
+    script: Arc<Mutex<Script>>,
+}
+
+impl ScriptedNetwork {
+    #[must_use]
+    pub fn new<Responses>(responses: Responses) -> Self
+    where
+        Responses: IntoIterator<Item = NetworkResponse>,
+    {
+        Self {
+            script: Arc::new(Mutex::new(Script {
+                responses: responses.into_iter().collect(),
+                requests: Vec::new(),
+            })),
+        }
+    }
+
+    pub fn push_response(&self, response: NetworkResponse) {
+        self.script().responses.push_back(response);
+    }
+
+    #[must_use]
+    pub fn take_requests(&self) -> Vec<RecordedRequest> {
+        take(&mut self.script().requests)
+    }
+
+    fn script(&self) -> MutexGuard<'_, Script> {
+        self.script.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl NetworkProvider for ScriptedNetwork {
+    type Result<'r> = Pin<Box<dyn Future<Output = AnyResult<NetworkResponse>> + Send + 'r>>;
+
+    fn send_request(&mut self, request: Request, _: Option<PinnedAddresses>) -> Self::Result<'_> {
+        Box::pin(async move {
+            let mut script: MutexGuard<'_, Script> = self.script();
+
+            let url: Url = request.url().clone();
+
+            script.requests.push(request.into());
+
+            script
+                .responses
+                .pop_front()
+                .ok_or_else(|| anyhow!(r#"No scripted response left for request to "{url}"!"#))
+        })
+    }
+}
@@ -0,0 +1,1143 @@
+use std::{
+    collections::VecDeque,
+    ffi::OsStr,
+    fs::{read, read_dir, write, DirEntry},
+    future::{ready, Future, Ready},
+    io::Result as IoResult,
+    mem::take,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context as _, Result as AnyResult};
+use reqwest::{
+    header::{HeaderName, HeaderValue},
+    Request as NetworkRequest,
+};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    spawn,
+    sync::oneshot::{channel as oneshot_channel, Receiver as OneshotReceiver},
+    task::{spawn_blocking, JoinHandle},
+};
+use tracing::warn;
+use wasmtime::{Engine, Linker};
+use zeroize::Zeroizing;
+
+use crate::{
+    crypto::VaultKey,
+    egress::PinnedAddresses,
+    invoke::{Invocation, InvokeError},
+    Context, CpuBudget, InstanceConfig, InvokeProvider, InvokedResponse, KvProvider, LinkerWithSdk,
+    NetworkProvider, NetworkResponse, RequestMeta, Response, ResponseBody, ResponseSender, SdkEnv,
+    SdkInstance, SdkUser, VaultProvider, VerifiedModule,
+};
+
+/// Configuration of recording an instance's executions. Each execution is
+/// written as a JSON file named after the module into the directory.
+///
+/// Recordings hold request data and everything the module received from the
+/// host, thus should be treated as sensitive even with secrets redacted.
+/// Functions imported from WASI are not recorded.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RecordConfig {
+    pub directory: PathBuf,
+    /// Whether fetched secrets, decrypted plaintext and credentials in headers,
+    /// e.g. the `Authorization` and `Cookie` headers, are recorded as opposed
+    /// to being redacted.
+    pub include_secrets: bool,
+    /// Maximum number of the module's recordings kept in the directory, with
+    /// further executions not being recorded.
+    pub max_recordings: Option<u64>,
+}
+
+/// Execution of a module's entry point, along with the outcome of every host
+/// call whose outcome doesn't depend solely on its inputs.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub module_id: String,
+    /// Handler executed, with [`None`] being the default entry point.
+    #[serde(default)]
+    pub handler: Option<String>,
+    pub data: Vec<u8>,
+    pub meta: RequestMeta,
+    pub sender: Option<String>,
+    pub calls: Vec<HostCall>,
+}
+
+impl Recording {
+    /// Reads recording written by a recording instance.
+    /// # Errors
+    /// Error will occur when file can't be read or isn't a valid recording.
+    pub fn read(path: &Path) -> AnyResult<Self> {
+        read(path)
+            .context("Failed to read recording!")
+            .and_then(|content: Vec<u8>| {
+                serde_json::from_slice(&content).context("Failed to parse recording!")
+            })
+    }
+
+    fn write(&self, path: &Path) -> AnyResult<()> {
+        write(path, serde_json::to_vec(self)?).map_err(Into::into)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct HostCall {
+    #[serde(flatten)]
+    pub inputs: CallInputs,
+    /// Output of the call, or message of the error it failed with.
+    pub outcome: Result<CallOutput, String>,
+}
+
+/// Inputs identifying a host call, which have to match for a recorded call
+/// to be replayed. Bodies of outbound requests are not part of the inputs,
+/// as they might still be written while the request is being sent.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "call", rename_all = "snake_case")]
+pub enum CallInputs {
+    FetchSecret {
+        identifier: String,
+    },
+    /// Cryptographic operation with a vault key, recorded in place of the
+    /// key, which is never recorded.
+    CryptoOperation {
+        operation: String,
+        identifier: String,
+        inputs: Vec<Vec<u8>>,
+    },
+    CheckEgress {
+        method: String,
+        url: String,
+    },
+    SendRequest {
+        method: String,
+        url: String,
+    },
+    KvGet {
+        key: Vec<u8>,
+    },
+    KvPut {
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    KvDelete {
+        key: Vec<u8>,
+    },
+    KvCompareAndSwap {
+        key: Vec<u8>,
+        expected: Option<Vec<u8>>,
+        new: Vec<u8>,
+    },
+    KvListKeys {
+        prefix: Vec<u8>,
+    },
+    Invoke {
+        module_id: String,
+        data: Vec<u8>,
+    },
+    Random {
+        length: usize,
+    },
+    WallClock,
+    Monotonic,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CallOutput {
+    Secret(Option<RecordedSecret>),
+    /// Egress denial code reported to the module, if any.
+    Denial(Option<u32>),
+    Response(RecordedResponse),
+    Value(Option<Vec<u8>>),
+    Done,
+    Flag(bool),
+    Keys(Vec<Vec<u8>>),
+    /// Invoked module's response, or invocation error code reported to the
+    /// module.
+    Invocation(Result<RecordedInvocation, u32>),
+    Bytes(Vec<u8>),
+    Nanos(u64),
+    /// Output of a cryptographic operation, or error code reported to the
+    /// module.
+    Crypto(Result<Vec<u8>, u32>),
+    /// Plaintext decrypted with a vault key, or error code reported to the
+    /// module.
+    Decrypted(Result<RecordedSecret, u32>),
+}
+
+/// Secret fetched from the vault, or plaintext decrypted with a vault key.
+/// Redacted secrets are replayed as zeroes.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedSecret {
+    Redacted { length: usize },
+    Plain(Vec<u8>),
+}
+
+impl RecordedSecret {
+    fn new(secret: &[u8], include_secrets: bool) -> Self {
+        if include_secrets {
+            Self::Plain(secret.to_vec())
+        } else {
+            Self::Redacted {
+                length: secret.len(),
+            }
+        }
+    }
+
+    fn into_secret(self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(match self {
+            Self::Redacted { length } => vec![0; length],
+            Self::Plain(secret) => secret,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status_code: u16,
+    pub headers: Vec<(String, Vec<u8>)>,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RecordedInvocation {
+    pub is_error: bool,
+    pub status_code: Option<u16>,
+    pub data: Vec<u8>,
+}
+
+/// Output of a host call which can be recorded and replayed.
+pub(crate) trait Recordable: Sized {
+    fn record(&self, include_secrets: bool) -> CallOutput;
+
+    /// Returns [`None`] when recorded output is of a different kind.
+    fn replay(output: CallOutput) -> Option<Self>;
+}
+
+impl Recordable for Option<Zeroizing<Vec<u8>>> {
+    fn record(&self, include_secrets: bool) -> CallOutput {
+        CallOutput::Secret(
+            self.as_ref()
+                .map(|secret: &Zeroizing<Vec<u8>>| RecordedSecret::new(secret, include_secrets)),
+        )
+    }
+
+    fn replay(output: CallOutput) -> Option<Self> {
+        let CallOutput::Secret(secret) = output else {
+            return None;
+        };
+
+        Some(secret.map(RecordedSecret::into_secret))
+    }
+}
+
+impl Recordable for Option<u32> {
+    fn record(&self, _: bool) -> CallOutput {
+        CallOutput::Denial(*self)
+    }
+
+    fn replay(output: CallOutput) -> Option<Self> {
+        if let CallOutput::Denial(denial) = output {
+            Some(denial)
+        } else {
+            None
+        }
+    }
+}
+
+impl Recordable for NetworkResponse {
+    fn record(&self, include_secrets: bool) -> CallOutput {
+        CallOutput::Response(RecordedResponse {
+            status_code: self.status_code,
+            headers: self
+                .headers
+                .iter()
+                .map(|(name, value): &(HeaderName, HeaderValue)| {
+                    (
+                        String::from(name.as_str()),
+                        if include_secrets || !is_sensitive_header(name.as_str()) {
+                            value.as_bytes().to_vec()
+                        } else {
+                            REDACTED_HEADER_VALUE.to_vec()
+                        },
+                    )
+                })
+                .collect(),
+            data: self.data.clone(),
+        })
+    }
+
+    fn replay(output: CallOutput) -> Option<Self> {
+        let CallOutput::Response(response) = output else {
+            return None;
+        };
+
+        Some(Self {
+            status_code: response.status_code,
+            headers: response
+                .headers
+                .into_iter()
+                .map(|(name, value): (String, Vec<u8>)| {
+                    Some((
+                        HeaderName::try_from(name).ok()?,
+                        HeaderValue::try_from(value).ok()?,
+                    ))
+                })
+                .collect::<Option<_>>()?,
+            data: response.data,
+        })
+    }
+}
+
+impl Recordable for Option<Vec<u8>> {
+    fn record(&self, _: bool) -> CallOutput {
+        CallOutput::Value(self.clone())
+    }
+
+    fn replay(output: CallOutput) -> Option<Self> {
+        if let CallOutput::Value(value) = output {
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
+
+impl Recordable for () {
+    fn record(&self, _: bool) -> CallOutput {
+        CallOutput::Done
+    }
+
+    fn replay(output: CallOutput) -> Option<Self> {
+        matches!(output, CallOutput::Done).then_some(())
+    }
+}
+
+impl Recordable for bool {
+    fn record(&self, _: bool) -> CallOutput {
+        CallOutput::Flag(*self)
+    }
+
+    fn replay(output: CallOutput) -> Option<Self> {
+        if let CallOutput::Flag(flag) = output {
+            Some(flag)
+        } else {
+            None
+        }
+    }
+}
+
+impl Recordable for Vec<Vec<u8>> {
+    fn record(&self, _: bool) -> CallOutput {
+        CallOutput::Keys(self.clone())
+    }
+
+    fn replay(output: CallOutput) -> Option<Self> {
+        if let CallOutput::Keys(keys) = output {
+            Some(keys)
+        } else {
+            None
+        }
+    }
+}
+
+impl Recordable for Result<InvokedResponse, u32> {
+    fn record(&self, _: bool) -> CallOutput {
+        CallOutput::Invocation(self.as_ref().map_err(|&error: &u32| error).map(
+            |response: &InvokedResponse| RecordedInvocation {
+                is_error: response.is_error,
+                status_code: response.status_code,
+                data: response.data.clone(),
+            },
+        ))
+    }
+
+    fn replay(output: CallOutput) -> Option<Self> {
+        let CallOutput::Invocation(invocation) = output else {
+            return None;
+        };
+
+        Some(
+            invocation.map(|response: RecordedInvocation| InvokedResponse {
+                is_error: response.is_error,
+                status_code: response.status_code,
+                data: response.data,
+            }),
+        )
+    }
+}
+
+impl Recordable for Vec<u8> {
+    fn record(&self, _: bool) -> CallOutput {
+        CallOutput::Bytes(self.clone())
+    }
+
+    fn replay(output: CallOutput) -> Option<Self> {
+        if let CallOutput::Bytes(bytes) = output {
+            Some(bytes)
+        } else {
+            None
+        }
+    }
+}
+
+impl Recordable for u64 {
+    fn record(&self, _: bool) -> CallOutput {
+        CallOutput::Nanos(*self)
+    }
+
+    fn replay(output: CallOutput) -> Option<Self> {
+        if let CallOutput::Nanos(nanos) = output {
+            Some(nanos)
+        } else {
+            None
+        }
+    }
+}
+
+impl Recordable for Result<Zeroizing<Vec<u8>>, u32> {
+    fn record(&self, _: bool) -> CallOutput {
+        CallOutput::Crypto(
+            self.as_ref()
+                .map(|output: &Zeroizing<Vec<u8>>| output.to_vec())
+                .map_err(|&error: &u32| error),
+        )
+    }
+
+    fn replay(output: CallOutput) -> Option<Self> {
+        if let CallOutput::Crypto(output) = output {
+            Some(output.map(Zeroizing::new))
+        } else {
+            None
+        }
+    }
+}
+
+/// Plaintext decrypted with a vault key, which is recorded like secrets
+/// fetched from the vault.
+#[derive(Debug)]
+pub(crate) struct Plaintext(pub(crate) Zeroizing<Vec<u8>>);
+
+impl Recordable for Result<Plaintext, u32> {
+    fn record(&self, include_secrets: bool) -> CallOutput {
+        CallOutput::Decrypted(
+            self.as_ref()
+                .map(|plaintext: &Plaintext| RecordedSecret::new(&plaintext.0, include_secrets))
+                .map_err(|&error: &u32| error),
+        )
+    }
+
+    fn replay(output: CallOutput) -> Option<Self> {
+        if let CallOutput::Decrypted(output) = output {
+            Some(output.map(|secret: RecordedSecret| Plaintext(secret.into_secret())))
+        } else {
+            None
+        }
+    }
+}
+
+/// Distinguishes recordings of executions finishing within the same
+/// nanosecond.
+static RECORDING_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Serializes writing recordings, so the maximum number of recordings can't be
+/// exceeded by executions finishing at once.
+static RECORDING_WRITES: Mutex<()> = Mutex::new(());
+
+/// Headers carrying credentials, redacted unless secrets are included.
+const SENSITIVE_HEADERS: [&str; 4] = [
+    "authorization",
+    "cookie",
+    "proxy-authorization",
+    "set-cookie",
+];
+
+const REDACTED_HEADER_VALUE: &[u8] = b"redacted";
+
+fn is_sensitive_header(name: &str) -> bool {
+    SENSITIVE_HEADERS
+        .iter()
+        .any(|sensitive: &&str| name.eq_ignore_ascii_case(sensitive))
+}
+
+/// Counts recordings of provided module within the directory.
+fn count_recordings(directory: &Path, module_id: &str) -> IoResult<u64> {
+    let prefix: String = format!("{module_id}.");
+
+    read_dir(directory)?.try_fold(0, |count: u64, entry: IoResult<DirEntry>| {
+        let is_recording: bool = entry?.file_name().to_str().is_some_and(|file_name: &str| {
+            file_name.starts_with(&prefix)
+                && Path::new(file_name).extension() == Some(OsStr::new("json"))
+        });
+
+        Ok(count + u64::from(is_recording))
+    })
+}
+
+/// Host calls of an instance, either being recorded or replayed.
+#[derive(Debug)]
+pub(crate) enum Tape {
+    Recording {
+        config: RecordConfig,
+        recording: Option<Box<Recording>>,
+    },
+    /// Recorded calls which weren't replayed yet.
+    Replaying(VecDeque<HostCall>),
+}
+
+impl Tape {
+    pub(crate) const fn recording(config: RecordConfig) -> Self {
+        Self::Recording {
+            config,
+            recording: None,
+        }
+    }
+
+    /// Starts recording execution of provided request. Credentials in its
+    /// headers are redacted unless secrets are included.
+    pub(crate) fn start(
+        &mut self,
+        module_id: &str,
+        handler: Option<&str>,
+        data: &[u8],
+        meta: &RequestMeta,
+        sender: Option<&str>,
+    ) {
+        if let Self::Recording { config, recording } = self {
+            let mut meta: RequestMeta = meta.clone();
+
+            if !config.include_secrets {
+                meta.headers
+                    .iter_mut()
+                    .filter(|(name, _): &&mut (String, Vec<u8>)| is_sensitive_header(name))
+                    .for_each(|(_, value): &mut (String, Vec<u8>)| {
+                        *value = REDACTED_HEADER_VALUE.to_vec();
+                    });
+            }
+
+            *recording = Some(Box::new(Recording {
+                module_id: String::from(module_id),
+                handler: handler.map(String::from),
+                data: data.to_vec(),
+                meta,
+                sender: sender.map(String::from),
+                calls: Vec::new(),
+            }));
+        }
+    }
+
+    /// Writes recording of the finished execution in the background, unless
+    /// the maximum number of recordings is reached, only logging failures.
+    pub(crate) fn finish(&mut self) {
+        let Self::Recording { config, recording } = self else {
+            return;
+        };
+
+        let Some(recording) = recording.take() else {
+            return;
+        };
+
+        let nanos: u128 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed: Duration| elapsed.as_nanos());
+
+        let path: PathBuf = config.directory.join(format!(
+            "{}.{nanos}.{}.json",
+            recording.module_id,
+            RECORDING_SEQUENCE.fetch_add(1, Ordering::Relaxed),
+        ));
+
+        let directory: PathBuf = config.directory.clone();
+
+        let max_recordings: Option<u64> = config.max_recordings;
+
+        // Safe to drop as failures are logged by the task itself.
+        drop(spawn_blocking(move || {
+            let _guard: MutexGuard<'_, ()> = RECORDING_WRITES
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+
+            if let Some(max_recordings) = max_recordings {
+                match count_recordings(&directory, &recording.module_id) {
+                    Ok(count) if count < max_recordings => {}
+                    Ok(_) => return,
+                    Err(error) => {
+                        warn!(
+                            directory = %directory.display(),
+                            "Failed to count recordings of module's executions! Error: {error:#}",
+                        );
+
+                        return;
+                    }
+                }
+            }
+
+            if let Err(error) = recording.write(&path) {
+                warn!(
+                    path = %path.display(),
+                    "Failed to write recording of module's execution! Error: {error:#}",
+                );
+            }
+        }));
+    }
+
+    /// Returns outcome of the earliest pending recorded call with the same
+    /// inputs when replaying, or [`None`] when recording.
+    pub(crate) fn replay<T>(&mut self, inputs: &CallInputs) -> Option<AnyResult<T>>
+    where
+        T: Recordable,
+    {
+        let Self::Replaying(calls) = self else {
+            return None;
+        };
+
+        let Some(index) = calls
+            .iter()
+            .position(|call: &HostCall| call.inputs == *inputs)
+        else {
+            return Some(Err(anyhow!(
+                "Execution diverged from recording, as no pending recorded call matches {inputs:?}!"
+            )));
+        };
+
+        let Some(call): Option<HostCall> = calls.remove(index) else {
+            unreachable!()
+        };
+
+        Some(match call.outcome {
+            Ok(output) => T::replay(output).ok_or_else(|| {
+                anyhow!("Recorded output of call {inputs:?} is of unexpected kind!")
+            }),
+            Err(error) => Err(anyhow!(error)),
+        })
+    }
+
+    pub(crate) fn record<T>(&mut self, inputs: CallInputs, outcome: &AnyResult<T>)
+    where
+        T: Recordable,
+    {
+        if let Self::Recording {
+            config,
+            recording: Some(recording),
+        } = self
+        {
+            recording.calls.push(HostCall {
+                inputs,
+                outcome: match outcome {
+                    Ok(output) => Ok(output.record(config.include_secrets)),
+                    Err(error) => Err(format!("{error:#}")),
+                },
+            });
+        }
+    }
+}
+
+/// Performs host call, recording its outcome when recording, or returns the
+/// recorded outcome when replaying.
+pub(crate) fn perform<T, F>(tape: &mut Option<Tape>, inputs: CallInputs, call: F) -> AnyResult<T>
+where
+    T: Recordable,
+    F: FnOnce() -> AnyResult<T>,
+{
+    let Some(tape) = tape else {
+        return call();
+    };
+
+    if let Some(outcome) = tape.replay(&inputs) {
+        return outcome;
+    }
+
+    let outcome: AnyResult<T> = call();
+
+    tape.record(inputs, &outcome);
+
+    outcome
+}
+
+/// Asynchronous counterpart of [`perform`].
+pub(crate) async fn perform_async<T, F, Fut>(
+    tape: &mut Option<Tape>,
+    inputs: CallInputs,
+    call: F,
+) -> AnyResult<T>
+where
+    T: Recordable,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = AnyResult<T>>,
+{
+    let Some(tape) = tape else {
+        return call().await;
+    };
+
+    if let Some(outcome) = tape.replay(&inputs) {
+        return outcome;
+    }
+
+    let outcome: AnyResult<T> = call().await;
+
+    tape.record(inputs, &outcome);
+
+    outcome
+}
+
+/// Provider standing in for all providers during replay, where host calls are
+/// answered from the recording instead.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Unavailable;
+
+impl VaultProvider for Unavailable {
+    type Result<'r> = Ready<AnyResult<Option<Zeroizing<Vec<u8>>>>>;
+
+    type KeyResult<'r> = Ready<AnyResult<Option<VaultKey>>>;
+
+    fn fetch_secret(&mut self, _: String) -> Self::Result<'_> {
+        ready(Err(anyhow!("Vault is unavailable during replay!")))
+    }
+
+    fn fetch_key(&mut self, _: String) -> Self::KeyResult<'_> {
+        ready(Err(anyhow!("Vault is unavailable during replay!")))
+    }
+}
+
+impl NetworkProvider for Unavailable {
+    type Result<'r> = Ready<AnyResult<NetworkResponse>>;
+
+    fn send_request(&mut self, _: NetworkRequest, _: Option<PinnedAddresses>) -> Self::Result<'_> {
+        ready(Err(anyhow!("Network is unavailable during replay!")))
+    }
+}
+
+impl KvProvider for Unavailable {
+    type Result<'r, T>
+        = Ready<AnyResult<T>>
+    where
+        T: Send + 'r;
+
+    fn get(&mut self, _: String, _: Vec<u8>) -> Self::Result<'_, Option<Vec<u8>>> {
+        ready(Err(anyhow!(
+            "Key-value storage is unavailable during replay!"
+        )))
+    }
+
+    fn put(&mut self, _: String, _: Vec<u8>, _: Vec<u8>) -> Self::Result<'_, ()> {
+        ready(Err(anyhow!(
+            "Key-value storage is unavailable during replay!"
+        )))
+    }
+
+    fn delete(&mut self, _: String, _: Vec<u8>) -> Self::Result<'_, bool> {
+        ready(Err(anyhow!(
+            "Key-value storage is unavailable during replay!"
+        )))
+    }
+
+    fn compare_and_swap(
+        &mut self,
+        _: String,
+        _: Vec<u8>,
+        _: Option<Vec<u8>>,
+        _: Vec<u8>,
+    ) -> Self::Result<'_, bool> {
+        ready(Err(anyhow!(
+            "Key-value storage is unavailable during replay!"
+        )))
+    }
+
+    fn list_keys(&mut self, _: String, _: Vec<u8>, _: usize) -> Self::Result<'_, Vec<Vec<u8>>> {
+        ready(Err(anyhow!(
+            "Key-value storage is unavailable during replay!"
+        )))
+    }
+}
+
+impl InvokeProvider for Unavailable {
+    type Result<'r> = Ready<AnyResult<Result<Response, InvokeError>>>;
+
+    fn invoke(&mut self, _: Invocation) -> Self::Result<'_> {
+        ready(Err(anyhow!(
+            "Invoking modules is unavailable during replay!"
+        )))
+    }
+}
+
+/// Context of an instance replaying recorded host calls, constructed from the
+/// recording's calls.
+#[derive(Debug)]
+pub struct ReplayContext {
+    env: SdkEnv<Unavailable, Unavailable, Unavailable, Unavailable>,
+    sender: Option<SdkUser>,
+}
+
+impl Context for ReplayContext {
+    type ConstructorContext = Vec<HostCall>;
+
+    type Vault = Unavailable;
+
+    type Network = Unavailable;
+
+    type Kv = Unavailable;
+
+    type Invoker = Unavailable;
+
+    type User = SdkUser;
+
+    fn with_providers_and_context(
+        vault: Unavailable,
+        network: Unavailable,
+        kv: Unavailable,
+        invoker: Unavailable,
+        calls: Vec<HostCall>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        let mut env: SdkEnv<Unavailable, Unavailable, Unavailable, Unavailable> =
+            SdkEnv::new(vault, network, kv, invoker);
+
+        env.tape = Some(Tape::Replaying(calls.into()));
+
+        Self { env, sender: None }
+    }
+
+    fn sdk(&self) -> &SdkEnv<Unavailable, Unavailable, Unavailable, Unavailable> {
+        &self.env
+    }
+
+    fn sdk_mut(&mut self) -> &mut SdkEnv<Unavailable, Unavailable, Unavailable, Unavailable> {
+        &mut self.env
+    }
+
+    fn sender(&self) -> Option<&SdkUser> {
+        self.sender.as_ref()
+    }
+
+    fn set_sender(&mut self, sender: SdkUser) {
+        self.sender = Some(sender);
+    }
+
+    fn clear_sender(&mut self) {
+        self.sender = None;
+    }
+}
+
+/// Result of replaying a recording. Response's body is always buffered.
+#[derive(Debug)]
+pub struct Replay {
+    pub response: Response,
+    /// Recorded calls the module didn't make during replay.
+    pub unreplayed_calls: Vec<HostCall>,
+}
+
+/// Executes module for the recorded request, answering host calls from the
+/// recording without reaching any provider. Recording is ignored, as is the
+/// CPU budget, while other configuration should match the recorded instance's.
+/// # Errors
+/// Error will occur when instance can't be created, execution fails or
+/// diverges from the recording.
+pub async fn replay(
+    engine: &Engine,
+    module: &VerifiedModule,
+    recording: Recording,
+    config: &InstanceConfig,
+) -> AnyResult<Replay> {
+    let linker: LinkerWithSdk<ReplayContext> = LinkerWithSdk::new(
+        Linker::new(engine),
+        Unavailable,
+        Unavailable,
+        Unavailable,
+        Unavailable,
+    )?;
+
+    let mut instance: SdkInstance<ReplayContext> = SdkInstance::consuming_new(
+        linker,
+        module,
+        recording.calls,
+        &InstanceConfig {
+            record: None,
+            ..config.clone()
+        },
+    )
+    .await?;
+
+    let (response_sender, response_receiver): (ResponseSender, OneshotReceiver<_>) =
+        oneshot_channel();
+
+    // Collected concurrently, as streamed responses are only consumed while
+    // the module is still executing.
+    let response: JoinHandle<AnyResult<Response>> = spawn(async move {
+        let response: Response = response_receiver
+            .await
+            .context("Module's execution was aborted!")??;
+
+        let data: Vec<u8> = match response.body {
+            ResponseBody::Buffered(data) => data,
+            ResponseBody::Streamed(mut chunks) => {
+                let mut data: Vec<u8> = Vec::new();
+
+                while let Some(chunk) = chunks.recv().await {
+                    data.extend_from_slice(&chunk);
+                }
+
+                data
+            }
+        };
+
+        Ok(Response {
+            body: ResponseBody::Buffered(data),
+            ..response
+        })
+    });
+
+    instance
+        .execute(
+            recording.handler.as_deref(),
+            recording.data,
+            recording.meta,
+            recording.sender.map(SdkUser::new),
+            CpuBudget::Unlimited,
+            response_sender,
+        )
+        .await?;
+
+    let response: Response = response
+        .await
+        .context("Task collecting response failed!")??;
+
+    let unreplayed_calls: Vec<HostCall> = match &mut instance.store.data_mut().env.tape {
+        Some(Tape::Replaying(calls)) => take(calls).into(),
+        _ => Vec::new(),
+    };
+
+    Ok(Replay {
+        response,
+        unreplayed_calls,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, fs::remove_file, path::PathBuf, process};
+
+    use anyhow::{anyhow, Result as AnyResult};
+
+    use zeroize::Zeroizing;
+
+    use super::{
+        perform, CallInputs, CallOutput, Plaintext, RecordConfig, RecordedSecret, Recording, Tape,
+        REDACTED_HEADER_VALUE,
+    };
+    use crate::RequestMeta;
+
+    fn start_recording(include_secrets: bool, meta: &RequestMeta) -> Tape {
+        let mut tape: Tape = Tape::recording(RecordConfig {
+            directory: temp_dir(),
+            include_secrets,
+            max_recordings: None,
+        });
+
+        tape.start("module", Some("handler"), b"data", meta, Some("user"));
+
+        tape
+    }
+
+    fn take_recording(tape: Option<Tape>) -> Recording {
+        let Some(Tape::Recording {
+            recording: Some(recording),
+            ..
+        }) = tape
+        else {
+            panic!("Tape holds no recording!");
+        };
+
+        *recording
+    }
+
+    fn kv_get(key: &[u8]) -> CallInputs {
+        CallInputs::KvGet { key: key.to_vec() }
+    }
+
+    fn not_performed<T>() -> AnyResult<T> {
+        panic!("Replayed call was performed!")
+    }
+
+    #[test]
+    fn recording_is_replayed_after_being_read() -> AnyResult<()> {
+        let mut tape: Option<Tape> = Some(start_recording(false, &RequestMeta::default()));
+
+        perform(&mut tape, kv_get(b"a"), || Ok(Some(b"1".to_vec())))?;
+        perform(&mut tape, CallInputs::Random { length: 4 }, || {
+            Ok(vec![1, 2, 3, 4])
+        })?;
+        perform::<(), _>(
+            &mut tape,
+            CallInputs::KvDelete { key: b"a".to_vec() },
+            || Err(anyhow!("Connection lost!")),
+        )
+        .unwrap_err();
+
+        let recording: Recording = take_recording(tape);
+
+        let path: PathBuf =
+            temp_dir().join(format!("lambda-rt-record-test.{}.json", process::id()));
+
+        recording.write(&path)?;
+
+        let read: AnyResult<Recording> = Recording::read(&path);
+
+        remove_file(&path)?;
+
+        let read: Recording = read?;
+
+        assert_eq!(read, recording);
+        assert_eq!(read.handler.as_deref(), Some("handler"));
+
+        let mut tape: Option<Tape> = Some(Tape::Replaying(read.calls.into()));
+
+        // Calls with differing inputs are matched regardless of their order.
+        assert_eq!(
+            perform::<Vec<u8>, _>(&mut tape, CallInputs::Random { length: 4 }, not_performed)?,
+            vec![1, 2, 3, 4],
+        );
+        assert_eq!(
+            perform::<Option<Vec<u8>>, _>(&mut tape, kv_get(b"a"), not_performed)?,
+            Some(b"1".to_vec()),
+        );
+        assert_eq!(
+            perform::<(), _>(
+                &mut tape,
+                CallInputs::KvDelete { key: b"a".to_vec() },
+                not_performed
+            )
+            .unwrap_err()
+            .to_string(),
+            "Connection lost!",
+        );
+
+        assert!(matches!(tape, Some(Tape::Replaying(calls)) if calls.is_empty()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn replay_follows_recorded_order_and_detects_divergence() -> AnyResult<()> {
+        let mut tape: Option<Tape> = Some(start_recording(false, &RequestMeta::default()));
+
+        perform(&mut tape, kv_get(b"a"), || Ok(Some(b"1".to_vec())))?;
+        perform(&mut tape, kv_get(b"a"), || Ok(Some(b"2".to_vec())))?;
+
+        let mut tape: Option<Tape> = Some(Tape::Replaying(take_recording(tape).calls.into()));
+
+        // Calls with equal inputs are replayed in the order they were recorded.
+        assert_eq!(
+            perform::<Option<Vec<u8>>, _>(&mut tape, kv_get(b"a"), not_performed)?,
+            Some(b"1".to_vec()),
+        );
+
+        // Outputs of a different kind are rejected.
+        assert!(perform::<bool, _>(&mut tape, kv_get(b"a"), not_performed)
+            .unwrap_err()
+            .to_string()
+            .contains("unexpected kind"));
+
+        // Calls which weren't recorded, or were already replayed, diverge.
+        assert!(
+            perform::<Option<Vec<u8>>, _>(&mut tape, kv_get(b"a"), not_performed)
+                .unwrap_err()
+                .to_string()
+                .contains("diverged")
+        );
+        assert!(
+            perform::<Option<Vec<u8>>, _>(&mut tape, kv_get(b"b"), not_performed)
+                .unwrap_err()
+                .to_string()
+                .contains("diverged")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sensitive_headers_are_redacted_unless_secrets_are_included() {
+        let meta: RequestMeta = RequestMeta {
+            headers: vec![
+                (String::from("Authorization"), b"Bearer token".to_vec()),
+                (String::from("cookie"), b"session=1".to_vec()),
+                (String::from("Accept"), b"*/*".to_vec()),
+            ],
+            ..RequestMeta::default()
+        };
+
+        assert_eq!(
+            take_recording(Some(start_recording(false, &meta)))
+                .meta
+                .headers,
+            vec![
+                (
+                    String::from("Authorization"),
+                    REDACTED_HEADER_VALUE.to_vec()
+                ),
+                (String::from("cookie"), REDACTED_HEADER_VALUE.to_vec()),
+                (String::from("Accept"), b"*/*".to_vec()),
+            ],
+        );
+
+        assert_eq!(
+            take_recording(Some(start_recording(true, &meta))).meta,
+            meta
+        );
+    }
+
+    #[test]
+    fn decrypted_plaintext_is_redacted_unless_secrets_are_included() -> AnyResult<()> {
+        let decrypt = || CallInputs::CryptoOperation {
+            operation: String::from("aes256_gcm_decrypt"),
+            identifier: String::from("key"),
+            inputs: vec![b"ciphertext".to_vec()],
+        };
+
+        let record = |include_secrets: bool| -> AnyResult<Recording> {
+            let mut tape: Option<Tape> =
+                Some(start_recording(include_secrets, &RequestMeta::default()));
+
+            let _: Result<Plaintext, u32> = perform(&mut tape, decrypt(), || {
+                Ok(Ok(Plaintext(Zeroizing::new(b"plaintext".to_vec()))))
+            })?;
+
+            Ok(take_recording(tape))
+        };
+
+        let redacted: Recording = record(false)?;
+
+        assert_eq!(
+            redacted.calls[0].outcome,
+            Ok(CallOutput::Decrypted(Ok(RecordedSecret::Redacted {
+                length: 9
+            }))),
+        );
+        assert_eq!(
+            record(true)?.calls[0].outcome,
+            Ok(CallOutput::Decrypted(Ok(RecordedSecret::Plain(
+                b"plaintext".to_vec()
+            )))),
+        );
+
+        // Redacted plaintext is replayed as zeroes of the same length.
+        let mut tape: Option<Tape> = Some(Tape::Replaying(redacted.calls.into()));
+
+        let replayed: Result<Plaintext, u32> = perform(&mut tape, decrypt(), not_performed)?;
+
+        assert_eq!(
+            replayed.map(|plaintext: Plaintext| plaintext.0.to_vec()),
+            Ok(vec![0; 9])
+        );
+
+        Ok(())
+    }
+}
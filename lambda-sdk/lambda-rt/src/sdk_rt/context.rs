@@ -1,11 +1,10 @@
 use anyhow::Result as AnyResult;
-use wasmtime::Linker;
 
-use crate::Context;
+use crate::{sdk_rt::linker::HostLinker, Context};
 
-const MODULE: &str = "sdk::io";
+const MODULE: &str = "sdk::context";
 
-pub fn link_rt<Ctx>(linker: &mut Linker<Ctx>) -> AnyResult<()>
+pub(super) fn link_rt<Ctx>(linker: &mut HostLinker<'_, Ctx>) -> AnyResult<()>
 where
     Ctx: Context,
 {
@@ -35,24 +34,20 @@ mod implementation {
 
     use crate::{
         sdk_rt::utils::{self, NeverError, WasmUsize},
-        Context, SdkEnv, User, INIT_ID,
+        Context, User,
     };
 
+    /// Returns length of sender's username, with zero meaning that there is
+    /// no sender.
     // ALLOW: Required by "wasmtime" API.
     #[allow(clippy::needless_pass_by_value)]
-    pub(super) fn sender_username_length<Ctx>(mut env: Caller<'_, Ctx>) -> u64
+    pub(super) fn sender_username_length<Ctx>(env: Caller<'_, Ctx>) -> AnyResult<u64>
     where
         Ctx: Context,
     {
-        let ctx: &mut SdkEnv<Ctx::Vault> = env.data_mut().sdk_mut();
-
-        ctx.request_reader_id = if let Some(id) = ctx.request_reader_id.checked_add(1) {
-            id
-        } else {
-            INIT_ID
-        };
-
-        ctx.request_reader_id.get()
+        env.data().sender().map_or(Ok(0), |user: &Ctx::User| {
+            u64::from_usize(user.username().len())
+        })
     }
 
     pub(super) fn sender_username<Ctx, Usize>(
@@ -72,12 +67,12 @@ mod implementation {
             |ctx: &Ctx| -> NeverError<_> {
                 Ok(ctx
                     .sender()
-                    .and_then(|user: &Ctx::User| user.username().as_bytes().get(..offset))
+                    .and_then(|user: &Ctx::User| user.username().as_bytes().get(offset..))
                     .unwrap_or(&[]))
             },
             buffer_ptr,
             buffer_length,
         )
-        .context("Couldn't write request's data to memory!")
+        .context("Couldn't write sender's username to memory!")
     }
 }
@@ -0,0 +1,477 @@
+use anyhow::Result as AnyResult;
+
+use crate::{sdk_rt::linker::HostLinker, Context};
+
+const MODULE: &str = "sdk::crypto";
+
+pub(super) fn link_rt<Ctx>(linker: &mut HostLinker<'_, Ctx>) -> AnyResult<()>
+where
+    Ctx: Context,
+{
+    linker.func_wrap3_async(
+        MODULE,
+        "ed25519_public_key~32",
+        implementation::ed25519_public_key::<_, u32>,
+    )?;
+    linker.func_wrap3_async(
+        MODULE,
+        "ed25519_public_key~64",
+        implementation::ed25519_public_key::<_, u64>,
+    )?;
+
+    linker.func_wrap5_async(
+        MODULE,
+        "ed25519_sign~32",
+        implementation::ed25519_sign::<_, u32>,
+    )?;
+    linker.func_wrap5_async(
+        MODULE,
+        "ed25519_sign~64",
+        implementation::ed25519_sign::<_, u64>,
+    )?;
+
+    linker.func_wrap5_async(
+        MODULE,
+        "ed25519_verify~32",
+        implementation::ed25519_verify::<_, u32>,
+    )?;
+    linker.func_wrap5_async(
+        MODULE,
+        "ed25519_verify~64",
+        implementation::ed25519_verify::<_, u64>,
+    )?;
+
+    linker.func_wrap5_async(
+        MODULE,
+        "hmac_sha256~32",
+        implementation::hmac_sha256::<_, u32>,
+    )?;
+    linker.func_wrap5_async(
+        MODULE,
+        "hmac_sha256~64",
+        implementation::hmac_sha256::<_, u64>,
+    )?;
+
+    linker.func_wrap8_async(
+        MODULE,
+        "aes256_gcm_encrypt~32",
+        implementation::aes256_gcm_encrypt::<_, u32>,
+    )?;
+    linker.func_wrap8_async(
+        MODULE,
+        "aes256_gcm_encrypt~64",
+        implementation::aes256_gcm_encrypt::<_, u64>,
+    )?;
+
+    linker.func_wrap8_async(
+        MODULE,
+        "aes256_gcm_decrypt~32",
+        implementation::aes256_gcm_decrypt::<_, u32>,
+    )?;
+    linker.func_wrap8_async(
+        MODULE,
+        "aes256_gcm_decrypt~64",
+        implementation::aes256_gcm_decrypt::<_, u64>,
+    )?;
+
+    linker.func_wrap(MODULE, "sha256~32", implementation::sha256::<_, u32>)?;
+    linker.func_wrap(MODULE, "sha256~64", implementation::sha256::<_, u64>)?;
+
+    linker.func_wrap(MODULE, "sha512~32", implementation::sha512::<_, u32>)?;
+    linker.func_wrap(MODULE, "sha512~64", implementation::sha512::<_, u64>)?;
+
+    Ok(())
+}
+
+mod implementation {
+    use std::future::Future;
+
+    use anyhow::{Context as _, Result as AnyResult};
+    use sha2::{Digest, Sha256, Sha512};
+    use wasmtime::{Caller, Memory};
+    use zeroize::Zeroizing;
+
+    use crate::{
+        crypto::{self, CryptoError, VaultKey, AES256_GCM_NONCE_LENGTH, ED25519_SIGNATURE_LENGTH},
+        record::{self, CallInputs, Plaintext, Recordable},
+        sdk_rt::utils::{self, WasmUsize},
+        Context, SdkEnv, VaultProvider,
+    };
+
+    /// Performs operation with the key stored in the vault under provided
+    /// identifier, returning its output or the error code reported to the
+    /// module. Output is recorded in place of the key, so replay never needs
+    /// the vault.
+    async fn with_key<Ctx, Usize, T, F>(
+        env: &mut Caller<'_, Ctx>,
+        identifier_ptr: Usize,
+        identifier_length: Usize,
+        operation: &str,
+        inputs: Vec<Vec<u8>>,
+        perform: F,
+    ) -> AnyResult<Result<T, u32>>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+        T: Send,
+        Result<T, u32>: Recordable,
+        F: FnOnce(&VaultKey) -> Result<T, CryptoError> + Send,
+    {
+        let identifier: String = String::from_utf8(utils::read_from_memory_to_buffer(
+            env,
+            identifier_ptr,
+            identifier_length,
+        )?)?;
+
+        let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+            env.data_mut().sdk_mut();
+
+        let vault: &mut Ctx::Vault = &mut sdk.vault_keeper.vault;
+
+        record::perform_async(
+            &mut sdk.tape,
+            CallInputs::CryptoOperation {
+                operation: operation.into(),
+                identifier: identifier.clone(),
+                inputs,
+            },
+            || async move {
+                let key: Option<VaultKey> = vault
+                    .fetch_key(identifier)
+                    .await
+                    .context("Failed to fetch key from vault provider!")?;
+
+                Ok(key
+                    .map_or(Err(CryptoError::NoSuchKey), |key: VaultKey| perform(&key))
+                    .map_err(|error: CryptoError| error as u32))
+            },
+        )
+        .await
+    }
+
+    fn to_output<const LENGTH: usize>(output: [u8; LENGTH]) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(output.to_vec())
+    }
+
+    fn read_array<Ctx, Usize, const LENGTH: usize>(
+        env: &mut Caller<'_, Ctx>,
+        buffer_ptr: Usize,
+    ) -> AnyResult<[u8; LENGTH]>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        let memory: Memory = utils::get_memory(env)?;
+
+        let mut buffer: [u8; LENGTH] = [0; LENGTH];
+
+        memory
+            .read(env, buffer_ptr.into_usize()?, &mut buffer)
+            .context("Couldn't read data from memory!")?;
+
+        Ok(buffer)
+    }
+
+    fn write_to_memory<Ctx, Usize>(
+        env: &mut Caller<'_, Ctx>,
+        buffer_ptr: Usize,
+        data: &[u8],
+    ) -> AnyResult<()>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        let memory: Memory = utils::get_memory(env)?;
+
+        memory
+            .write(env, buffer_ptr.into_usize()?, data)
+            .context("Couldn't write data to memory!")
+    }
+
+    /// Writes output of a successful operation to provided buffer, returning
+    /// the error code reported to the module.
+    fn finish<Ctx, Usize>(
+        env: &mut Caller<'_, Ctx>,
+        output_ptr: Usize,
+        result: Result<Zeroizing<Vec<u8>>, u32>,
+    ) -> AnyResult<u32>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        match result {
+            Ok(output) => write_to_memory(env, output_ptr, &output).map(|()| 0),
+            Err(error) => Ok(error),
+        }
+    }
+
+    /// Writes public key of the key to provided buffer of 32 bytes.
+    pub(super) fn ed25519_public_key<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        key_ptr: Usize,
+        key_length: Usize,
+        public_key_ptr: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<u32>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            let result: Result<Zeroizing<Vec<u8>>, u32> = with_key(
+                &mut env,
+                key_ptr,
+                key_length,
+                "ed25519_public_key",
+                Vec::new(),
+                |key: &VaultKey| crypto::ed25519_public_key(key).map(to_output),
+            )
+            .await?;
+
+            finish(&mut env, public_key_ptr, result)
+        })
+    }
+
+    /// Writes signature to provided buffer of 64 bytes.
+    pub(super) fn ed25519_sign<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        key_ptr: Usize,
+        key_length: Usize,
+        message_ptr: Usize,
+        message_length: Usize,
+        signature_ptr: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<u32>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            let message: Vec<u8> =
+                utils::read_from_memory_to_buffer(&mut env, message_ptr, message_length)
+                    .context("Couldn't read message from memory!")?;
+
+            let result: Result<Zeroizing<Vec<u8>>, u32> = with_key(
+                &mut env,
+                key_ptr,
+                key_length,
+                "ed25519_sign",
+                vec![message.clone()],
+                |key: &VaultKey| crypto::ed25519_sign(key, &message).map(to_output),
+            )
+            .await?;
+
+            finish(&mut env, signature_ptr, result)
+        })
+    }
+
+    /// Reads signature from provided buffer of 64 bytes.
+    pub(super) fn ed25519_verify<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        key_ptr: Usize,
+        key_length: Usize,
+        message_ptr: Usize,
+        message_length: Usize,
+        signature_ptr: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<u32>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            let message: Vec<u8> =
+                utils::read_from_memory_to_buffer(&mut env, message_ptr, message_length)
+                    .context("Couldn't read message from memory!")?;
+
+            let signature: [u8; ED25519_SIGNATURE_LENGTH] =
+                read_array(&mut env, signature_ptr).context("Couldn't read signature!")?;
+
+            let result: Result<Zeroizing<Vec<u8>>, u32> = with_key(
+                &mut env,
+                key_ptr,
+                key_length,
+                "ed25519_verify",
+                vec![message.clone(), signature.to_vec()],
+                |key: &VaultKey| {
+                    crypto::ed25519_verify(key, &message, &signature)
+                        .map(|()| Zeroizing::new(Vec::new()))
+                },
+            )
+            .await?;
+
+            Ok(result.err().unwrap_or(0))
+        })
+    }
+
+    /// Writes MAC to provided buffer of 32 bytes.
+    pub(super) fn hmac_sha256<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        key_ptr: Usize,
+        key_length: Usize,
+        message_ptr: Usize,
+        message_length: Usize,
+        mac_ptr: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<u32>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            let message: Vec<u8> =
+                utils::read_from_memory_to_buffer(&mut env, message_ptr, message_length)
+                    .context("Couldn't read message from memory!")?;
+
+            let result: Result<Zeroizing<Vec<u8>>, u32> = with_key(
+                &mut env,
+                key_ptr,
+                key_length,
+                "hmac_sha256",
+                vec![message.clone()],
+                |key: &VaultKey| crypto::hmac_sha256(key, &message).map(to_output),
+            )
+            .await?;
+
+            finish(&mut env, mac_ptr, result)
+        })
+    }
+
+    /// Reads nonce from provided buffer of 12 bytes and writes ciphertext,
+    /// followed by the 16-byte authentication tag, to provided buffer.
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn aes256_gcm_encrypt<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        key_ptr: Usize,
+        key_length: Usize,
+        nonce_ptr: Usize,
+        associated_data_ptr: Usize,
+        associated_data_length: Usize,
+        plaintext_ptr: Usize,
+        plaintext_length: Usize,
+        ciphertext_ptr: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<u32>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            let nonce: [u8; AES256_GCM_NONCE_LENGTH] =
+                read_array(&mut env, nonce_ptr).context("Couldn't read nonce!")?;
+
+            let associated_data: Vec<u8> = utils::read_from_memory_to_buffer(
+                &mut env,
+                associated_data_ptr,
+                associated_data_length,
+            )
+            .context("Couldn't read associated data from memory!")?;
+
+            let plaintext: Vec<u8> =
+                utils::read_from_memory_to_buffer(&mut env, plaintext_ptr, plaintext_length)
+                    .context("Couldn't read plaintext from memory!")?;
+
+            let result: Result<Zeroizing<Vec<u8>>, u32> = with_key(
+                &mut env,
+                key_ptr,
+                key_length,
+                "aes256_gcm_encrypt",
+                vec![nonce.to_vec(), associated_data.clone(), plaintext.clone()],
+                |key: &VaultKey| {
+                    crypto::aes256_gcm_encrypt(key, &nonce, &associated_data, &plaintext)
+                        .map(Zeroizing::new)
+                },
+            )
+            .await?;
+
+            finish(&mut env, ciphertext_ptr, result)
+        })
+    }
+
+    /// Reads nonce from provided buffer of 12 bytes and ciphertext, followed
+    /// by the 16-byte authentication tag, from provided buffer. Writes
+    /// plaintext to provided buffer only when the ciphertext is authentic.
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn aes256_gcm_decrypt<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        key_ptr: Usize,
+        key_length: Usize,
+        nonce_ptr: Usize,
+        associated_data_ptr: Usize,
+        associated_data_length: Usize,
+        ciphertext_ptr: Usize,
+        ciphertext_length: Usize,
+        plaintext_ptr: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<u32>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            let nonce: [u8; AES256_GCM_NONCE_LENGTH] =
+                read_array(&mut env, nonce_ptr).context("Couldn't read nonce!")?;
+
+            let associated_data: Vec<u8> = utils::read_from_memory_to_buffer(
+                &mut env,
+                associated_data_ptr,
+                associated_data_length,
+            )
+            .context("Couldn't read associated data from memory!")?;
+
+            let ciphertext: Vec<u8> =
+                utils::read_from_memory_to_buffer(&mut env, ciphertext_ptr, ciphertext_length)
+                    .context("Couldn't read ciphertext from memory!")?;
+
+            // Plaintext is recorded like secrets, thus redacted by default.
+            let result: Result<Plaintext, u32> = with_key(
+                &mut env,
+                key_ptr,
+                key_length,
+                "aes256_gcm_decrypt",
+                vec![nonce.to_vec(), associated_data.clone(), ciphertext.clone()],
+                |key: &VaultKey| {
+                    crypto::aes256_gcm_decrypt(key, &nonce, &associated_data, &ciphertext)
+                        .map(Plaintext)
+                },
+            )
+            .await?;
+
+            finish(
+                &mut env,
+                plaintext_ptr,
+                result.map(|plaintext: Plaintext| plaintext.0),
+            )
+        })
+    }
+
+    /// Writes digest to provided buffer of 32 bytes.
+    pub(super) fn sha256<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        data_ptr: Usize,
+        data_length: Usize,
+        digest_ptr: Usize,
+    ) -> AnyResult<()>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        let data: Vec<u8> = utils::read_from_memory_to_buffer(&mut env, data_ptr, data_length)
+            .context("Couldn't read data to hash from memory!")?;
+
+        write_to_memory(&mut env, digest_ptr, &Sha256::digest(data))
+    }
+
+    /// Writes digest to provided buffer of 64 bytes.
+    pub(super) fn sha512<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        data_ptr: Usize,
+        data_length: Usize,
+        digest_ptr: Usize,
+    ) -> AnyResult<()>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        let data: Vec<u8> = utils::read_from_memory_to_buffer(&mut env, data_ptr, data_length)
+            .context("Couldn't read data to hash from memory!")?;
+
+        write_to_memory(&mut env, digest_ptr, &Sha512::digest(data))
+    }
+}
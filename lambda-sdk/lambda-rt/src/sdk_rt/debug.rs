@@ -1,11 +1,10 @@
 use anyhow::Result as AnyResult;
-use wasmtime::Linker;
 
-use crate::Context;
+use crate::{sdk_rt::linker::HostLinker, Context};
 
 const MODULE: &str = "sdk::debug";
 
-pub fn link_rt<Ctx>(linker: &mut Linker<Ctx>) -> AnyResult<()>
+pub(super) fn link_rt<Ctx>(linker: &mut HostLinker<'_, Ctx>) -> AnyResult<()>
 where
     Ctx: Context,
 {
@@ -16,14 +15,17 @@ where
 }
 
 mod implementation {
-    use anyhow::{Context as _, Result as AnyResult};
+    use anyhow::Result as AnyResult;
+    use tracing::Level;
     use wasmtime::Caller;
 
     use crate::{
-        sdk_rt::utils::{self, WasmUsize},
+        sdk_rt::{log::implementation::record, utils::WasmUsize},
         Context,
     };
 
+    /// Emitted as a debug level log record, subject to the module's log
+    /// policy.
     pub(super) fn debug_str<Ctx, Usize>(
         mut env: Caller<'_, Ctx>,
         buffer_ptr: Usize,
@@ -33,15 +35,13 @@ mod implementation {
         Ctx: Context,
         Usize: WasmUsize,
     {
-        println!(
-            "{}",
-            String::from_utf8(
-                utils::read_from_memory_to_buffer(&mut env, buffer_ptr, buffer_length)
-                    .context("Couldn't read debug string from memory!")?
-            )
-            .context("Invalid UTF-8 encoded debug string passed to `debug_str`!")?
-        );
-
-        Ok(())
+        record(
+            &mut env,
+            Level::DEBUG,
+            "debug",
+            buffer_ptr,
+            buffer_length,
+            &[],
+        )
     }
 }
@@ -0,0 +1,523 @@
+use anyhow::Result as AnyResult;
+
+use crate::{sdk_rt::linker::HostLinker, Context};
+
+const MODULE: &str = "sdk::invoke";
+
+pub(super) fn link_rt<Ctx>(linker: &mut HostLinker<'_, Ctx>) -> AnyResult<()>
+where
+    Ctx: Context,
+{
+    linker.func_wrap4_async(MODULE, "invoke~32", implementation::invoke::<_, u32>)?;
+    linker.func_wrap4_async(MODULE, "invoke~64", implementation::invoke::<_, u64>)?;
+
+    linker.func_wrap(MODULE, "invoke_error", implementation::invoke_error::<_>)?;
+
+    linker.func_wrap(
+        MODULE,
+        "response_is_error",
+        implementation::response_is_error::<_>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "response_status_code",
+        implementation::response_status_code::<_>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "response_data_length",
+        implementation::response_data_length::<_>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "read_response_data~32",
+        implementation::read_response_data::<_, u32>,
+    )?;
+    linker.func_wrap(
+        MODULE,
+        "read_response_data~64",
+        implementation::read_response_data::<_, u64>,
+    )?;
+
+    linker.func_wrap(MODULE, "drop_response", implementation::drop_response::<_>)?;
+
+    Ok(())
+}
+
+mod implementation {
+    use std::future::Future;
+
+    use anyhow::{anyhow, bail, Context as _, Result as AnyResult};
+    use wasmtime::Caller;
+
+    use crate::{
+        invoke::{Invocation, InvokeError},
+        record::{self, CallInputs},
+        sdk_rt::utils::{self, WasmUsize},
+        Context, InvokeKeeper, InvokeProvider, InvokedResponse, Response, ResponseBody, SdkEnv,
+        User, INIT_ID,
+    };
+
+    /// Maximum length of an invoked module's identifier.
+    const MAX_MODULE_ID_LENGTH: usize = 255;
+
+    async fn collect_response(
+        response: Response,
+        max_length: usize,
+    ) -> Result<InvokedResponse, InvokeError> {
+        let data: Vec<u8> = match response.body {
+            ResponseBody::Buffered(data) => data,
+            ResponseBody::Streamed(mut chunks) => {
+                let mut data: Vec<u8> = Vec::new();
+
+                while let Some(chunk) = chunks.recv().await {
+                    if max_length - data.len() < chunk.len() {
+                        return Err(InvokeError::ResponseTooLarge);
+                    }
+
+                    data.extend_from_slice(&chunk);
+                }
+
+                data
+            }
+        };
+
+        if max_length < data.len() {
+            return Err(InvokeError::ResponseTooLarge);
+        }
+
+        Ok(InvokedResponse {
+            is_error: response.is_error,
+            status_code: response.status_code,
+            data,
+        })
+    }
+
+    /// Returns zero when invocation fails, with the reason available through
+    /// `invoke_error`.
+    pub(super) fn invoke<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        module_id_ptr: Usize,
+        module_id_length: Usize,
+        data_ptr: Usize,
+        data_length: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<u64>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            if env.data().sdk().invoke_keeper.response.is_some() {
+                bail!("Failed to invoke module because previous response is not dropped!");
+            }
+
+            if MAX_MODULE_ID_LENGTH < module_id_length.into_usize()? {
+                bail!(
+                    "Module identifier exceeds the maximum length of {MAX_MODULE_ID_LENGTH} bytes!"
+                );
+            }
+
+            let module_id: String = String::from_utf8(
+                utils::read_from_memory_to_buffer(&mut env, module_id_ptr, module_id_length)
+                    .context("Couldn't read module identifier from memory!")?,
+            )
+            .context("Invalid UTF-8 encoded module identifier!")?;
+
+            let data: Vec<u8> = utils::read_from_memory_to_buffer(&mut env, data_ptr, data_length)
+                .context("Couldn't read invocation data from memory!")?;
+
+            let ctx: &Ctx = env.data();
+
+            let sdk: &SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> = ctx.sdk();
+
+            let mut call_chain: Vec<String> = sdk.request_meta.call_chain.clone();
+
+            call_chain.push(sdk.module_id.clone());
+
+            let sender: Option<String> = ctx
+                .sender()
+                .map(|user: &Ctx::User| String::from(user.username()));
+
+            // Error codes are reported to the module as they are recorded.
+            let result: Result<InvokedResponse, u32> = if call_chain.contains(&module_id) {
+                Err(InvokeError::CycleDetected as u32)
+            } else if InvokeKeeper::<Ctx::Invoker>::MAX_CALL_DEPTH <= call_chain.len() {
+                Err(InvokeError::CallDepthExceeded as u32)
+            } else {
+                let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+                    env.data_mut().sdk_mut();
+
+                let invoker: &mut Ctx::Invoker = &mut sdk.invoke_keeper.invoker;
+
+                record::perform_async(
+                    &mut sdk.tape,
+                    CallInputs::Invoke {
+                        module_id: module_id.clone(),
+                        data: data.clone(),
+                    },
+                    || async move {
+                        Ok(
+                            match invoker
+                                .invoke(Invocation {
+                                    module_id,
+                                    data,
+                                    sender,
+                                    call_chain,
+                                })
+                                .await?
+                            {
+                                Ok(response) => collect_response(
+                                    response,
+                                    InvokeKeeper::<Ctx::Invoker>::MAX_RESPONSE_LENGTH,
+                                )
+                                .await
+                                .map_err(|error: InvokeError| error as u32),
+                                Err(error) => Err(error as u32),
+                            },
+                        )
+                    },
+                )
+                .await
+                .context("Failed to invoke module through invoke provider!")?
+            };
+
+            let keeper: &mut InvokeKeeper<Ctx::Invoker> =
+                &mut env.data_mut().sdk_mut().invoke_keeper;
+
+            match result {
+                Ok(response) => {
+                    keeper.response = Some(response);
+
+                    keeper.response_id = if let Some(id) = keeper.response_id.checked_add(1) {
+                        id
+                    } else {
+                        INIT_ID
+                    };
+
+                    keeper.last_error = 0;
+
+                    Ok(keeper.response_id.get())
+                }
+                Err(error) => {
+                    keeper.last_error = error;
+
+                    Ok(0)
+                }
+            }
+        })
+    }
+
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) fn invoke_error<Ctx>(env: Caller<'_, Ctx>) -> u32
+    where
+        Ctx: Context,
+    {
+        env.data().sdk().invoke_keeper.last_error
+    }
+
+    fn response<Ctx>(ctx: &Ctx, id: u64) -> AnyResult<&InvokedResponse>
+    where
+        Ctx: Context,
+    {
+        let keeper: &InvokeKeeper<Ctx::Invoker> = &ctx.sdk().invoke_keeper;
+
+        if id != keeper.response_id.get() {
+            bail!("Expected invocation response access ID didn't match provided one!");
+        }
+
+        keeper
+            .response
+            .as_ref()
+            .ok_or_else(|| anyhow!("No invocation response is held!"))
+    }
+
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) fn response_is_error<Ctx>(env: Caller<'_, Ctx>, id: u64) -> AnyResult<u32>
+    where
+        Ctx: Context,
+    {
+        response(env.data(), id).map(|response: &InvokedResponse| u32::from(response.is_error))
+    }
+
+    /// Returns zero when the invoked module didn't set a status code.
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) fn response_status_code<Ctx>(env: Caller<'_, Ctx>, id: u64) -> AnyResult<u32>
+    where
+        Ctx: Context,
+    {
+        response(env.data(), id)
+            .map(|response: &InvokedResponse| response.status_code.map_or(0, u32::from))
+    }
+
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) fn response_data_length<Ctx>(env: Caller<'_, Ctx>, id: u64) -> AnyResult<u64>
+    where
+        Ctx: Context,
+    {
+        response(env.data(), id)
+            .and_then(|response: &InvokedResponse| u64::from_usize(response.data.len()))
+    }
+
+    pub(super) fn read_response_data<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        id: u64,
+        buffer_ptr: Usize,
+        buffer_length: Usize,
+    ) -> AnyResult<Usize>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        if id != env.data().sdk().invoke_keeper.response_id.get() {
+            bail!("Expected invocation response access ID didn't match provided one!");
+        }
+
+        utils::write_from_buffer_to_memory(
+            &mut env,
+            |ctx: &mut Ctx| {
+                ctx.sdk_mut()
+                    .invoke_keeper
+                    .response
+                    .as_mut()
+                    .map(|response: &mut InvokedResponse| &mut response.data)
+                    .ok_or_else(|| anyhow!("No invocation response is held!"))
+            },
+            buffer_ptr,
+            buffer_length,
+        )
+        .context("Couldn't write invocation response's data to memory!")
+    }
+
+    pub(super) fn drop_response<Ctx>(mut env: Caller<'_, Ctx>, id: u64) -> AnyResult<()>
+    where
+        Ctx: Context,
+    {
+        let keeper: &mut InvokeKeeper<Ctx::Invoker> = &mut env.data_mut().sdk_mut().invoke_keeper;
+
+        if id != keeper.response_id.get() {
+            bail!("Expected invocation response access ID didn't match provided one!");
+        }
+
+        keeper.response = None;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::{ready, Ready},
+        mem::take,
+        sync::{Arc, Mutex, PoisonError},
+    };
+
+    use anyhow::Result as AnyResult;
+
+    use crate::{
+        invoke::{Invocation, InvokeError},
+        kv::MemoryKv,
+        tests::{Guest, Unavailable},
+        InvokeKeeper, InvokeProvider, RequestMeta, Response, ResponseBody, SdkContext, SdkEnv,
+    };
+
+    /// Invoker replying to every invocation with data of a fixed length,
+    /// keeping the invocations for inspection.
+    #[derive(Clone)]
+    struct ReplyingInvoker {
+        data_length: usize,
+        invocations: Arc<Mutex<Vec<Invocation>>>,
+    }
+
+    impl ReplyingInvoker {
+        fn new(data_length: usize) -> Self {
+            Self {
+                data_length,
+                invocations: Arc::default(),
+            }
+        }
+
+        fn take_invocations(&self) -> Vec<Invocation> {
+            take(
+                &mut *self
+                    .invocations
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner),
+            )
+        }
+    }
+
+    impl InvokeProvider for ReplyingInvoker {
+        type Result<'r> = Ready<AnyResult<Result<Response, InvokeError>>>;
+
+        fn invoke(&mut self, invocation: Invocation) -> Self::Result<'_> {
+            self.invocations
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(invocation);
+
+            ready(Ok(Ok(Response {
+                is_error: false,
+                status_code: Some(200),
+                headers: Vec::new(),
+                body: ResponseBody::Buffered(vec![0; self.data_length]),
+            })))
+        }
+    }
+
+    type InvokeContext = SdkContext<Unavailable, Unavailable, MemoryKv, ReplyingInvoker>;
+
+    /// Module invoking `callee` with data `ping` and exposing the invocation
+    /// functions it imports.
+    const GUEST: &str = r#"(module
+        (import "sdk::invoke" "invoke~32" (func $invoke (param i32 i32 i32 i32) (result i64)))
+        (import "sdk::invoke" "invoke_error" (func $invoke_error (result i32)))
+        (import "sdk::invoke" "response_data_length"
+            (func $response_data_length (param i64) (result i64)))
+        (memory (export "memory") 1)
+        (data (i32.const 0) "callee")
+        (data (i32.const 16) "ping")
+        (func (export "invoke") (result i64)
+            (call $invoke (i32.const 0) (i32.const 6) (i32.const 16) (i32.const 4)))
+        (func (export "invoke_error") (result i32) (call $invoke_error))
+        (func (export "data_length") (param i64) (result i64)
+            (call $response_data_length (local.get 0))))"#;
+
+    /// Guest run by module `module_id`, invoked through provided call chain.
+    async fn new_guest(
+        invoker: ReplyingInvoker,
+        module_id: &str,
+        call_chain: &[&str],
+    ) -> AnyResult<Guest<InvokeContext>> {
+        let mut env: SdkEnv<Unavailable, Unavailable, MemoryKv, ReplyingInvoker> =
+            SdkEnv::new(Unavailable, Unavailable, MemoryKv::new(), invoker);
+
+        env.set_module_id(String::from(module_id));
+
+        env.set_request_meta(RequestMeta {
+            call_chain: call_chain.iter().copied().map(String::from).collect(),
+            ..RequestMeta::default()
+        });
+
+        Guest::new(env, GUEST).await
+    }
+
+    /// Call chain of modules `m0`, `m1` and so on, of provided length.
+    fn call_chain(length: usize) -> Vec<String> {
+        (0..length)
+            .map(|index: usize| format!("m{index}"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn invocations_are_refused_beyond_maximum_call_depth() -> AnyResult<()> {
+        const MAX_CALL_DEPTH: usize = InvokeKeeper::<ReplyingInvoker>::MAX_CALL_DEPTH;
+
+        let invoker: ReplyingInvoker = ReplyingInvoker::new(0);
+
+        // Invoking module completes the call chain to its maximum depth.
+        let full: Vec<String> = call_chain(MAX_CALL_DEPTH - 1);
+
+        let mut guest: Guest<InvokeContext> = new_guest(
+            invoker.clone(),
+            "caller",
+            &full.iter().map(String::as_str).collect::<Vec<&str>>(),
+        )
+        .await?;
+
+        assert_eq!(guest.call::<_, u64>("invoke", ()).await?, 0);
+        assert_eq!(
+            guest.call::<_, u32>("invoke_error", ()).await?,
+            InvokeError::CallDepthExceeded as u32
+        );
+        assert_eq!(invoker.take_invocations(), Vec::<Invocation>::new());
+
+        // One module less leaves room for the invoked one.
+        let shorter: Vec<String> = call_chain(MAX_CALL_DEPTH - 2);
+
+        let mut guest: Guest<InvokeContext> = new_guest(
+            invoker.clone(),
+            "caller",
+            &shorter.iter().map(String::as_str).collect::<Vec<&str>>(),
+        )
+        .await?;
+
+        assert_ne!(guest.call::<_, u64>("invoke", ()).await?, 0);
+        assert_eq!(guest.call::<_, u32>("invoke_error", ()).await?, 0);
+
+        let invocations: Vec<Invocation> = invoker.take_invocations();
+
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(invocations[0].module_id, "callee");
+        assert_eq!(invocations[0].data, b"ping");
+        assert_eq!(invocations[0].call_chain.len(), MAX_CALL_DEPTH - 1);
+        assert_eq!(
+            invocations[0].call_chain.last().map(String::as_str),
+            Some("caller")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn invocations_of_modules_in_call_chain_are_refused() -> AnyResult<()> {
+        let invoker: ReplyingInvoker = ReplyingInvoker::new(0);
+
+        // Module invoking itself.
+        let mut guest: Guest<InvokeContext> = new_guest(invoker.clone(), "callee", &[]).await?;
+
+        assert_eq!(guest.call::<_, u64>("invoke", ()).await?, 0);
+        assert_eq!(
+            guest.call::<_, u32>("invoke_error", ()).await?,
+            InvokeError::CycleDetected as u32
+        );
+
+        // Module invoking one which led to it.
+        let mut guest: Guest<InvokeContext> =
+            new_guest(invoker.clone(), "caller", &["callee"]).await?;
+
+        assert_eq!(guest.call::<_, u64>("invoke", ()).await?, 0);
+        assert_eq!(
+            guest.call::<_, u32>("invoke_error", ()).await?,
+            InvokeError::CycleDetected as u32
+        );
+
+        assert_eq!(invoker.take_invocations(), Vec::<Invocation>::new());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn responses_exceeding_maximum_length_are_refused() -> AnyResult<()> {
+        const MAX_RESPONSE_LENGTH: usize = InvokeKeeper::<ReplyingInvoker>::MAX_RESPONSE_LENGTH;
+
+        let mut guest: Guest<InvokeContext> =
+            new_guest(ReplyingInvoker::new(MAX_RESPONSE_LENGTH + 1), "caller", &[]).await?;
+
+        assert_eq!(guest.call::<_, u64>("invoke", ()).await?, 0);
+        assert_eq!(
+            guest.call::<_, u32>("invoke_error", ()).await?,
+            InvokeError::ResponseTooLarge as u32
+        );
+
+        let mut guest: Guest<InvokeContext> =
+            new_guest(ReplyingInvoker::new(MAX_RESPONSE_LENGTH), "caller", &[]).await?;
+
+        let response: u64 = guest.call("invoke", ()).await?;
+
+        assert_ne!(response, 0);
+        assert_eq!(
+            guest.call::<_, u64>("data_length", response).await?,
+            u64::try_from(MAX_RESPONSE_LENGTH)?
+        );
+
+        Ok(())
+    }
+}
@@ -1,11 +1,10 @@
 use anyhow::Result as AnyResult;
-use wasmtime::Linker;
 
-use crate::Context;
+use crate::{sdk_rt::linker::HostLinker, Context};
 
 const MODULE: &str = "sdk::io";
 
-pub fn link_rt<Ctx>(linker: &mut Linker<Ctx>) -> AnyResult<()>
+pub(super) fn link_rt<Ctx>(linker: &mut HostLinker<'_, Ctx>) -> AnyResult<()>
 where
     Ctx: Context,
 {
@@ -32,6 +31,8 @@ where
         implementation::read_request_data::<_, u64>,
     )?;
 
+    link_request_meta(linker)?;
+
     linker.func_wrap(
         MODULE,
         "set_response_is_error",
@@ -39,11 +40,34 @@ where
     )?;
 
     linker.func_wrap(
+        MODULE,
+        "set_response_status_code",
+        implementation::set_response_status_code::<_>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "add_response_header~32",
+        implementation::add_response_header::<_, u32>,
+    )?;
+    linker.func_wrap(
+        MODULE,
+        "add_response_header~64",
+        implementation::add_response_header::<_, u64>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "start_response_stream",
+        implementation::start_response_stream::<_>,
+    )?;
+
+    linker.func_wrap2_async(
         MODULE,
         "write_response_data~32",
         implementation::write_response_data::<_, u32>,
     )?;
-    linker.func_wrap(
+    linker.func_wrap2_async(
         MODULE,
         "write_response_data~64",
         implementation::write_response_data::<_, u64>,
@@ -52,22 +76,118 @@ where
     Ok(())
 }
 
+fn link_request_meta<Ctx>(linker: &mut HostLinker<'_, Ctx>) -> AnyResult<()>
+where
+    Ctx: Context,
+{
+    linker.func_wrap(
+        MODULE,
+        "request_meta_length",
+        implementation::request_meta_length::<_>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "request_meta~32",
+        implementation::request_meta::<_, u32>,
+    )?;
+    linker.func_wrap(
+        MODULE,
+        "request_meta~64",
+        implementation::request_meta::<_, u64>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "request_headers_count",
+        implementation::request_headers_count::<_>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "request_header_name_length",
+        implementation::request_header_name_length::<_>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "request_header_name~32",
+        implementation::request_header_name::<_, u32>,
+    )?;
+    linker.func_wrap(
+        MODULE,
+        "request_header_name~64",
+        implementation::request_header_name::<_, u64>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "request_header_value_length",
+        implementation::request_header_value_length::<_>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "request_header_value~32",
+        implementation::request_header_value::<_, u32>,
+    )?;
+    linker.func_wrap(
+        MODULE,
+        "request_header_value~64",
+        implementation::request_header_value::<_, u64>,
+    )?;
+
+    Ok(())
+}
+
 mod implementation {
-    use anyhow::{bail, Context as _, Result as AnyResult};
+    use std::future::Future;
+
+    use anyhow::{anyhow, bail, Context as _, Result as AnyResult};
+    use reqwest::header::{HeaderName, HeaderValue};
     use wasmtime::Caller;
 
     use crate::{
         sdk_rt::utils::{self, NeverError, WasmUsize},
-        Context, SdkEnv, INIT_ID,
+        Context, RequestMeta, SdkEnv, INIT_ID,
     };
 
+    fn request_meta_field<Ctx>(ctx: &Ctx, field: u32) -> AnyResult<&str>
+    where
+        Ctx: Context,
+    {
+        let meta: &RequestMeta = &ctx.sdk().request_meta;
+
+        Ok(match field {
+            0 => &meta.method,
+            1 => &meta.path,
+            2 => &meta.route,
+            3 => &meta.query,
+            _ => bail!("No request metadata field with such index exists!"),
+        })
+    }
+
+    fn request_header<Ctx>(ctx: &Ctx, index: u64) -> AnyResult<&(String, Vec<u8>)>
+    where
+        Ctx: Context,
+    {
+        let index: usize = index.into_usize()?;
+
+        ctx.sdk()
+            .request_meta
+            .headers
+            .get(index)
+            .ok_or_else(|| anyhow!("No request header with such index exists!"))
+    }
+
     // ALLOW: Required by "wasmtime" API.
     #[allow(clippy::needless_pass_by_value)]
     pub(super) fn receive_request_data_id<Ctx>(mut env: Caller<'_, Ctx>) -> u64
     where
         Ctx: Context,
     {
-        let ctx: &mut SdkEnv<Ctx::Vault> = env.data_mut().sdk_mut();
+        let ctx: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+            env.data_mut().sdk_mut();
 
         ctx.request_reader_id = if let Some(id) = ctx.request_reader_id.checked_add(1) {
             id
@@ -84,7 +204,7 @@ mod implementation {
     where
         Ctx: Context,
     {
-        let sdk: &SdkEnv<Ctx::Vault> = env.data().sdk();
+        let sdk: &SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> = env.data().sdk();
 
         if id != sdk.request_reader_id.get() {
             bail!("Expected request data access ID didn't match provided one!");
@@ -116,34 +236,211 @@ mod implementation {
         .context("Couldn't write request's data to memory!")
     }
 
-    pub(super) fn set_response_is_error<Ctx>(mut env: Caller<'_, Ctx>, id: u64) -> AnyResult<()>
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) fn request_meta_length<Ctx>(env: Caller<'_, Ctx>, field: u32) -> AnyResult<u64>
     where
         Ctx: Context,
     {
-        if id != env.data().sdk().request_reader_id.get() {
-            bail!("Expected request data access ID didn't match provided one!");
-        }
+        request_meta_field(env.data(), field)
+            .map(str::len)
+            .and_then(u64::from_usize)
+    }
 
+    pub(super) fn request_meta<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        field: u32,
+        buffer_ptr: Usize,
+        buffer_length: Usize,
+    ) -> AnyResult<Usize>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        utils::write_constant_to_memory(
+            &mut env,
+            |ctx: &Ctx| request_meta_field(ctx, field).map(str::as_bytes),
+            buffer_ptr,
+            buffer_length,
+        )
+        .context("Couldn't write request's metadata to memory!")
+    }
+
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) fn request_headers_count<Ctx>(env: Caller<'_, Ctx>) -> AnyResult<u64>
+    where
+        Ctx: Context,
+    {
+        u64::from_usize(env.data().sdk().request_meta.headers.len())
+    }
+
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) fn request_header_name_length<Ctx>(
+        env: Caller<'_, Ctx>,
+        index: u64,
+    ) -> AnyResult<u64>
+    where
+        Ctx: Context,
+    {
+        request_header(env.data(), index)
+            .map(|(name, _): &(String, Vec<u8>)| name.len())
+            .and_then(u64::from_usize)
+    }
+
+    pub(super) fn request_header_name<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        index: u64,
+        buffer_ptr: Usize,
+        buffer_length: Usize,
+    ) -> AnyResult<Usize>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        utils::write_constant_to_memory(
+            &mut env,
+            |ctx: &Ctx| {
+                request_header(ctx, index).map(|(name, _): &(String, Vec<u8>)| name.as_bytes())
+            },
+            buffer_ptr,
+            buffer_length,
+        )
+        .context("Couldn't write request header's name to memory!")
+    }
+
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) fn request_header_value_length<Ctx>(
+        env: Caller<'_, Ctx>,
+        index: u64,
+    ) -> AnyResult<u64>
+    where
+        Ctx: Context,
+    {
+        request_header(env.data(), index)
+            .map(|(_, value): &(String, Vec<u8>)| value.len())
+            .and_then(u64::from_usize)
+    }
+
+    pub(super) fn request_header_value<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        index: u64,
+        buffer_ptr: Usize,
+        buffer_length: Usize,
+    ) -> AnyResult<Usize>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        utils::write_constant_to_memory(
+            &mut env,
+            |ctx: &Ctx| {
+                request_header(ctx, index).map(|(_, value): &(String, Vec<u8>)| value.as_slice())
+            },
+            buffer_ptr,
+            buffer_length,
+        )
+        .context("Couldn't write request header's value to memory!")
+    }
+
+    pub(super) fn set_response_is_error<Ctx>(mut env: Caller<'_, Ctx>)
+    where
+        Ctx: Context,
+    {
         env.data_mut().sdk_mut().response.is_error = true;
+    }
+
+    pub(super) fn set_response_status_code<Ctx>(
+        mut env: Caller<'_, Ctx>,
+        status_code: u32,
+    ) -> AnyResult<()>
+    where
+        Ctx: Context,
+    {
+        let status_code: u16 = status_code
+            .try_into()
+            .ok()
+            .filter(|status_code: &u16| (100..600).contains(status_code))
+            .ok_or_else(|| anyhow!("Response status code outside of the valid range!"))?;
+
+        env.data_mut().sdk_mut().response.status_code = Some(status_code);
+
+        Ok(())
+    }
+
+    pub(super) fn add_response_header<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        name_ptr: Usize,
+        name_length: Usize,
+        value_ptr: Usize,
+        value_length: Usize,
+    ) -> AnyResult<()>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        let name: HeaderName = HeaderName::from_bytes(
+            &utils::read_from_memory_to_buffer(&mut env, name_ptr, name_length)
+                .context("Couldn't read response header's name from memory!")?,
+        )
+        .context("Invalid response header name!")?;
+
+        let value: HeaderValue = HeaderValue::from_bytes(
+            &utils::read_from_memory_to_buffer(&mut env, value_ptr, value_length)
+                .context("Couldn't read response header's value from memory!")?,
+        )
+        .context("Invalid response header value!")?;
+
+        env.data_mut()
+            .sdk_mut()
+            .response
+            .headers
+            .push((String::from(name.as_str()), value.as_bytes().to_vec()));
 
         Ok(())
     }
 
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) fn start_response_stream<Ctx>(mut env: Caller<'_, Ctx>) -> AnyResult<()>
+    where
+        Ctx: Context,
+    {
+        env.data_mut().sdk_mut().start_response_stream()
+    }
+
     pub(super) fn write_response_data<Ctx, Usize>(
         mut env: Caller<'_, Ctx>,
         buffer_ptr: Usize,
         buffer_length: Usize,
-    ) -> AnyResult<()>
+    ) -> Box<dyn Future<Output = AnyResult<()>> + Send + '_>
     where
         Ctx: Context,
         Usize: WasmUsize,
     {
-        let mut buffer: Vec<u8> =
-            utils::read_from_memory_to_buffer(&mut env, buffer_ptr, buffer_length)
-                .context("Couldn't read response data from memory!")?;
+        Box::new(async move {
+            let mut buffer: Vec<u8> =
+                utils::read_from_memory_to_buffer(&mut env, buffer_ptr, buffer_length)
+                    .context("Couldn't read response data from memory!")?;
 
-        env.data_mut().sdk_mut().response.data.append(&mut buffer);
+            let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+                env.data_mut().sdk_mut();
 
-        Ok(())
+            sdk.metrics_keeper.add_bytes_out(buffer.len());
+
+            if let Some(response_stream) = &sdk.response_stream {
+                if !buffer.is_empty() {
+                    // Client might have disconnected, in which case written
+                    // data is simply discarded.
+                    let _: Result<(), _> = response_stream.send(buffer).await;
+                }
+            } else {
+                sdk.response.data.append(&mut buffer);
+            }
+
+            Ok(())
+        })
     }
 }
@@ -0,0 +1,484 @@
+use anyhow::Result as AnyResult;
+
+use crate::{sdk_rt::linker::HostLinker, Context};
+
+const MODULE: &str = "sdk::kv";
+
+pub(super) fn link_rt<Ctx>(linker: &mut HostLinker<'_, Ctx>) -> AnyResult<()>
+where
+    Ctx: Context,
+{
+    linker.func_wrap2_async(MODULE, "get~32", implementation::get::<_, u32>)?;
+    linker.func_wrap2_async(MODULE, "get~64", implementation::get::<_, u64>)?;
+
+    linker.func_wrap(MODULE, "value_length", implementation::value_length::<_>)?;
+
+    linker.func_wrap(
+        MODULE,
+        "read_value~32",
+        implementation::read_value::<_, u32>,
+    )?;
+    linker.func_wrap(
+        MODULE,
+        "read_value~64",
+        implementation::read_value::<_, u64>,
+    )?;
+
+    linker.func_wrap(MODULE, "drop_value", implementation::drop_value::<_>)?;
+
+    linker.func_wrap4_async(MODULE, "put~32", implementation::put::<_, u32>)?;
+    linker.func_wrap4_async(MODULE, "put~64", implementation::put::<_, u64>)?;
+
+    linker.func_wrap2_async(MODULE, "delete~32", implementation::delete::<_, u32>)?;
+    linker.func_wrap2_async(MODULE, "delete~64", implementation::delete::<_, u64>)?;
+
+    linker.func_wrap6_async(
+        MODULE,
+        "compare_and_swap~32",
+        implementation::compare_and_swap::<_, u32>,
+    )?;
+    linker.func_wrap6_async(
+        MODULE,
+        "compare_and_swap~64",
+        implementation::compare_and_swap::<_, u64>,
+    )?;
+
+    linker.func_wrap2_async(MODULE, "list_keys~32", implementation::list_keys::<_, u32>)?;
+    linker.func_wrap2_async(MODULE, "list_keys~64", implementation::list_keys::<_, u64>)?;
+
+    linker.func_wrap(MODULE, "keys_count", implementation::keys_count::<_>)?;
+
+    linker.func_wrap(MODULE, "key_length", implementation::key_length::<_>)?;
+
+    linker.func_wrap(MODULE, "read_key~32", implementation::read_key::<_, u32>)?;
+    linker.func_wrap(MODULE, "read_key~64", implementation::read_key::<_, u64>)?;
+
+    linker.func_wrap(MODULE, "drop_keys", implementation::drop_keys::<_>)?;
+
+    Ok(())
+}
+
+mod implementation {
+    use std::future::Future;
+
+    use anyhow::{anyhow, bail, Context as _, Result as AnyResult};
+    use wasmtime::Caller;
+
+    use crate::{
+        record::{self, CallInputs},
+        sdk_rt::utils::{self, WasmUsize},
+        Context, KvKeeper, KvProvider, SdkEnv, INIT_ID,
+    };
+
+    fn read_limited<Ctx, Usize>(
+        env: &mut Caller<'_, Ctx>,
+        buffer_ptr: Usize,
+        buffer_length: Usize,
+        max_length: usize,
+        name: &str,
+    ) -> AnyResult<Vec<u8>>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        if max_length < buffer_length.into_usize()? {
+            bail!("{name} exceeds the maximum length of {max_length} bytes!");
+        }
+
+        utils::read_from_memory_to_buffer(env, buffer_ptr, buffer_length)
+            .with_context(|| format!("Couldn't read {name} from memory!"))
+    }
+
+    fn read_key_from_memory<Ctx, Usize>(
+        env: &mut Caller<'_, Ctx>,
+        key_ptr: Usize,
+        key_length: Usize,
+    ) -> AnyResult<Vec<u8>>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        read_limited(
+            env,
+            key_ptr,
+            key_length,
+            KvKeeper::<Ctx::Kv>::MAX_KEY_LENGTH,
+            "Key",
+        )
+    }
+
+    fn read_value_from_memory<Ctx, Usize>(
+        env: &mut Caller<'_, Ctx>,
+        value_ptr: Usize,
+        value_length: Usize,
+    ) -> AnyResult<Vec<u8>>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        read_limited(
+            env,
+            value_ptr,
+            value_length,
+            KvKeeper::<Ctx::Kv>::MAX_VALUE_LENGTH,
+            "Value",
+        )
+    }
+
+    pub(super) fn get<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        key_ptr: Usize,
+        key_length: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<u64>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            if env.data().sdk().kv_keeper.value.is_some() {
+                bail!("Failed to get value because previous value is not dropped!");
+            }
+
+            let key: Vec<u8> = read_key_from_memory(&mut env, key_ptr, key_length)?;
+
+            let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+                env.data_mut().sdk_mut();
+
+            let keeper: &mut KvKeeper<Ctx::Kv> = &mut sdk.kv_keeper;
+
+            keeper.value = record::perform_async(
+                &mut sdk.tape,
+                CallInputs::KvGet { key: key.clone() },
+                || keeper.kv.get(keeper.namespace.clone(), key),
+            )
+            .await
+            .context("Failed to get value from key-value provider!")?;
+
+            if keeper.value.is_none() {
+                return Ok(0);
+            }
+
+            keeper.value_id = if let Some(id) = keeper.value_id.checked_add(1) {
+                id
+            } else {
+                INIT_ID
+            };
+
+            Ok(keeper.value_id.get())
+        })
+    }
+
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) fn value_length<Ctx>(env: Caller<'_, Ctx>, id: u64) -> AnyResult<u64>
+    where
+        Ctx: Context,
+    {
+        let keeper: &KvKeeper<Ctx::Kv> = &env.data().sdk().kv_keeper;
+
+        if id != keeper.value_id.get() {
+            bail!("Expected value access ID didn't match provided one!");
+        }
+
+        keeper
+            .value
+            .as_ref()
+            .map(Vec::len)
+            .ok_or_else(|| anyhow!("No value has been fetched!"))
+            .and_then(u64::from_usize)
+    }
+
+    pub(super) fn read_value<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        id: u64,
+        buffer_ptr: Usize,
+        buffer_length: Usize,
+    ) -> AnyResult<Usize>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        if id != env.data().sdk().kv_keeper.value_id.get() {
+            bail!("Expected value access ID didn't match provided one!");
+        }
+
+        utils::write_from_buffer_to_memory(
+            &mut env,
+            |ctx: &mut Ctx| {
+                ctx.sdk_mut()
+                    .kv_keeper
+                    .value
+                    .as_mut()
+                    .ok_or_else(|| anyhow!("No value has been fetched!"))
+            },
+            buffer_ptr,
+            buffer_length,
+        )
+        .context("Couldn't write value to memory!")
+    }
+
+    pub(super) fn drop_value<Ctx>(mut env: Caller<'_, Ctx>, id: u64) -> AnyResult<()>
+    where
+        Ctx: Context,
+    {
+        let keeper: &mut KvKeeper<Ctx::Kv> = &mut env.data_mut().sdk_mut().kv_keeper;
+
+        if id != keeper.value_id.get() {
+            bail!("Expected value access ID didn't match provided one!");
+        }
+
+        keeper.value = None;
+
+        Ok(())
+    }
+
+    pub(super) fn put<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        key_ptr: Usize,
+        key_length: Usize,
+        value_ptr: Usize,
+        value_length: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<()>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            let key: Vec<u8> = read_key_from_memory(&mut env, key_ptr, key_length)?;
+
+            let value: Vec<u8> = read_value_from_memory(&mut env, value_ptr, value_length)?;
+
+            let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+                env.data_mut().sdk_mut();
+
+            let keeper: &mut KvKeeper<Ctx::Kv> = &mut sdk.kv_keeper;
+
+            record::perform_async(
+                &mut sdk.tape,
+                CallInputs::KvPut {
+                    key: key.clone(),
+                    value: value.clone(),
+                },
+                || keeper.kv.put(keeper.namespace.clone(), key, value),
+            )
+            .await
+            .context("Failed to put value through key-value provider!")
+        })
+    }
+
+    pub(super) fn delete<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        key_ptr: Usize,
+        key_length: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<u32>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            let key: Vec<u8> = read_key_from_memory(&mut env, key_ptr, key_length)?;
+
+            let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+                env.data_mut().sdk_mut();
+
+            let keeper: &mut KvKeeper<Ctx::Kv> = &mut sdk.kv_keeper;
+
+            record::perform_async(
+                &mut sdk.tape,
+                CallInputs::KvDelete { key: key.clone() },
+                || keeper.kv.delete(keeper.namespace.clone(), key),
+            )
+            .await
+            .map(u32::from)
+            .context("Failed to delete value through key-value provider!")
+        })
+    }
+
+    /// Null expected value pointer denotes that the entry is expected not to
+    /// exist.
+    pub(super) fn compare_and_swap<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        key_ptr: Usize,
+        key_length: Usize,
+        expected_ptr: Usize,
+        expected_length: Usize,
+        new_ptr: Usize,
+        new_length: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<u32>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            let key: Vec<u8> = read_key_from_memory(&mut env, key_ptr, key_length)?;
+
+            let expected: Option<Vec<u8>> = if expected_ptr == Usize::ZERO {
+                None
+            } else {
+                Some(read_value_from_memory(
+                    &mut env,
+                    expected_ptr,
+                    expected_length,
+                )?)
+            };
+
+            let new: Vec<u8> = read_value_from_memory(&mut env, new_ptr, new_length)?;
+
+            let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+                env.data_mut().sdk_mut();
+
+            let keeper: &mut KvKeeper<Ctx::Kv> = &mut sdk.kv_keeper;
+
+            record::perform_async(
+                &mut sdk.tape,
+                CallInputs::KvCompareAndSwap {
+                    key: key.clone(),
+                    expected: expected.clone(),
+                    new: new.clone(),
+                },
+                || {
+                    keeper
+                        .kv
+                        .compare_and_swap(keeper.namespace.clone(), key, expected, new)
+                },
+            )
+            .await
+            .map(u32::from)
+            .context("Failed to compare and swap value through key-value provider!")
+        })
+    }
+
+    pub(super) fn list_keys<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        prefix_ptr: Usize,
+        prefix_length: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<u64>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            if env.data().sdk().kv_keeper.keys.is_some() {
+                bail!("Failed to list keys because previous list is not dropped!");
+            }
+
+            let prefix: Vec<u8> = read_key_from_memory(&mut env, prefix_ptr, prefix_length)?;
+
+            let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+                env.data_mut().sdk_mut();
+
+            let keeper: &mut KvKeeper<Ctx::Kv> = &mut sdk.kv_keeper;
+
+            keeper.keys = Some(
+                record::perform_async(
+                    &mut sdk.tape,
+                    CallInputs::KvListKeys {
+                        prefix: prefix.clone(),
+                    },
+                    || {
+                        keeper.kv.list_keys(
+                            keeper.namespace.clone(),
+                            prefix,
+                            KvKeeper::<Ctx::Kv>::MAX_LISTED_KEYS,
+                        )
+                    },
+                )
+                .await
+                .context("Failed to list keys through key-value provider!")?,
+            );
+
+            keeper.keys_id = if let Some(id) = keeper.keys_id.checked_add(1) {
+                id
+            } else {
+                INIT_ID
+            };
+
+            Ok(keeper.keys_id.get())
+        })
+    }
+
+    fn listed_keys<Ctx>(ctx: &Ctx, id: u64) -> AnyResult<&[Vec<u8>]>
+    where
+        Ctx: Context,
+    {
+        let keeper: &KvKeeper<Ctx::Kv> = &ctx.sdk().kv_keeper;
+
+        if id != keeper.keys_id.get() {
+            bail!("Expected key list access ID didn't match provided one!");
+        }
+
+        keeper
+            .keys
+            .as_deref()
+            .ok_or_else(|| anyhow!("No keys have been listed!"))
+    }
+
+    fn listed_key<Ctx>(ctx: &Ctx, id: u64, index: u64) -> AnyResult<&[u8]>
+    where
+        Ctx: Context,
+    {
+        let index: usize = index.into_usize()?;
+
+        listed_keys(ctx, id)?
+            .get(index)
+            .map(Vec::as_slice)
+            .ok_or_else(|| anyhow!("No listed key with such index exists!"))
+    }
+
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) fn keys_count<Ctx>(env: Caller<'_, Ctx>, id: u64) -> AnyResult<u64>
+    where
+        Ctx: Context,
+    {
+        listed_keys(env.data(), id)
+            .map(<[Vec<u8>]>::len)
+            .and_then(u64::from_usize)
+    }
+
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) fn key_length<Ctx>(env: Caller<'_, Ctx>, id: u64, index: u64) -> AnyResult<u64>
+    where
+        Ctx: Context,
+    {
+        listed_key(env.data(), id, index)
+            .map(<[u8]>::len)
+            .and_then(u64::from_usize)
+    }
+
+    pub(super) fn read_key<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        id: u64,
+        index: u64,
+        buffer_ptr: Usize,
+        buffer_length: Usize,
+    ) -> AnyResult<Usize>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        utils::write_constant_to_memory(
+            &mut env,
+            |ctx: &Ctx| listed_key(ctx, id, index),
+            buffer_ptr,
+            buffer_length,
+        )
+        .context("Couldn't write listed key to memory!")
+    }
+
+    pub(super) fn drop_keys<Ctx>(mut env: Caller<'_, Ctx>, id: u64) -> AnyResult<()>
+    where
+        Ctx: Context,
+    {
+        let keeper: &mut KvKeeper<Ctx::Kv> = &mut env.data_mut().sdk_mut().kv_keeper;
+
+        if id != keeper.keys_id.get() {
+            bail!("Expected key list access ID didn't match provided one!");
+        }
+
+        keeper.keys = None;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use std::future::Future;
+
+use anyhow::Result as AnyResult;
+use wasmtime::{Caller, Linker, WasmRet, WasmTy};
+
+use crate::Context;
+
+/// Wrapper around [`Linker`] which attributes each call of a defined host
+/// function to the module it is defined in, in the execution's metrics, as
+/// call hooks can't tell host functions apart.
+pub(super) struct HostLinker<'r, Ctx>
+where
+    Ctx: Context,
+{
+    linker: &'r mut Linker<Ctx>,
+}
+
+impl<'r, Ctx> HostLinker<'r, Ctx>
+where
+    Ctx: Context,
+{
+    pub(super) fn new(linker: &'r mut Linker<Ctx>) -> Self {
+        Self { linker }
+    }
+
+    pub(super) fn func_wrap<Params, Results, F>(
+        &mut self,
+        module: &'static str,
+        name: &str,
+        func: F,
+    ) -> AnyResult<&mut Self>
+    where
+        F: HostFunc<Ctx, Params, Results>,
+    {
+        func.define(self.linker, module, name)?;
+
+        Ok(self)
+    }
+}
+
+fn attribute_host_call<Ctx>(env: &mut Caller<'_, Ctx>, module: &'static str)
+where
+    Ctx: Context,
+{
+    env.data_mut()
+        .sdk_mut()
+        .metrics_keeper
+        .attribute_host_call(module);
+}
+
+pub(super) trait HostFunc<Ctx, Params, Results>
+where
+    Ctx: Context,
+{
+    fn define(self, linker: &mut Linker<Ctx>, module: &'static str, name: &str) -> AnyResult<()>;
+}
+
+macro_rules! impl_host_func {
+    ($(($($arg: ident: $type: ident),*))+) => {
+        $(
+            impl<Ctx, F, $($type,)* R> HostFunc<Ctx, ($($type,)*), R> for F
+            where
+                Ctx: Context,
+                F: Fn(Caller<'_, Ctx>, $($type),*) -> R + Send + Sync + 'static,
+                $($type: WasmTy,)*
+                R: WasmRet,
+            {
+                fn define(
+                    self,
+                    linker: &mut Linker<Ctx>,
+                    module: &'static str,
+                    name: &str,
+                ) -> AnyResult<()> {
+                    linker.func_wrap(
+                        module,
+                        name,
+                        move |mut env: Caller<'_, Ctx>, $($arg: $type),*| -> R {
+                            attribute_host_call(&mut env, module);
+
+                            self(env, $($arg),*)
+                        },
+                    )?;
+
+                    Ok(())
+                }
+            }
+        )+
+    };
+}
+
+impl_host_func!(
+    ()
+    (a1: A1)
+    (a1: A1, a2: A2)
+    (a1: A1, a2: A2, a3: A3)
+    (a1: A1, a2: A2, a3: A3, a4: A4)
+    (a1: A1, a2: A2, a3: A3, a4: A4, a5: A5)
+    (a1: A1, a2: A2, a3: A3, a4: A4, a5: A5, a6: A6)
+    (a1: A1, a2: A2, a3: A3, a4: A4, a5: A5, a6: A6, a7: A7)
+    (a1: A1, a2: A2, a3: A3, a4: A4, a5: A5, a6: A6, a7: A7, a8: A8)
+);
+
+macro_rules! host_linker_func_wrap_async {
+    ($($name: ident($($arg: ident: $type: ident),+);)+) => {
+        impl<'r, Ctx> HostLinker<'r, Ctx>
+        where
+            Ctx: Context,
+        {
+            $(
+                pub(super) fn $name<$($type,)+ R>(
+                    &mut self,
+                    module: &'static str,
+                    name: &str,
+                    func: impl for<'a> Fn(
+                            Caller<'a, Ctx>,
+                            $($type),+
+                        ) -> Box<dyn Future<Output = R> + Send + 'a>
+                        + Send
+                        + Sync
+                        + 'static,
+                ) -> AnyResult<&mut Self>
+                where
+                    $($type: WasmTy,)+
+                    R: WasmRet,
+                {
+                    self.linker.$name(
+                        module,
+                        name,
+                        move |mut env: Caller<'_, Ctx>, $($arg: $type),+| {
+                            attribute_host_call(&mut env, module);
+
+                            func(env, $($arg),+)
+                        },
+                    )?;
+
+                    Ok(self)
+                }
+            )+
+        }
+    };
+}
+
+host_linker_func_wrap_async!(
+    func_wrap1_async(a1: A1);
+    func_wrap2_async(a1: A1, a2: A2);
+    func_wrap3_async(a1: A1, a2: A2, a3: A3);
+    func_wrap4_async(a1: A1, a2: A2, a3: A3, a4: A4);
+    func_wrap5_async(a1: A1, a2: A2, a3: A3, a4: A4, a5: A5);
+    func_wrap6_async(a1: A1, a2: A2, a3: A3, a4: A4, a5: A5, a6: A6);
+    func_wrap8_async(a1: A1, a2: A2, a3: A3, a4: A4, a5: A5, a6: A6, a7: A7, a8: A8);
+);
@@ -0,0 +1,172 @@
+use anyhow::Result as AnyResult;
+
+use crate::{sdk_rt::linker::HostLinker, Context};
+
+const MODULE: &str = "sdk::log";
+
+pub(super) fn link_rt<Ctx>(linker: &mut HostLinker<'_, Ctx>) -> AnyResult<()>
+where
+    Ctx: Context,
+{
+    linker.func_wrap(MODULE, "log~32", implementation::log::<_, u32>)?;
+    linker.func_wrap(MODULE, "log~64", implementation::log::<_, u64>)?;
+
+    Ok(())
+}
+
+pub(super) mod implementation {
+    use anyhow::{anyhow, bail, Context as _, Result as AnyResult};
+    use tracing::Level;
+    use wasmtime::Caller;
+
+    use crate::{
+        log::{self, LogRecord},
+        sdk_rt::utils::{self, RawValue, Size, SlicePointer, WasmUsize},
+        Context, User,
+    };
+
+    /// Maximum number of key-value fields attached to a single record.
+    const MAX_FIELDS: usize = 32;
+
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    #[repr(packed, C)]
+    pub(in crate::sdk_rt) struct FieldPointer<Usize>
+    where
+        Usize: WasmUsize,
+    {
+        pub key: SlicePointer<Usize>,
+        pub value: SlicePointer<Usize>,
+    }
+
+    impl<Usize> RawValue for FieldPointer<Usize>
+    where
+        Usize: WasmUsize,
+    {
+        fn from_wasm_bytes(bytes: &[u8]) -> AnyResult<Self> {
+            let (key, value): (&[u8], &[u8]) =
+                bytes.split_at(bytes.len().min(<SlicePointer<Usize>>::SIZE));
+
+            Ok(Self {
+                key: SlicePointer::from_wasm_bytes(key)
+                    .context("Failed to deserialize `key` field from bytes!")?,
+                value: SlicePointer::from_wasm_bytes(value)
+                    .context("Failed to deserialize `value` field from bytes!")?,
+            })
+        }
+    }
+
+    fn read_string<Ctx, Usize>(
+        env: &mut Caller<'_, Ctx>,
+        buffer_ptr: Usize,
+        buffer_length: Usize,
+        name: &str,
+    ) -> AnyResult<String>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        String::from_utf8(
+            utils::read_from_memory_to_buffer(env, buffer_ptr, buffer_length)
+                .with_context(|| format!("Couldn't read log record's {name} from memory!"))?,
+        )
+        .with_context(|| format!("Invalid UTF-8 encoded log record's {name}!"))
+    }
+
+    /// Emits a record unless it is filtered out by the module's log policy,
+    /// or exceeds the per-request limits.
+    pub(in crate::sdk_rt) fn record<Ctx, Usize>(
+        env: &mut Caller<'_, Ctx>,
+        level: Level,
+        target: &str,
+        message_ptr: Usize,
+        message_length: Usize,
+        fields: &[FieldPointer<Usize>],
+    ) -> AnyResult<()>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        let length: usize = fields.iter().try_fold(
+            message_length
+                .into_usize()?
+                .checked_add(target.len())
+                .context("Log record's length overflows!")?,
+            |acc: usize, field: &FieldPointer<Usize>| -> AnyResult<usize> {
+                Ok(acc
+                    .saturating_add(field.key.length.into_usize()?)
+                    .saturating_add(field.value.length.into_usize()?))
+            },
+        )?;
+
+        if !env.data_mut().sdk_mut().log_keeper.admit(level, length) {
+            return Ok(());
+        }
+
+        let message: String = read_string(env, message_ptr, message_length, "message")?;
+
+        let fields: Vec<(String, String)> = fields
+            .iter()
+            .map(|field: &FieldPointer<Usize>| {
+                Ok((
+                    read_string(env, field.key.pointer, field.key.length, "field key")?,
+                    read_string(env, field.value.pointer, field.value.length, "field value")?,
+                ))
+            })
+            .collect::<AnyResult<_>>()?;
+
+        let ctx: &Ctx = env.data();
+
+        log::emit(
+            level,
+            &LogRecord {
+                module_id: &ctx.sdk().module_id,
+                username: ctx.sender().map(User::username),
+                target,
+                message: &message,
+                fields: &fields,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Fields are passed as an array of key and value slice pairs.
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn log<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        level: u32,
+        target_ptr: Usize,
+        target_length: Usize,
+        message_ptr: Usize,
+        message_length: Usize,
+        fields_ptr: Usize,
+        fields_count: Usize,
+    ) -> AnyResult<()>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        let level: Level =
+            log::level_from_code(level).ok_or_else(|| anyhow!("Invalid log level provided!"))?;
+
+        if MAX_FIELDS < fields_count.into_usize()? {
+            bail!("Log record exceeds the maximum of {MAX_FIELDS} fields!");
+        }
+
+        let fields: Vec<FieldPointer<Usize>> =
+            utils::read_value_array_from_memory(&mut env, fields_ptr, fields_count)
+                .context("Couldn't read log record's fields from memory!")?;
+
+        let target: String = read_string(&mut env, target_ptr, target_length, "target")?;
+
+        record(
+            &mut env,
+            level,
+            &target,
+            message_ptr,
+            message_length,
+            &fields,
+        )
+    }
+}
@@ -1,25 +1,44 @@
 use anyhow::Result as AnyResult;
 use wasmtime::Linker;
 
+use self::linker::HostLinker;
 use super::Context;
 
 mod context;
+mod crypto;
 mod debug;
+mod invoke;
 mod io;
+mod kv;
+mod linker;
+mod log;
 mod net;
 mod panic;
+mod random;
+mod time;
 mod vault;
 
 pub fn link_rt<Ctx>(linker: &mut Linker<Ctx>) -> AnyResult<()>
 where
     Ctx: Context,
 {
-    context::link_rt(linker)?;
-    debug::link_rt(linker)?;
-    io::link_rt(linker)?;
-    net::link_rt(linker)?;
-    panic::link_rt(linker)?;
-    vault::link_rt(linker)?;
+    let host_linker: &mut HostLinker<'_, Ctx> = &mut HostLinker::new(linker);
+
+    context::link_rt(host_linker)?;
+    crypto::link_rt(host_linker)?;
+    debug::link_rt(host_linker)?;
+    invoke::link_rt(host_linker)?;
+    io::link_rt(host_linker)?;
+    kv::link_rt(host_linker)?;
+    log::link_rt(host_linker)?;
+    net::link_rt(host_linker)?;
+    panic::link_rt(host_linker)?;
+    random::link_rt(host_linker)?;
+    time::link_rt(host_linker)?;
+    vault::link_rt(host_linker)?;
+
+    #[cfg(feature = "wasi")]
+    wasmtime_wasi::add_to_linker(linker, |context: &mut Ctx| &mut context.sdk_mut().wasi.ctx)?;
 
     Ok(())
 }
@@ -396,3 +415,90 @@ mod utils {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use lambda_abi::{imports, Import, PointerWidth, ValueType};
+    use wasmtime::{Config, Engine, Extern, FuncType, Linker, Store, ValType};
+
+    use crate::{
+        kv::MemoryKv,
+        tests::{TestContext, Unavailable},
+        SdkContext, SdkEnv,
+    };
+
+    fn value_type(r#type: ValType) -> ValueType {
+        match r#type {
+            ValType::I32 => ValueType::I32,
+            ValType::I64 => ValueType::I64,
+            r#type => panic!("Unsupported value type: {type}"),
+        }
+    }
+
+    #[test]
+    fn linked_functions_match_abi() {
+        let engine: Engine = Engine::new(Config::new().async_support(true)).unwrap();
+
+        let mut linker: Linker<TestContext> = Linker::new(&engine);
+
+        super::link_rt(&mut linker).unwrap();
+
+        let mut store: Store<TestContext> = Store::new(
+            &engine,
+            SdkContext::new(SdkEnv::new(
+                Unavailable,
+                Unavailable,
+                MemoryKv::new(),
+                Unavailable,
+            )),
+        );
+
+        let externs: Vec<(String, String, Extern)> = linker
+            .iter(&mut store)
+            .filter(|&(module, _, _): &(&str, &str, Extern)| module.starts_with("sdk::"))
+            .map(|(module, name, r#extern): (&str, &str, Extern)| {
+                (String::from(module), String::from(name), r#extern)
+            })
+            .collect();
+
+        let provided: BTreeSet<(String, String, Vec<ValueType>, Vec<ValueType>)> = externs
+            .into_iter()
+            .map(|(module, name, r#extern): (String, String, Extern)| {
+                let r#type: FuncType = r#extern
+                    .into_func()
+                    .unwrap_or_else(|| panic!(r#""{name}" of "{module}" isn't a function!"#))
+                    .ty(&store);
+
+                (
+                    module,
+                    name,
+                    r#type.params().map(value_type).collect(),
+                    r#type.results().map(value_type).collect(),
+                )
+            })
+            .collect();
+
+        let expected: BTreeSet<(String, String, Vec<ValueType>, Vec<ValueType>)> =
+            PointerWidth::ALL
+                .into_iter()
+                .flat_map(imports)
+                .map(|import: Import| {
+                    (
+                        String::from(import.module),
+                        import.name,
+                        import.params,
+                        import.results,
+                    )
+                })
+                .collect();
+
+        assert!(
+            provided == expected,
+            "Provided functions don't match the ABI!\nNot in ABI: {:#?}\nNot provided: {:#?}",
+            provided.difference(&expected).collect::<Vec<_>>(),
+            expected.difference(&provided).collect::<Vec<_>>(),
+        );
+    }
+}
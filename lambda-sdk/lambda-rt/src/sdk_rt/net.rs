@@ -1,11 +1,10 @@
 use anyhow::Result as AnyResult;
-use wasmtime::Linker;
 
-use crate::Context;
+use crate::{sdk_rt::linker::HostLinker, Context};
 
 const MODULE: &str = "sdk::net";
 
-pub fn link_rt<Ctx>(linker: &mut Linker<Ctx>) -> AnyResult<()>
+pub(super) fn link_rt<Ctx>(linker: &mut HostLinker<'_, Ctx>) -> AnyResult<()>
 where
     Ctx: Context,
 {
@@ -20,12 +19,84 @@ where
         implementation::send_request::<_, u64>,
     )?;
 
+    linker.func_wrap1_async(
+        MODULE,
+        "open_request~32",
+        implementation::open_request::<_, u32>,
+    )?;
+    linker.func_wrap1_async(
+        MODULE,
+        "open_request~64",
+        implementation::open_request::<_, u64>,
+    )?;
+
+    linker.func_wrap3_async(
+        MODULE,
+        "write_request_body~32",
+        implementation::write_request_body::<_, u32>,
+    )?;
+    linker.func_wrap3_async(
+        MODULE,
+        "write_request_body~64",
+        implementation::write_request_body::<_, u64>,
+    )?;
+
+    linker.func_wrap1_async(
+        MODULE,
+        "finish_request",
+        implementation::finish_request::<_>,
+    )?;
+
+    linker.func_wrap(MODULE, "drop_request", implementation::drop_request::<_>)?;
+
+    linker.func_wrap(MODULE, "request_error", implementation::request_error::<_>)?;
+
     linker.func_wrap(
         MODULE,
         "response_status_code",
         implementation::response_status_code::<_>,
     )?;
 
+    linker.func_wrap(
+        MODULE,
+        "response_headers_count",
+        implementation::response_headers_count::<_>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "response_header_name_length",
+        implementation::response_header_name_length::<_>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "response_header_name~32",
+        implementation::response_header_name::<_, u32>,
+    )?;
+    linker.func_wrap(
+        MODULE,
+        "response_header_name~64",
+        implementation::response_header_name::<_, u64>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "response_header_value_length",
+        implementation::response_header_value_length::<_>,
+    )?;
+
+    linker.func_wrap(
+        MODULE,
+        "response_header_value~32",
+        implementation::response_header_value::<_, u32>,
+    )?;
+    linker.func_wrap(
+        MODULE,
+        "response_header_value~64",
+        implementation::response_header_value::<_, u64>,
+    )?;
+
     linker.func_wrap(
         MODULE,
         "response_data_length",
@@ -56,19 +127,23 @@ where
 
 mod implementation {
     use std::future::Future;
+    use std::num::NonZeroU64;
     use std::slice::ChunksExact;
 
     use anyhow::{anyhow, bail, Context as _, Result as AnyResult};
     use reqwest::{
         header::{HeaderMap, HeaderName, HeaderValue},
-        Body, Client, Method, Request, Response, Url,
+        Body, Method, Request, Url,
     };
+    use tokio::{sync::mpsc::Sender as MpscSender, task::JoinHandle};
     use wasmtime::Caller;
 
     use crate::sdk_rt::utils::Size;
     use crate::{
+        egress::{EgressDenial, EgressPolicy, PinnedAddresses},
+        record::{self, CallInputs},
         sdk_rt::utils::{self, RawValue, SlicePointer, WasmUsize},
-        Context, Network, NetworkResponse, INIT_ID,
+        Context, NetworkKeeper, NetworkProvider, NetworkResponse, SdkEnv,
     };
 
     #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -149,6 +224,115 @@ mod implementation {
         }
     }
 
+    /// Inputs under which request's response is recorded.
+    fn recorded_call_inputs(request: &Request) -> CallInputs {
+        CallInputs::SendRequest {
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+        }
+    }
+
+    /// Reads request, without its body, from module's memory and checks it
+    /// against the egress policy. Returns request along with addresses it has
+    /// to be sent to, or [`None`] and records the reason when request is
+    /// denied or the response table is full.
+    async fn read_request<Ctx, Usize>(
+        env: &mut Caller<'_, Ctx>,
+        request_data: RequestData<Usize>,
+    ) -> AnyResult<Option<(Request, Option<PinnedAddresses>)>>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        let method: Method = Method::from_bytes(&utils::read_from_memory_to_buffer(
+            env,
+            request_data.method.pointer,
+            request_data.method.length,
+        )?)?;
+
+        let url: Url = Url::parse(&String::from_utf8(utils::read_from_memory_to_buffer(
+            env,
+            request_data.url.pointer,
+            request_data.url.length,
+        )?)?)?;
+
+        let egress_policy: EgressPolicy = env.data().sdk().network_keeper.egress_policy.clone();
+
+        // Addresses are not recorded, as requests are replayed without being
+        // sent.
+        let mut pinned_addresses: Option<PinnedAddresses> = None;
+
+        if let Some(denial) = record::perform_async(
+            &mut env.data_mut().sdk_mut().tape,
+            CallInputs::CheckEgress {
+                method: method.to_string(),
+                url: url.to_string(),
+            },
+            || async {
+                Ok(match egress_policy.check(&method, &url).await {
+                    Ok(addresses) => {
+                        pinned_addresses = addresses;
+
+                        None
+                    }
+                    Err(denial) => Some(denial as u32),
+                })
+            },
+        )
+        .await
+        .context("Failed to check request against egress policy!")?
+        {
+            env.data_mut().sdk_mut().network_keeper.last_error = denial;
+
+            return Ok(None);
+        }
+
+        if env.data().sdk().network_keeper.is_full() {
+            env.data_mut().sdk_mut().network_keeper.last_error =
+                EgressDenial::TooManyResponses as u32;
+
+            return Ok(None);
+        }
+
+        let mut request: Request = Request::new(method, url);
+
+        {
+            let headers: &mut HeaderMap<HeaderValue> = request.headers_mut();
+
+            utils::read_value_array_from_memory::<_, _, HeaderData<Usize>>(
+                env,
+                request_data.headers.pointer,
+                request_data.headers.length,
+            )?
+            .into_iter()
+            .try_for_each(|header: HeaderData<Usize>| {
+                if headers
+                    .insert(
+                        HeaderName::try_from(utils::read_from_memory_to_buffer(
+                            env,
+                            header.name.pointer,
+                            header.name.length,
+                        )?)?,
+                        HeaderValue::try_from(utils::read_from_memory_to_buffer(
+                            env,
+                            header.value.pointer,
+                            header.value.length,
+                        )?)?,
+                    )
+                    .is_none()
+                {
+                    Ok(())
+                } else {
+                    Err(anyhow!(
+                        "Duplicated header name found while preparing request!"
+                    ))
+                }
+            })?;
+        }
+
+        Ok(Some((request, pinned_addresses)))
+    }
+
     pub(super) fn send_request<Ctx, Usize>(
         mut env: Caller<'_, Ctx>,
         request_ptr: Usize,
@@ -161,124 +345,234 @@ mod implementation {
             let request_data: RequestData<Usize> =
                 utils::read_value_from_memory(&mut env, request_ptr)?;
 
-            let method: Method = Method::from_bytes(&utils::read_from_memory_to_buffer(
-                &mut env,
-                request_data.method.pointer,
-                request_data.method.length,
-            )?)?;
+            let Some((mut request, pinned_addresses)) =
+                read_request(&mut env, request_data).await?
+            else {
+                return Ok(0);
+            };
 
-            let url: String = String::from_utf8(utils::read_from_memory_to_buffer(
+            let body: Vec<u8> = utils::read_from_memory_to_buffer(
                 &mut env,
-                request_data.url.pointer,
-                request_data.url.length,
-            )?)?;
+                request_data.body.pointer,
+                request_data.body.length,
+            )?;
 
-            let body: Vec<u8> = utils::read_from_memory_to_buffer(
+            *request.body_mut() = Some(Body::from(body));
+
+            let inputs: CallInputs = recorded_call_inputs(&request);
+
+            let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+                env.data_mut().sdk_mut();
+
+            sdk.metrics_keeper.count_outbound_request();
+
+            let network: &mut NetworkKeeper<Ctx::Network> = &mut sdk.network_keeper;
+
+            let response: NetworkResponse = record::perform_async(&mut sdk.tape, inputs, || {
+                network.network.send_request(request, pinned_addresses)
+            })
+            .await
+            .context("Failed to send request through network provider!")?;
+
+            let Some(id) = network.insert_response(response) else {
+                bail!("Response table got filled while request was being sent!");
+            };
+
+            network.last_error = 0;
+
+            Ok(id.get())
+        })
+    }
+
+    pub(super) fn open_request<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        request_ptr: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<u64>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            let request_data: RequestData<Usize> =
+                utils::read_value_from_memory(&mut env, request_ptr)?;
+
+            let Some((request, pinned_addresses)) = read_request(&mut env, request_data).await?
+            else {
+                return Ok(0);
+            };
+
+            let initial_chunk: Vec<u8> = utils::read_from_memory_to_buffer(
                 &mut env,
                 request_data.body.pointer,
                 request_data.body.length,
             )?;
 
-            let mut request: Request = Request::new(method, Url::parse(&{ url })?);
+            let inputs: CallInputs = recorded_call_inputs(&request);
 
-            *request.body_mut() = Some(Body::from(body));
+            let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+                env.data_mut().sdk_mut();
+
+            sdk.metrics_keeper.count_outbound_request();
+
+            let network: &mut NetworkKeeper<Ctx::Network> = &mut sdk.network_keeper;
 
-            {
-                let headers: &mut HeaderMap<HeaderValue> = request.headers_mut();
-
-                utils::read_value_array_from_memory::<_, _, HeaderData<Usize>>(
-                    &mut env,
-                    request_data.headers.pointer,
-                    request_data.headers.length,
-                )?
-                .into_iter()
-                .try_for_each(|header: HeaderData<Usize>| {
-                    if headers
-                        .insert(
-                            HeaderName::try_from(utils::read_from_memory_to_buffer(
-                                &mut env,
-                                header.name.pointer,
-                                header.name.length,
-                            )?)?,
-                            HeaderValue::try_from(utils::read_from_memory_to_buffer(
-                                &mut env,
-                                header.value.pointer,
-                                header.value.length,
-                            )?)?,
-                        )
-                        .is_none()
-                    {
-                        Ok(())
-                    } else {
-                        Err(anyhow!(
-                            "Duplicated header name found while preparing request!"
-                        ))
+            let maybe_id: Option<NonZeroU64> = match &mut sdk.tape {
+                None => network.open_request(request, pinned_addresses, initial_chunk, None),
+                Some(tape) => match tape.replay(&inputs) {
+                    Some(response) => network.open_replayed_request(initial_chunk, response),
+                    None => {
+                        network.open_request(request, pinned_addresses, initial_chunk, Some(inputs))
                     }
-                })?;
+                },
+            };
+
+            let Some(id) = maybe_id else {
+                bail!("Response table got filled while request was being prepared!");
+            };
+
+            network.last_error = 0;
+
+            Ok(id.get())
+        })
+    }
+
+    pub(super) fn write_request_body<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        id: u64,
+        buffer_ptr: Usize,
+        buffer_length: Usize,
+    ) -> Box<dyn Future<Output = AnyResult<()>> + Send + '_>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        Box::new(async move {
+            let body_sender: MpscSender<Vec<u8>> =
+                env.data().sdk().network_keeper.request_body_sender(id)?;
+
+            let chunk: Vec<u8> =
+                utils::read_from_memory_to_buffer(&mut env, buffer_ptr, buffer_length)
+                    .context("Couldn't read request body chunk from memory!")?;
+
+            if chunk.is_empty() {
+                return Ok(());
             }
 
-            let response: Response = Client::new().execute(request).await?;
+            // Failure means the network provider stopped consuming the body,
+            // in which case the reason is reported when finishing the request.
+            let _: Result<(), _> = body_sender.send(chunk).await;
+
+            Ok(())
+        })
+    }
+
+    pub(super) fn finish_request<Ctx>(
+        mut env: Caller<'_, Ctx>,
+        id: u64,
+    ) -> Box<dyn Future<Output = AnyResult<u64>> + Send + '_>
+    where
+        Ctx: Context,
+    {
+        Box::new(async move {
+            let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+                env.data_mut().sdk_mut();
+
+            let (response, maybe_inputs): (
+                JoinHandle<AnyResult<NetworkResponse>>,
+                Option<CallInputs>,
+            ) = sdk.network_keeper.finish_request(id)?;
 
-            let network: &mut Network = &mut env.data_mut().sdk_mut().network;
+            let response: AnyResult<NetworkResponse> =
+                response.await.context("Task sending request failed!")?;
 
-            network.response = Some(NetworkResponse::new(
-                response.status().as_u16(),
-                response
-                    .bytes()
-                    .await
-                    .context("Failed to fetch network response's data!")?
-                    .into(),
-            ));
+            if let (Some(tape), Some(inputs)) = (&mut sdk.tape, maybe_inputs) {
+                tape.record(inputs, &response);
+            }
+
+            let response: NetworkResponse =
+                response.context("Failed to send request through network provider!")?;
 
-            network.response_id = if let Some(id) = network.response_id.checked_add(1) {
-                id
-            } else {
-                INIT_ID
+            let network: &mut NetworkKeeper<Ctx::Network> = &mut sdk.network_keeper;
+
+            let Some(id) = network.insert_response(response) else {
+                bail!("Response table got filled while request was being sent!");
             };
 
-            Ok(network.response_id.get())
+            Ok(id.get())
         })
     }
 
-    pub(super) fn response_status_code<Ctx>(env: Caller<'_, Ctx>, id: u64) -> AnyResult<u32>
+    pub(super) fn drop_request<Ctx>(mut env: Caller<'_, Ctx>, id: u64) -> AnyResult<()>
     where
         Ctx: Context,
     {
-        let network: &Network = &env.data().sdk().network;
+        env.data_mut().sdk_mut().network_keeper.abort_request(id)
+    }
 
-        if id != network.response_id.get() {
-            bail!("Expected request data access ID didn't match provided one!");
-        }
+    // ALLOW: Required by "wasmtime" API.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) fn request_error<Ctx>(env: Caller<'_, Ctx>) -> u32
+    where
+        Ctx: Context,
+    {
+        env.data().sdk().network_keeper.last_error
+    }
 
-        network
-            .response
-            .as_ref()
-            .map(|response| response.status_code().into())
-            .ok_or_else(|| anyhow!("No response with such ID exists!"))
+    pub(super) fn response_status_code<Ctx>(env: Caller<'_, Ctx>, id: u64) -> AnyResult<u32>
+    where
+        Ctx: Context,
+    {
+        env.data()
+            .sdk()
+            .network_keeper
+            .response(id)
+            .map(|response: &NetworkResponse| response.status_code().into())
             .context("Failed to return network response's status code!")
     }
 
-    pub(super) fn response_data_length<Ctx>(env: Caller<'_, Ctx>, id: u64) -> AnyResult<u64>
+    pub(super) fn response_headers_count<Ctx>(env: Caller<'_, Ctx>, id: u64) -> AnyResult<u64>
     where
         Ctx: Context,
     {
-        let network: &Network = &env.data().sdk().network;
+        env.data()
+            .sdk()
+            .network_keeper
+            .response(id)
+            .map(|response: &NetworkResponse| response.headers().len())
+            .and_then(u64::from_usize)
+    }
 
-        if id != network.response_id.get() {
-            bail!("Expected request data access ID didn't match provided one!");
-        }
+    fn response_header<Ctx>(ctx: &Ctx, id: u64, index: u64) -> AnyResult<&(HeaderName, HeaderValue)>
+    where
+        Ctx: Context,
+    {
+        let index: usize = index.into_usize()?;
+
+        ctx.sdk()
+            .network_keeper
+            .response(id)?
+            .headers()
+            .get(index)
+            .ok_or_else(|| anyhow!("No response header with such index exists!"))
+    }
 
-        network
-            .response
-            .as_ref()
-            .map(|response| response.data().len())
-            .ok_or_else(|| anyhow!("No response with such ID exists!"))
+    pub(super) fn response_header_name_length<Ctx>(
+        env: Caller<'_, Ctx>,
+        id: u64,
+        index: u64,
+    ) -> AnyResult<u64>
+    where
+        Ctx: Context,
+    {
+        response_header(env.data(), id, index)
+            .map(|(name, _): &(HeaderName, HeaderValue)| name.as_str().len())
             .and_then(u64::from_usize)
     }
 
-    pub(super) fn response_data<Ctx, Usize>(
+    pub(super) fn response_header_name<Ctx, Usize>(
         mut env: Caller<'_, Ctx>,
         id: u64,
+        index: u64,
         buffer_ptr: Usize,
         buffer_length: Usize,
     ) -> AnyResult<Usize>
@@ -286,21 +580,83 @@ mod implementation {
         Ctx: Context,
         Usize: WasmUsize,
     {
-        let network: &Network = &env.data().sdk().network;
+        utils::write_constant_to_memory(
+            &mut env,
+            |ctx: &Ctx| {
+                response_header(ctx, id, index)
+                    .map(|(name, _): &(HeaderName, HeaderValue)| name.as_str().as_bytes())
+            },
+            buffer_ptr,
+            buffer_length,
+        )
+        .context("Couldn't write response header's name to memory!")
+    }
 
-        if id != network.response_id.get() {
-            bail!("Expected request data access ID didn't match provided one!");
-        }
+    pub(super) fn response_header_value_length<Ctx>(
+        env: Caller<'_, Ctx>,
+        id: u64,
+        index: u64,
+    ) -> AnyResult<u64>
+    where
+        Ctx: Context,
+    {
+        response_header(env.data(), id, index)
+            .map(|(_, value): &(HeaderName, HeaderValue)| value.len())
+            .and_then(u64::from_usize)
+    }
+
+    pub(super) fn response_header_value<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        id: u64,
+        index: u64,
+        buffer_ptr: Usize,
+        buffer_length: Usize,
+    ) -> AnyResult<Usize>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        utils::write_constant_to_memory(
+            &mut env,
+            |ctx: &Ctx| {
+                response_header(ctx, id, index)
+                    .map(|(_, value): &(HeaderName, HeaderValue)| value.as_bytes())
+            },
+            buffer_ptr,
+            buffer_length,
+        )
+        .context("Couldn't write response header's value to memory!")
+    }
+
+    pub(super) fn response_data_length<Ctx>(env: Caller<'_, Ctx>, id: u64) -> AnyResult<u64>
+    where
+        Ctx: Context,
+    {
+        env.data()
+            .sdk()
+            .network_keeper
+            .response(id)
+            .map(|response: &NetworkResponse| response.data().len())
+            .and_then(u64::from_usize)
+    }
 
+    pub(super) fn response_data<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        id: u64,
+        buffer_ptr: Usize,
+        buffer_length: Usize,
+    ) -> AnyResult<Usize>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
         utils::write_from_buffer_to_memory(
             &mut env,
             |ctx: &mut Ctx| {
                 ctx.sdk_mut()
-                    .network
-                    .response
-                    .as_mut()
+                    .network_keeper
+                    .response_mut(id)
                     .map(NetworkResponse::data_mut)
-                    .ok_or_else(|| anyhow!("No response with such ID exists!"))
             },
             buffer_ptr,
             buffer_length,
@@ -315,16 +671,11 @@ mod implementation {
     where
         Ctx: Context,
     {
-        let network: &mut Network = &mut env.data_mut().sdk_mut().network;
-
-        if id != network.response_id.get() {
-            bail!("Expected request data access ID didn't match provided one!");
-        }
-
-        let response: &mut Vec<u8> = network
-            .response
-            .as_mut()
-            .ok_or_else(|| anyhow!("No response with such ID exists!"))
+        let response: &mut Vec<u8> = env
+            .data_mut()
+            .sdk_mut()
+            .network_keeper
+            .response_mut(id)
             .map(NetworkResponse::data_mut)?;
 
         let length: usize = length
@@ -344,17 +695,168 @@ mod implementation {
     where
         Ctx: Context,
     {
-        let network: &mut Network = &mut env.data_mut().sdk_mut().network;
+        env.data_mut()
+            .sdk_mut()
+            .network_keeper
+            .remove_response(id)
+            .map(drop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result as AnyResult;
+
+    use crate::{
+        egress::{EgressDenial, EgressPolicy},
+        kv::MemoryKv,
+        network::{RecordedRequest, ScriptedNetwork},
+        tests::{Guest, Unavailable},
+        NetworkKeeper, NetworkResponse, SdkContext, SdkEnv,
+    };
+
+    type NetContext = SdkContext<Unavailable, ScriptedNetwork, MemoryKv, Unavailable>;
+
+    /// Module exposing the network functions it imports, preparing a `POST`
+    /// request to `http://localhost/` with body `hello`, written to later
+    /// with ` world`.
+    const GUEST: &str = r#"(module
+        (import "sdk::net" "send_request~32" (func $send_request (param i32) (result i64)))
+        (import "sdk::net" "open_request~32" (func $open_request (param i32) (result i64)))
+        (import "sdk::net" "write_request_body~32"
+            (func $write_request_body (param i64 i32 i32)))
+        (import "sdk::net" "finish_request" (func $finish_request (param i64) (result i64)))
+        (import "sdk::net" "request_error" (func $request_error (result i32)))
+        (import "sdk::net" "response_status_code"
+            (func $response_status_code (param i64) (result i32)))
+        (import "sdk::net" "drop_response" (func $drop_response (param i64)))
+        (memory (export "memory") 1)
+        (data (i32.const 0) "POST")
+        (data (i32.const 16) "http://localhost/")
+        (data (i32.const 48) "hello")
+        (data (i32.const 64)
+            "\00\00\00\00\04\00\00\00"
+            "\10\00\00\00\11\00\00\00"
+            "\00\00\00\00\00\00\00\00"
+            "\30\00\00\00\05\00\00\00")
+        (data (i32.const 96) " world")
+        (func (export "send") (result i64) (call $send_request (i32.const 64)))
+        (func (export "open") (result i64) (call $open_request (i32.const 64)))
+        (func (export "write") (param i64)
+            (call $write_request_body (local.get 0) (i32.const 96) (i32.const 6)))
+        (func (export "finish") (param i64) (result i64) (call $finish_request (local.get 0)))
+        (func (export "request_error") (result i32) (call $request_error))
+        (func (export "status_code") (param i64) (result i32)
+            (call $response_status_code (local.get 0)))
+        (func (export "drop_response") (param i64) (call $drop_response (local.get 0))))"#;
+
+    /// Guest allowed to send requests to private destinations, e.g. `localhost`.
+    async fn new_guest(network: ScriptedNetwork) -> AnyResult<Guest<NetContext>> {
+        let mut env: SdkEnv<Unavailable, ScriptedNetwork, MemoryKv, Unavailable> =
+            SdkEnv::new(Unavailable, network, MemoryKv::new(), Unavailable);
+
+        env.set_egress_policy(EgressPolicy {
+            allow_private_destinations: true,
+            ..EgressPolicy::default()
+        });
+
+        Guest::new(env, GUEST).await
+    }
+
+    #[tokio::test]
+    async fn sent_and_opened_requests_are_answered_in_order() -> AnyResult<()> {
+        let network: ScriptedNetwork = ScriptedNetwork::new([
+            NetworkResponse::new(200, b"sent".to_vec()),
+            NetworkResponse::new(201, b"opened".to_vec()),
+        ]);
+
+        let mut guest: Guest<NetContext> = new_guest(network.clone()).await?;
+
+        let sent: u64 = guest.call("send", ()).await?;
 
-        if id != network.response_id.get() {
-            bail!("Expected request data access ID didn't match provided one!");
+        assert_ne!(sent, 0);
+        assert_eq!(guest.call::<_, u32>("status_code", sent).await?, 200);
+
+        let request: u64 = guest.call("open", ()).await?;
+
+        assert_ne!(request, 0);
+
+        guest.call::<_, ()>("write", request).await?;
+
+        let opened: u64 = guest.call("finish", request).await?;
+
+        assert_ne!(opened, 0);
+        assert_ne!(opened, sent);
+        assert_eq!(guest.call::<_, u32>("status_code", opened).await?, 201);
+
+        // Request is gone once finished.
+        assert!(guest.call::<_, u64>("finish", request).await.is_err());
+
+        let requests: Vec<RecordedRequest> = network.take_requests();
+
+        assert_eq!(requests.len(), 2);
+
+        for request in &requests {
+            assert_eq!(request.method, "POST");
+            assert_eq!(request.url.as_str(), "http://localhost/");
         }
 
-        network
-            .response
-            .as_mut()
-            .ok_or_else(|| anyhow!("No response with such ID exists!"))
-            .map(NetworkResponse::data_mut)
-            .map(|vec: &mut Vec<u8>| *vec = Vec::new())
+        assert_eq!(requests[0].body.as_deref(), Some(b"hello".as_slice()));
+
+        // Body of opened requests is streamed, thus not buffered upfront.
+        assert_eq!(requests[1].body, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn requests_are_refused_while_response_table_is_full() -> AnyResult<()> {
+        let network: ScriptedNetwork = ScriptedNetwork::new(
+            (0..=NetworkKeeper::<ScriptedNetwork>::MAX_RESPONSES)
+                .map(|_: usize| NetworkResponse::new(200, Vec::new())),
+        );
+
+        let mut guest: Guest<NetContext> = new_guest(network.clone()).await?;
+
+        // Opened requests take up an entry until they are finished.
+        let request: u64 = guest.call("open", ()).await?;
+
+        assert_ne!(request, 0);
+
+        let mut first_sent: u64 = 0;
+
+        for _ in 1..NetworkKeeper::<ScriptedNetwork>::MAX_RESPONSES {
+            let sent: u64 = guest.call("send", ()).await?;
+
+            assert_ne!(sent, 0);
+
+            if first_sent == 0 {
+                first_sent = sent;
+            }
+        }
+
+        assert_eq!(guest.call::<_, u64>("send", ()).await?, 0);
+        assert_eq!(
+            guest.call::<_, u32>("request_error", ()).await?,
+            EgressDenial::TooManyResponses as u32
+        );
+
+        assert_eq!(guest.call::<_, u64>("open", ()).await?, 0);
+
+        // Finishing request reuses its entry for the response.
+        assert_ne!(guest.call::<_, u64>("finish", request).await?, 0);
+
+        // Refused requests don't reach the network provider.
+        assert_eq!(
+            network.take_requests().len(),
+            NetworkKeeper::<ScriptedNetwork>::MAX_RESPONSES
+        );
+
+        guest.call::<_, ()>("drop_response", first_sent).await?;
+
+        assert_ne!(guest.call::<_, u64>("send", ()).await?, 0);
+        assert_eq!(guest.call::<_, u32>("request_error", ()).await?, 0);
+
+        Ok(())
     }
 }
@@ -1,11 +1,10 @@
 use anyhow::Result as AnyResult;
-use wasmtime::Linker;
 
-use crate::Context;
+use crate::{sdk_rt::linker::HostLinker, Context};
 
 const MODULE: &str = "sdk::panic";
 
-pub fn link_rt<Ctx>(linker: &mut Linker<Ctx>) -> AnyResult<()>
+pub(super) fn link_rt<Ctx>(linker: &mut HostLinker<'_, Ctx>) -> AnyResult<()>
 where
     Ctx: Context,
 {
@@ -42,6 +41,12 @@ mod implementation {
 
         module_response.is_error = true;
 
+        // Status code and headers were meant for a response that will never
+        // be completed.
+        module_response.status_code = None;
+
+        module_response.headers.clear();
+
         Err(PanickedError.into())
     }
 }
@@ -0,0 +1,75 @@
+use anyhow::Result as AnyResult;
+
+use crate::{sdk_rt::linker::HostLinker, Context};
+
+const MODULE: &str = "sdk::random";
+
+pub(super) fn link_rt<Ctx>(linker: &mut HostLinker<'_, Ctx>) -> AnyResult<()>
+where
+    Ctx: Context,
+{
+    linker.func_wrap(MODULE, "fill~32", implementation::fill::<_, u32>)?;
+    linker.func_wrap(MODULE, "fill~64", implementation::fill::<_, u64>)?;
+
+    Ok(())
+}
+
+mod implementation {
+    use anyhow::{anyhow, bail, Context as _, Result as AnyResult};
+    use wasmtime::{Caller, Memory};
+
+    use crate::{
+        record::{self, CallInputs},
+        sdk_rt::utils::{self, WasmUsize},
+        Context,
+    };
+
+    /// Fills provided buffer with bytes from the operating system's secure
+    /// random number generator.
+    pub(super) fn fill<Ctx, Usize>(
+        mut env: Caller<'_, Ctx>,
+        buffer_ptr: Usize,
+        buffer_length: Usize,
+    ) -> AnyResult<()>
+    where
+        Ctx: Context,
+        Usize: WasmUsize,
+    {
+        let memory: Memory = utils::get_memory(&mut env)?;
+
+        let start: usize = buffer_ptr.into_usize()?;
+
+        let end: usize = start
+            .checked_add(buffer_length.into_usize()?)
+            .ok_or_else(|| anyhow!("Buffer's end overflows address space!"))?;
+
+        let (data, ctx): (&mut [u8], &mut Ctx) = memory.data_and_store_mut(&mut env);
+
+        let buffer: &mut [u8] = data
+            .get_mut(start..end)
+            .ok_or_else(|| anyhow!("Buffer is out of memory's bounds!"))?;
+
+        let bytes: Vec<u8> = record::perform(
+            &mut ctx.sdk_mut().tape,
+            CallInputs::Random {
+                length: buffer.len(),
+            },
+            || {
+                let mut bytes: Vec<u8> = vec![0; buffer.len()];
+
+                getrandom::getrandom(&mut bytes)
+                    .map(|()| bytes)
+                    .map_err(Into::into)
+            },
+        )
+        .context("Failed to fill buffer with random bytes!")?;
+
+        if bytes.len() != buffer.len() {
+            bail!("Recorded random bytes don't match buffer's length!");
+        }
+
+        buffer.copy_from_slice(&bytes);
+
+        Ok(())
+    }
+}
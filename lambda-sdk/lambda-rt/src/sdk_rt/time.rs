@@ -0,0 +1,68 @@
+use anyhow::Result as AnyResult;
+
+use crate::{sdk_rt::linker::HostLinker, Context};
+
+const MODULE: &str = "sdk::time";
+
+pub(super) fn link_rt<Ctx>(linker: &mut HostLinker<'_, Ctx>) -> AnyResult<()>
+where
+    Ctx: Context,
+{
+    linker.func_wrap(MODULE, "wall_clock", implementation::wall_clock::<_>)?;
+
+    linker.func_wrap(MODULE, "monotonic", implementation::monotonic::<_>)?;
+
+    Ok(())
+}
+
+mod implementation {
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+    use anyhow::{Context as _, Result as AnyResult};
+    use wasmtime::Caller;
+
+    use crate::{
+        record::{self, CallInputs},
+        Context, SdkEnv,
+    };
+
+    fn nanos(duration: Duration) -> AnyResult<u64> {
+        duration
+            .as_nanos()
+            .try_into()
+            .context("Duration in nanoseconds doesn't fit in 64-bit integer!")
+    }
+
+    /// Returns nanoseconds elapsed since the UNIX epoch.
+    pub(super) fn wall_clock<Ctx>(mut env: Caller<'_, Ctx>) -> AnyResult<u64>
+    where
+        Ctx: Context,
+    {
+        record::perform(
+            &mut env.data_mut().sdk_mut().tape,
+            CallInputs::WallClock,
+            || {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .context("System clock is set before the UNIX epoch!")
+                    .and_then(nanos)
+            },
+        )
+    }
+
+    /// Returns nanoseconds elapsed since the instance was created. Never
+    /// decreases, unlike the wall clock.
+    pub(super) fn monotonic<Ctx>(mut env: Caller<'_, Ctx>) -> AnyResult<u64>
+    where
+        Ctx: Context,
+    {
+        let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+            env.data_mut().sdk_mut();
+
+        let clock_origin: Instant = sdk.clock_origin;
+
+        record::perform(&mut sdk.tape, CallInputs::Monotonic, || {
+            nanos(clock_origin.elapsed())
+        })
+    }
+}
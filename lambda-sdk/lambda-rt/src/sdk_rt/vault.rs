@@ -1,11 +1,10 @@
 use anyhow::Result as AnyResult;
-use wasmtime::Linker;
 
-use crate::Context;
+use crate::{sdk_rt::linker::HostLinker, Context};
 
 const MODULE: &str = "sdk::vault";
 
-pub fn link_rt<Ctx>(linker: &mut Linker<Ctx>) -> AnyResult<()>
+pub(super) fn link_rt<Ctx>(linker: &mut HostLinker<'_, Ctx>) -> AnyResult<()>
 where
     Ctx: Context,
 {
@@ -45,8 +44,9 @@ mod implementation {
     use wasmtime::Caller;
 
     use crate::{
+        record::{self, CallInputs},
         sdk_rt::utils::{self, WasmUsize},
-        Context, VaultKeeper, VaultProvider, INIT_ID,
+        Context, SdkEnv, VaultKeeper, VaultProvider, INIT_ID,
     };
 
     pub(super) fn fetch_secret<Ctx, Usize>(
@@ -69,13 +69,20 @@ mod implementation {
                 identifier_length,
             )?)?;
 
-            let keeper: &mut VaultKeeper<Ctx::Vault> = &mut env.data_mut().sdk_mut().vault_keeper;
+            let sdk: &mut SdkEnv<Ctx::Vault, Ctx::Network, Ctx::Kv, Ctx::Invoker> =
+                env.data_mut().sdk_mut();
 
-            keeper.secret = keeper
-                .vault
-                .fetch_secret(identifier)
-                .await
-                .context("Failed to fetch secret from vault provider!")?;
+            let keeper: &mut VaultKeeper<Ctx::Vault> = &mut sdk.vault_keeper;
+
+            keeper.secret = record::perform_async(
+                &mut sdk.tape,
+                CallInputs::FetchSecret {
+                    identifier: identifier.clone(),
+                },
+                || keeper.vault.fetch_secret(identifier),
+            )
+            .await
+            .context("Failed to fetch secret from vault provider!")?;
 
             if keeper.secret.is_none() {
                 return Ok(0);
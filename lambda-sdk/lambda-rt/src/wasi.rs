@@ -0,0 +1,243 @@
+use std::path::PathBuf;
+
+/// Configuration of WASI for instances of a module. Modules importing WASI are
+/// rejected unless it is enabled for them. Instances get a sandboxed
+/// filesystem, holding only the preopened directories, while output written to
+/// standard output and standard error is forwarded to the log pipeline.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct WasiConfig {
+    pub env: Vec<(String, String)>,
+    /// Host directories, along with the paths they are exposed to the module
+    /// under. Their contents are read-only to the module.
+    pub preopens: Vec<(PathBuf, String)>,
+}
+
+/// Names of the modules WASI functions are imported from.
+pub(crate) const WASI_MODULES: [&str; 2] = ["wasi_snapshot_preview1", "wasi_unstable"];
+
+#[cfg(feature = "wasi")]
+pub(crate) use self::state::WasiState;
+
+#[cfg(feature = "wasi")]
+mod state {
+    use std::{
+        any::Any,
+        fmt::{Debug, Formatter, Result as FmtResult},
+        io::{Result as IoResult, Write},
+        mem::take,
+        path::PathBuf,
+        sync::{Arc, Mutex, MutexGuard, PoisonError},
+    };
+
+    use anyhow::{Context as _, Result as AnyResult};
+    use async_trait::async_trait;
+    use wasi_common::{
+        dir::{OpenResult, ReaddirCursor, ReaddirEntity, WasiDir},
+        file::{FdFlags, Filestat, OFlags},
+        pipe::WritePipe,
+        Error as WasiError, ErrorExt as _,
+    };
+    use wasmtime_wasi::{
+        sync::{ambient_authority, dir::Dir as CapStdDir, Dir, WasiCtxBuilder},
+        WasiCtx,
+    };
+
+    use super::WasiConfig;
+
+    /// Directory refusing to open files for writing, to create files and to
+    /// modify itself, as do directories opened through it.
+    struct ReadOnlyDir(Box<dyn WasiDir>);
+
+    #[async_trait]
+    impl WasiDir for ReadOnlyDir {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        async fn open_file(
+            &self,
+            symlink_follow: bool,
+            path: &str,
+            oflags: OFlags,
+            read: bool,
+            write: bool,
+            fdflags: FdFlags,
+        ) -> Result<OpenResult, WasiError> {
+            if write
+                || oflags.intersects(OFlags::CREATE | OFlags::TRUNCATE)
+                || fdflags.contains(FdFlags::APPEND)
+            {
+                return Err(WasiError::perm());
+            }
+
+            Ok(
+                match self
+                    .0
+                    .open_file(symlink_follow, path, oflags, read, write, fdflags)
+                    .await?
+                {
+                    OpenResult::Dir(dir) => OpenResult::Dir(Box::new(Self(dir))),
+                    file @ OpenResult::File(_) => file,
+                },
+            )
+        }
+
+        async fn readdir(
+            &self,
+            cursor: ReaddirCursor,
+        ) -> Result<Box<dyn Iterator<Item = Result<ReaddirEntity, WasiError>> + Send>, WasiError>
+        {
+            self.0.readdir(cursor).await
+        }
+
+        async fn read_link(&self, path: &str) -> Result<PathBuf, WasiError> {
+            self.0.read_link(path).await
+        }
+
+        async fn get_filestat(&self) -> Result<Filestat, WasiError> {
+            self.0.get_filestat().await
+        }
+
+        async fn get_path_filestat(
+            &self,
+            path: &str,
+            follow_symlinks: bool,
+        ) -> Result<Filestat, WasiError> {
+            self.0.get_path_filestat(path, follow_symlinks).await
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct OutputBufferState {
+        data: Vec<u8>,
+        overflowed: bool,
+    }
+
+    /// Buffer collecting output written by a module, discarding anything
+    /// beyond its capacity.
+    #[derive(Debug, Clone, Default)]
+    pub(crate) struct OutputBuffer {
+        state: Arc<Mutex<OutputBufferState>>,
+        capacity: usize,
+    }
+
+    impl OutputBuffer {
+        fn new(capacity: usize) -> Self {
+            Self {
+                state: Arc::default(),
+                capacity,
+            }
+        }
+
+        fn state(&self) -> MutexGuard<'_, OutputBufferState> {
+            self.state.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        /// Takes collected output, along with whether any was discarded.
+        pub(crate) fn take(&self) -> (Vec<u8>, bool) {
+            let mut state: MutexGuard<'_, OutputBufferState> = self.state();
+
+            (take(&mut state.data), take(&mut state.overflowed))
+        }
+    }
+
+    impl Write for OutputBuffer {
+        fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+            let mut state: MutexGuard<'_, OutputBufferState> = self.state();
+
+            let available: usize = self.capacity.saturating_sub(state.data.len());
+
+            if available < buf.len() {
+                state.overflowed = true;
+            }
+
+            state
+                .data
+                .extend_from_slice(&buf[..buf.len().min(available)]);
+
+            // Reported as fully written so modules don't retry discarded output.
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> IoResult<()> {
+            Ok(())
+        }
+    }
+
+    pub(crate) struct WasiState {
+        pub(crate) ctx: WasiCtx,
+        pub(crate) stdout: OutputBuffer,
+        pub(crate) stderr: OutputBuffer,
+    }
+
+    impl Debug for WasiState {
+        fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+            f.debug_struct("WasiState")
+                .field("stdout", &self.stdout)
+                .field("stderr", &self.stderr)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl WasiState {
+        fn builder(stdout: &OutputBuffer, stderr: &OutputBuffer) -> WasiCtxBuilder {
+            WasiCtxBuilder::new()
+                .stdout(Box::new(WritePipe::new(stdout.clone())))
+                .stderr(Box::new(WritePipe::new(stderr.clone())))
+        }
+
+        pub(crate) fn empty() -> Self {
+            let stdout: OutputBuffer = OutputBuffer::default();
+
+            let stderr: OutputBuffer = OutputBuffer::default();
+
+            Self {
+                ctx: Self::builder(&stdout, &stderr).build(),
+                stdout,
+                stderr,
+            }
+        }
+
+        /// Creates state with output buffers of provided capacity.
+        /// # Errors
+        /// Error will occur when environment variables are malformed, or when
+        /// preopened directories can't be opened.
+        pub(crate) fn new(config: &WasiConfig, output_capacity: usize) -> AnyResult<Self> {
+            let stdout: OutputBuffer = OutputBuffer::new(output_capacity);
+
+            let stderr: OutputBuffer = OutputBuffer::new(output_capacity);
+
+            let mut ctx: WasiCtx = config
+                .env
+                .iter()
+                .try_fold(
+                    Self::builder(&stdout, &stderr),
+                    |builder: WasiCtxBuilder, (name, value): &(String, String)| {
+                        builder.env(name, value)
+                    },
+                )?
+                .build();
+
+            for (host_path, guest_path) in &config.preopens {
+                let dir: Dir =
+                    Dir::open_ambient_dir(host_path, ambient_authority()).with_context(|| {
+                        format!(
+                            "Failed to open preopened directory {}!",
+                            host_path.display()
+                        )
+                    })?;
+
+                ctx.push_preopened_dir(
+                    Box::new(ReadOnlyDir(Box::new(CapStdDir::from_cap_std(dir)))),
+                    guest_path,
+                )?;
+            }
+
+            Ok(Self {
+                ctx,
+                stdout,
+                stderr,
+            })
+        }
+    }
+}
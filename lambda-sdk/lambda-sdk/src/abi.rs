@@ -0,0 +1,179 @@
+use std::num::NonZeroU64;
+
+use lambda_abi::Type;
+
+use crate::interops::Pointer;
+
+/// Type of host functions' parameters and results, as described by the ABI.
+pub(crate) trait AbiType {
+    const TYPE: Type;
+}
+
+impl AbiType for u32 {
+    const TYPE: Type = Type::I32;
+}
+
+impl AbiType for u64 {
+    const TYPE: Type = Type::I64;
+}
+
+impl AbiType for NonZeroU64 {
+    const TYPE: Type = Type::I64;
+}
+
+impl AbiType for Option<NonZeroU64> {
+    const TYPE: Type = Type::I64;
+}
+
+impl AbiType for usize {
+    const TYPE: Type = Type::Usize;
+}
+
+impl<T: ?Sized, const MUTABILITY: bool> AbiType for Pointer<'_, T, MUTABILITY> {
+    const TYPE: Type = Type::Usize;
+}
+
+impl<T: ?Sized, const MUTABILITY: bool> AbiType for Option<Pointer<'_, T, MUTABILITY>> {
+    const TYPE: Type = Type::Usize;
+}
+
+/// Declares functions imported from the runtime, checking each against the
+/// ABI at compile time.
+///
+/// Functions marked with `#[pointer_sized]` are imported under the variant
+/// matching the target's pointer width.
+macro_rules! host_functions {
+    (
+        #[link(wasm_import_module = $module: literal)]
+        extern "C" {
+            $($functions: tt)*
+        }
+    ) => {
+        $crate::abi::host_functions!(@functions $module [] $($functions)*);
+    };
+    (@functions $module: literal [$($declared: ident)*]) => {
+        #[cfg(test)]
+        pub(crate) const DECLARED: (&str, &[&str]) = ($module, &[$(stringify!($declared)),*]);
+    };
+    (
+        @functions $module: literal [$($declared: ident)*]
+        $(#[$pointer_sized: ident])?
+        $vis: vis fn $name: ident($($param: ident: $param_type: ty),* $(,)?) -> !;
+        $($rest: tt)*
+    ) => {
+        $crate::abi::host_functions!(
+            @function $module [$($pointer_sized)?] $vis fn $name($($param: $param_type),*) [!] []
+        );
+
+        $crate::abi::host_functions!(@functions $module [$($declared)* $name] $($rest)*);
+    };
+    (
+        @functions $module: literal [$($declared: ident)*]
+        $(#[$pointer_sized: ident])?
+        $vis: vis fn $name: ident($($param: ident: $param_type: ty),* $(,)?) -> $result: ty;
+        $($rest: tt)*
+    ) => {
+        $crate::abi::host_functions!(
+            @function $module [$($pointer_sized)?] $vis fn $name($($param: $param_type),*)
+                [$result] [$result]
+        );
+
+        $crate::abi::host_functions!(@functions $module [$($declared)* $name] $($rest)*);
+    };
+    (
+        @functions $module: literal [$($declared: ident)*]
+        $(#[$pointer_sized: ident])?
+        $vis: vis fn $name: ident($($param: ident: $param_type: ty),* $(,)?);
+        $($rest: tt)*
+    ) => {
+        $crate::abi::host_functions!(
+            @function $module [$($pointer_sized)?] $vis fn $name($($param: $param_type),*) [] []
+        );
+
+        $crate::abi::host_functions!(@functions $module [$($declared)* $name] $($rest)*);
+    };
+    (
+        @function $module: literal [pointer_sized] $vis: vis fn $name: ident $params: tt
+            [$($return: ty)?] [$($result: ty)?]
+    ) => {
+        #[link(wasm_import_module = $module)]
+        extern "C" {
+            #[cfg_attr(
+                target_pointer_width = "32",
+                link_name = concat!(stringify!($name), "~32")
+            )]
+            #[cfg_attr(
+                target_pointer_width = "64",
+                link_name = concat!(stringify!($name), "~64")
+            )]
+            $vis fn $name $params $(-> $return)?;
+        }
+
+        $crate::abi::host_functions!(@check $module $name true $params [$($result)?]);
+    };
+    (
+        @function $module: literal [] $vis: vis fn $name: ident $params: tt
+            [$($return: ty)?] [$($result: ty)?]
+    ) => {
+        #[link(wasm_import_module = $module)]
+        extern "C" {
+            $vis fn $name $params $(-> $return)?;
+        }
+
+        $crate::abi::host_functions!(@check $module $name false $params [$($result)?]);
+    };
+    (
+        @check $module: literal $name: ident $pointer_sized: literal
+            ($($param: ident: $param_type: ty),*) [$($result: ty)?]
+    ) => {
+        const _: () = lambda_abi::Function::assert_provided(
+            $module,
+            stringify!($name),
+            $pointer_sized,
+            &[$(<$param_type as $crate::abi::AbiType>::TYPE),*],
+            &[$(<$result as $crate::abi::AbiType>::TYPE)?],
+        );
+    };
+}
+
+pub(crate) use host_functions;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use lambda_abi::{Function, FUNCTIONS};
+
+    #[test]
+    fn every_provided_function_is_declared() {
+        let declared: BTreeSet<(&str, &str)> = [
+            crate::context::external::DECLARED,
+            crate::crypto::external::DECLARED,
+            crate::debug::external::DECLARED,
+            crate::invoke::external::DECLARED,
+            crate::io::external::DECLARED,
+            crate::kv::external::DECLARED,
+            crate::log::external::DECLARED,
+            crate::net::external::DECLARED,
+            crate::panic::external::DECLARED,
+            crate::random::external::DECLARED,
+            crate::time::external::DECLARED,
+            crate::vault::external::DECLARED,
+        ]
+        .into_iter()
+        .flat_map(|(module, names): (&str, &[&str])| {
+            names.iter().map(move |&name: &&str| (module, name))
+        })
+        .collect();
+
+        let missing: Vec<&Function> = FUNCTIONS
+            .iter()
+            .filter(|function: &&Function| !declared.contains(&(function.module, function.name)))
+            .collect();
+
+        assert!(
+            missing.is_empty(),
+            "Functions provided by the runtime aren't declared: {missing:?}"
+        );
+    }
+}
@@ -2,7 +2,7 @@ use std::num::NonZeroU64;
 
 use crate::interops::Pointer;
 
-mod external;
+pub(crate) mod external;
 
 pub struct User {
     offset: u64,
@@ -33,7 +33,7 @@ impl User {
                 )
             };
 
-            self.length += u64::try_from(read_length)?;
+            self.offset += u64::try_from(read_length)?;
 
             read_length
         } else {
@@ -0,0 +1,78 @@
+use crate::{abi::host_functions, interops::Pointer};
+
+host_functions! {
+    #[link(wasm_import_module = "sdk::crypto")]
+    extern "C" {
+        #[pointer_sized]
+        pub(super) fn ed25519_public_key(
+            key_id: Pointer<'_, u8, false>,
+            key_id_len: usize,
+            public_key: Pointer<'_, u8, true>,
+        ) -> u32;
+
+        #[pointer_sized]
+        pub(super) fn ed25519_sign(
+            key_id: Pointer<'_, u8, false>,
+            key_id_len: usize,
+            message: Pointer<'_, u8, false>,
+            message_len: usize,
+            signature: Pointer<'_, u8, true>,
+        ) -> u32;
+
+        #[pointer_sized]
+        pub(super) fn ed25519_verify(
+            key_id: Pointer<'_, u8, false>,
+            key_id_len: usize,
+            message: Pointer<'_, u8, false>,
+            message_len: usize,
+            signature: Pointer<'_, u8, false>,
+        ) -> u32;
+
+        #[pointer_sized]
+        pub(super) fn hmac_sha256(
+            key_id: Pointer<'_, u8, false>,
+            key_id_len: usize,
+            message: Pointer<'_, u8, false>,
+            message_len: usize,
+            mac: Pointer<'_, u8, true>,
+        ) -> u32;
+
+        #[pointer_sized]
+        pub(super) fn aes256_gcm_encrypt(
+            key_id: Pointer<'_, u8, false>,
+            key_id_len: usize,
+            nonce: Pointer<'_, u8, false>,
+            associated_data: Pointer<'_, u8, false>,
+            associated_data_len: usize,
+            plaintext: Pointer<'_, u8, false>,
+            plaintext_len: usize,
+            ciphertext: Pointer<'_, u8, true>,
+        ) -> u32;
+
+        #[pointer_sized]
+        pub(super) fn aes256_gcm_decrypt(
+            key_id: Pointer<'_, u8, false>,
+            key_id_len: usize,
+            nonce: Pointer<'_, u8, false>,
+            associated_data: Pointer<'_, u8, false>,
+            associated_data_len: usize,
+            ciphertext: Pointer<'_, u8, false>,
+            ciphertext_len: usize,
+            plaintext: Pointer<'_, u8, true>,
+        ) -> u32;
+
+        #[pointer_sized]
+        pub(super) fn sha256(
+            data: Pointer<'_, u8, false>,
+            data_len: usize,
+            digest: Pointer<'_, u8, true>,
+        );
+
+        #[pointer_sized]
+        pub(super) fn sha512(
+            data: Pointer<'_, u8, false>,
+            data_len: usize,
+            digest: Pointer<'_, u8, true>,
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use crate::interops::SlicePointer;
+
+pub(crate) mod external;
+
+pub const ED25519_PUBLIC_KEY_LENGTH: usize = 32;
+
+pub const ED25519_SIGNATURE_LENGTH: usize = 64;
+
+pub const HMAC_SHA256_LENGTH: usize = 32;
+
+pub const AES256_GCM_NONCE_LENGTH: usize = 12;
+
+/// Length of the authentication tag appended to ciphertexts.
+pub const AES256_GCM_TAG_LENGTH: usize = 16;
+
+/// Reason for a cryptographic operation on a vault key failing.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CryptoError {
+    /// No key with provided identifier exists in the vault.
+    NoSuchKey,
+    /// Key is not valid for the requested algorithm, e.g. an Ed25519 key which
+    /// isn't a 32-byte seed.
+    InvalidKey,
+    /// Signature or ciphertext failed verification.
+    VerificationFailed,
+    /// Data exceeds the maximum length supported by the algorithm.
+    DataTooLong,
+    Unknown(u32),
+}
+
+impl CryptoError {
+    fn from_code(code: u32) -> Result<(), Self> {
+        match code {
+            0 => Ok(()),
+            1 => Err(Self::NoSuchKey),
+            2 => Err(Self::InvalidKey),
+            3 => Err(Self::VerificationFailed),
+            4 => Err(Self::DataTooLong),
+            code => Err(Self::Unknown(code)),
+        }
+    }
+}
+
+impl Display for CryptoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::NoSuchKey => f.write_str("No key with such identifier exists!"),
+            Self::InvalidKey => f.write_str("Key is not valid for the requested algorithm!"),
+            Self::VerificationFailed => f.write_str("Signature or ciphertext failed verification!"),
+            Self::DataTooLong => {
+                f.write_str("Data exceeds the maximum length supported by the algorithm!")
+            }
+            Self::Unknown(code) => {
+                write!(
+                    f,
+                    "Cryptographic operation failed with unknown error code {code}!"
+                )
+            }
+        }
+    }
+}
+
+impl Error for CryptoError {}
+
+/// Returns public key of the Ed25519 key stored in the vault under provided
+/// identifier.
+pub fn ed25519_public_key(key_id: &str) -> Result<[u8; ED25519_PUBLIC_KEY_LENGTH], CryptoError> {
+    let mut public_key: [u8; ED25519_PUBLIC_KEY_LENGTH] = [0; ED25519_PUBLIC_KEY_LENGTH];
+
+    CryptoError::from_code(unsafe {
+        external::ed25519_public_key(
+            SlicePointer::from(key_id.as_bytes()).into(),
+            key_id.len(),
+            SlicePointer::from(public_key.as_mut_slice()).into(),
+        )
+    })
+    .map(|()| public_key)
+}
+
+/// Signs message with the Ed25519 key stored in the vault under provided
+/// identifier, without the key leaving the host.
+pub fn ed25519_sign(
+    key_id: &str,
+    message: &[u8],
+) -> Result<[u8; ED25519_SIGNATURE_LENGTH], CryptoError> {
+    let mut signature: [u8; ED25519_SIGNATURE_LENGTH] = [0; ED25519_SIGNATURE_LENGTH];
+
+    CryptoError::from_code(unsafe {
+        external::ed25519_sign(
+            SlicePointer::from(key_id.as_bytes()).into(),
+            key_id.len(),
+            SlicePointer::from(message).into(),
+            message.len(),
+            SlicePointer::from(signature.as_mut_slice()).into(),
+        )
+    })
+    .map(|()| signature)
+}
+
+/// Returns whether signature of message is valid for the Ed25519 key stored in
+/// the vault under provided identifier.
+pub fn ed25519_verify(
+    key_id: &str,
+    message: &[u8],
+    signature: &[u8; ED25519_SIGNATURE_LENGTH],
+) -> Result<bool, CryptoError> {
+    match CryptoError::from_code(unsafe {
+        external::ed25519_verify(
+            SlicePointer::from(key_id.as_bytes()).into(),
+            key_id.len(),
+            SlicePointer::from(message).into(),
+            message.len(),
+            SlicePointer::from(signature.as_slice()).into(),
+        )
+    }) {
+        Ok(()) => Ok(true),
+        Err(CryptoError::VerificationFailed) => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+/// Computes HMAC-SHA256 of message with the key stored in the vault under
+/// provided identifier.
+pub fn hmac_sha256(key_id: &str, message: &[u8]) -> Result<[u8; HMAC_SHA256_LENGTH], CryptoError> {
+    let mut mac: [u8; HMAC_SHA256_LENGTH] = [0; HMAC_SHA256_LENGTH];
+
+    CryptoError::from_code(unsafe {
+        external::hmac_sha256(
+            SlicePointer::from(key_id.as_bytes()).into(),
+            key_id.len(),
+            SlicePointer::from(message).into(),
+            message.len(),
+            SlicePointer::from(mac.as_mut_slice()).into(),
+        )
+    })
+    .map(|()| mac)
+}
+
+/// Encrypts plaintext with the AES-256-GCM key stored in the vault under
+/// provided identifier. Returns ciphertext followed by the authentication tag.
+///
+/// Nonce must never be reused with the same key.
+pub fn aes256_gcm_encrypt(
+    key_id: &str,
+    nonce: &[u8; AES256_GCM_NONCE_LENGTH],
+    associated_data: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let mut ciphertext: Vec<u8> = vec![0; plaintext.len() + AES256_GCM_TAG_LENGTH];
+
+    CryptoError::from_code(unsafe {
+        external::aes256_gcm_encrypt(
+            SlicePointer::from(key_id.as_bytes()).into(),
+            key_id.len(),
+            SlicePointer::from(nonce.as_slice()).into(),
+            SlicePointer::from(associated_data).into(),
+            associated_data.len(),
+            SlicePointer::from(plaintext).into(),
+            plaintext.len(),
+            SlicePointer::from(ciphertext.as_mut_slice()).into(),
+        )
+    })
+    .map(|()| ciphertext)
+}
+
+/// Decrypts ciphertext, followed by the authentication tag, with the
+/// AES-256-GCM key stored in the vault under provided identifier.
+pub fn aes256_gcm_decrypt(
+    key_id: &str,
+    nonce: &[u8; AES256_GCM_NONCE_LENGTH],
+    associated_data: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let Some(plaintext_len) = ciphertext.len().checked_sub(AES256_GCM_TAG_LENGTH) else {
+        return Err(CryptoError::VerificationFailed);
+    };
+
+    let mut plaintext: Vec<u8> = vec![0; plaintext_len];
+
+    CryptoError::from_code(unsafe {
+        external::aes256_gcm_decrypt(
+            SlicePointer::from(key_id.as_bytes()).into(),
+            key_id.len(),
+            SlicePointer::from(nonce.as_slice()).into(),
+            SlicePointer::from(associated_data).into(),
+            associated_data.len(),
+            SlicePointer::from(ciphertext).into(),
+            ciphertext.len(),
+            SlicePointer::from(plaintext.as_mut_slice()).into(),
+        )
+    })
+    .map(|()| plaintext)
+}
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut digest: [u8; 32] = [0; 32];
+
+    unsafe {
+        external::sha256(
+            SlicePointer::from(data).into(),
+            data.len(),
+            SlicePointer::from(digest.as_mut_slice()).into(),
+        );
+    }
+
+    digest
+}
+
+pub fn sha512(data: &[u8]) -> [u8; 64] {
+    let mut digest: [u8; 64] = [0; 64];
+
+    unsafe {
+        external::sha512(
+            SlicePointer::from(data).into(),
+            data.len(),
+            SlicePointer::from(digest.as_mut_slice()).into(),
+        );
+    }
+
+    digest
+}
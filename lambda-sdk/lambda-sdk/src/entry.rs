@@ -1,5 +1,26 @@
+/// Exports provided function as the module's entry point. Alternatively,
+/// exports a list of named handlers, which routes select by name.
+///
+/// ```ignore
+/// entry! {
+///     create => create,
+///     delete => delete,
+/// }
+/// ```
 #[macro_export]
 macro_rules! entry {
+    ($($handler: ident => $main: expr),+ $(,)?) => {
+        $(
+            const _: () = {
+                #[export_name = concat!("entry::", stringify!($handler))]
+                pub extern "C" fn handler() {
+                    $crate::panic::install_handler();
+
+                    let _: () = $main();
+                }
+            };
+        )+
+    };
     ($main: expr) => {
         #[no_mangle]
         pub extern "C" fn entry() {
@@ -9,3 +30,51 @@ macro_rules! entry {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic, process,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+
+    crate::entry! {
+        first => || {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+        },
+        second => || {
+            CALLS.fetch_add(10, Ordering::SeqCst);
+        },
+    }
+
+    /// Stands in for the host's panic function, which only modules import.
+    #[cfg_attr(target_pointer_width = "32", export_name = "panic~32")]
+    #[cfg_attr(target_pointer_width = "64", export_name = "panic~64")]
+    extern "C" fn host_panic(_: *const u8, _: usize) -> ! {
+        process::abort()
+    }
+
+    extern "C" {
+        #[link_name = "entry::first"]
+        fn first();
+
+        #[link_name = "entry::second"]
+        fn second();
+    }
+
+    #[test]
+    fn handlers_are_exported_by_name() {
+        unsafe {
+            first();
+            second();
+            second();
+        }
+
+        // Restores default panic hook replaced by the handlers.
+        drop(panic::take_hook());
+
+        assert_eq!(CALLS.load(Ordering::SeqCst), 21);
+    }
+}
@@ -0,0 +1,33 @@
+use std::num::NonZeroU64;
+
+use crate::{abi::host_functions, interops::Pointer};
+
+host_functions! {
+    #[link(wasm_import_module = "sdk::invoke")]
+    extern "C" {
+        #[pointer_sized]
+        pub(super) fn invoke(
+            module_id: Pointer<'_, u8, false>,
+            module_id_len: usize,
+            data: Pointer<'_, u8, false>,
+            data_len: usize,
+        ) -> u64;
+
+        pub(super) fn invoke_error() -> u32;
+
+        pub(super) fn response_is_error(id: NonZeroU64) -> u32;
+
+        pub(super) fn response_status_code(id: NonZeroU64) -> u32;
+
+        pub(super) fn response_data_length(id: NonZeroU64) -> u64;
+
+        #[pointer_sized]
+        pub(super) fn read_response_data(
+            id: NonZeroU64,
+            buf: Pointer<'_, u8, true>,
+            buf_len: usize,
+        ) -> usize;
+
+        pub(super) fn drop_response(id: NonZeroU64);
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+    num::NonZeroU64,
+};
+
+use crate::interops::{read_with_id, SlicePointer};
+
+pub(crate) mod external;
+
+/// Invokes another module with provided data, propagating the current
+/// request's sender. Only one response can be held at a time; it has to be
+/// dropped before invoking another module.
+pub fn invoke(module_id: &str, data: &[u8]) -> Result<Response, InvokeError> {
+    let id: u64 = unsafe {
+        external::invoke(
+            SlicePointer::from(module_id.as_bytes()).into(),
+            module_id.len(),
+            SlicePointer::from(data).into(),
+            data.len(),
+        )
+    };
+
+    if let Some(id) = NonZeroU64::new(id) {
+        Ok(Response {
+            id,
+            length: unsafe { external::response_data_length(id) },
+        })
+    } else {
+        Err(InvokeError::from_code(unsafe { external::invoke_error() }))
+    }
+}
+
+/// Reason for an invocation failing.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum InvokeError {
+    /// No module with provided identifier is configured.
+    UnknownModule,
+    /// Invoked module already takes part in the current call chain.
+    CycleDetected,
+    /// Call chain would exceed the maximum call depth.
+    CallDepthExceeded,
+    /// Invoked module failed to produce a response.
+    Failed,
+    /// Invoked module exhausted its CPU budget.
+    CpuBudgetExhausted,
+    /// Invoked module's response exceeds the maximum length.
+    ResponseTooLarge,
+    /// Current request has no sender to propagate.
+    NoSender,
+    /// Invoked module only exports named handlers, which can't be invoked.
+    NoDefaultEntryPoint,
+    Unknown(u32),
+}
+
+impl InvokeError {
+    fn from_code(code: u32) -> Self {
+        match code {
+            1 => Self::UnknownModule,
+            2 => Self::CycleDetected,
+            3 => Self::CallDepthExceeded,
+            4 => Self::Failed,
+            5 => Self::CpuBudgetExhausted,
+            6 => Self::ResponseTooLarge,
+            7 => Self::NoSender,
+            8 => Self::NoDefaultEntryPoint,
+            code => Self::Unknown(code),
+        }
+    }
+}
+
+impl Display for InvokeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::UnknownModule => f.write_str("No module with such identifier is configured!"),
+            Self::CycleDetected => f.write_str("Invoked module is already part of the call chain!"),
+            Self::CallDepthExceeded => f.write_str("Call chain exceeds maximum call depth!"),
+            Self::Failed => f.write_str("Invoked module failed to produce a response!"),
+            Self::CpuBudgetExhausted => f.write_str("Invoked module exhausted its CPU budget!"),
+            Self::ResponseTooLarge => {
+                f.write_str("Invoked module's response exceeds maximum length!")
+            }
+            Self::NoSender => f.write_str("Invoking request has no sender to propagate!"),
+            Self::NoDefaultEntryPoint => {
+                f.write_str("Invoked module doesn't export default entry point!")
+            }
+            Self::Unknown(code) => {
+                write!(f, "Invocation failed with unknown error code {code}!")
+            }
+        }
+    }
+}
+
+impl Error for InvokeError {}
+
+/// Response of an invoked module, with its body fully collected.
+pub struct Response {
+    id: NonZeroU64,
+    length: u64,
+}
+
+impl Response {
+    pub fn is_error(&self) -> bool {
+        unsafe { external::response_is_error(self.id) != 0 }
+    }
+
+    /// Returns [`None`] when the invoked module didn't set a status code.
+    pub fn status_code(&self) -> Option<u16> {
+        u16::try_from(unsafe { external::response_status_code(self.id) })
+            .ok()
+            .filter(|&status_code: &u16| status_code != 0)
+    }
+
+    pub fn unread_length(&self) -> u64 {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    #[inline]
+    pub fn read<'r>(&'_ mut self, buf: &'r mut [u8]) -> &'r [u8] {
+        read_with_id(
+            external::read_response_data,
+            external::response_data_length,
+            self.id,
+            &mut self.length,
+            buf,
+        )
+    }
+}
+
+impl Drop for Response {
+    fn drop(&mut self) {
+        unsafe { external::drop_response(self.id) }
+    }
+}
@@ -1,27 +1,61 @@
 use std::num::NonZeroU64;
 
-use crate::interops::Pointer;
-
-#[link(wasm_import_module = "sdk::io")]
-extern "C" {
-    #[link_name = "receive_request_data_id"]
-    pub(super) fn receive_request_data_id() -> u64;
-
-    #[link_name = "request_data_length"]
-    pub(super) fn request_data_length(id: NonZeroU64) -> u64;
-
-    #[cfg_attr(target_pointer_width = "32", link_name = "read_request_data~32")]
-    #[cfg_attr(target_pointer_width = "64", link_name = "read_request_data~64")]
-    pub(super) fn read_request_data(
-        id: NonZeroU64,
-        buf: Pointer<'_, u8, true>,
-        buf_len: usize,
-    ) -> usize;
-
-    #[link_name = "set_response_is_error"]
-    pub(super) fn set_response_is_error();
-
-    #[cfg_attr(target_pointer_width = "32", link_name = "write_response_data~32")]
-    #[cfg_attr(target_pointer_width = "64", link_name = "write_response_data~64")]
-    pub(super) fn write_response_data(buf: Pointer<'_, u8, false>, buf_len: usize);
+use crate::{abi::host_functions, interops::Pointer};
+
+host_functions! {
+    #[link(wasm_import_module = "sdk::io")]
+    extern "C" {
+        pub(super) fn receive_request_data_id() -> u64;
+
+        pub(super) fn request_data_length(id: NonZeroU64) -> u64;
+
+        #[pointer_sized]
+        pub(super) fn read_request_data(
+            id: NonZeroU64,
+            buf: Pointer<'_, u8, true>,
+            buf_len: usize,
+        ) -> usize;
+
+        pub(super) fn request_meta_length(field: u32) -> u64;
+
+        #[pointer_sized]
+        pub(super) fn request_meta(field: u32, buf: Pointer<'_, u8, true>, buf_len: usize) -> usize;
+
+        pub(super) fn request_headers_count() -> u64;
+
+        pub(super) fn request_header_name_length(index: u64) -> u64;
+
+        #[pointer_sized]
+        pub(super) fn request_header_name(
+            index: u64,
+            buf: Pointer<'_, u8, true>,
+            buf_len: usize,
+        ) -> usize;
+
+        pub(super) fn request_header_value_length(index: u64) -> u64;
+
+        #[pointer_sized]
+        pub(super) fn request_header_value(
+            index: u64,
+            buf: Pointer<'_, u8, true>,
+            buf_len: usize,
+        ) -> usize;
+
+        pub(super) fn set_response_is_error();
+
+        pub(super) fn set_response_status_code(status_code: u32);
+
+        #[pointer_sized]
+        pub(super) fn add_response_header(
+            name: Pointer<'_, u8, false>,
+            name_len: usize,
+            value: Pointer<'_, u8, false>,
+            value_len: usize,
+        );
+
+        pub(super) fn start_response_stream();
+
+        #[pointer_sized]
+        pub(super) fn write_response_data(buf: Pointer<'_, u8, false>, buf_len: usize);
+    }
 }
@@ -2,7 +2,8 @@ use std::num::NonZeroU64;
 
 use crate::interops::{self, Pointer};
 
-mod external;
+pub(crate) mod external;
+pub mod sse;
 
 pub struct RequestData {
     id: NonZeroU64,
@@ -39,6 +40,113 @@ impl RequestData {
     }
 }
 
+const METHOD_FIELD: u32 = 0;
+const PATH_FIELD: u32 = 1;
+const ROUTE_FIELD: u32 = 2;
+const QUERY_FIELD: u32 = 3;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RequestHeader {
+    pub name: String,
+    pub value: Vec<u8>,
+}
+
+/// Metadata of the inbound request the module is executed for.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RequestMeta {
+    /// Request's HTTP method, e.g. `POST`.
+    pub method: String,
+    /// Full path of the request, without the query string.
+    pub path: String,
+    /// Configured route which matched the request.
+    pub route: String,
+    /// Raw query string, without the leading `?`.
+    pub query: String,
+    pub headers: Vec<RequestHeader>,
+}
+
+impl RequestMeta {
+    pub fn new() -> Self {
+        Self {
+            method: meta_string(METHOD_FIELD),
+            path: meta_string(PATH_FIELD),
+            route: meta_string(ROUTE_FIELD),
+            query: meta_string(QUERY_FIELD),
+            headers: (0..unsafe { external::request_headers_count() })
+                .map(|index: u64| RequestHeader {
+                    name: String::from_utf8(read_field(
+                        external::request_header_name_length,
+                        external::request_header_name,
+                        index,
+                    ))
+                    .expect("Expected header name to be valid UTF-8!"),
+                    value: read_field(
+                        external::request_header_value_length,
+                        external::request_header_value,
+                        index,
+                    ),
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns value of first header with provided name, compared
+    /// case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&[u8]> {
+        self.headers
+            .iter()
+            .find(|header: &&RequestHeader| header.name.eq_ignore_ascii_case(name))
+            .map(|header: &RequestHeader| header.value.as_slice())
+    }
+
+    /// Returns raw value of first query parameter with provided name.
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .split('&')
+            .map(|pair: &str| pair.split_once('=').unwrap_or((pair, "")))
+            .find(|&(key, _): &(&str, &str)| key == name)
+            .map(|(_, value): (&str, &str)| value)
+    }
+}
+
+impl Default for RequestMeta {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn meta_string(field: u32) -> String {
+    String::from_utf8(read_field(
+        external::request_meta_length,
+        external::request_meta,
+        field,
+    ))
+    .expect("Expected request metadata to be valid UTF-8!")
+}
+
+fn read_field<Index>(
+    length_fn: unsafe extern "C" fn(Index) -> u64,
+    read_fn: unsafe extern "C" fn(Index, Pointer<'_, u8, true>, usize) -> usize,
+    index: Index,
+) -> Vec<u8>
+where
+    Index: Copy,
+{
+    let length: usize = unsafe { length_fn(index) }
+        .try_into()
+        .expect("Expected metadata length to fit in native-width integer!");
+
+    let mut buf: Vec<u8> = vec![0; length];
+
+    if length != 0 {
+        let read_length: usize = unsafe { read_fn(index, Pointer::from(&mut buf[0]), length) };
+
+        buf.truncate(read_length);
+    }
+
+    buf
+}
+
 #[derive(Default)]
 pub struct Response;
 
@@ -48,6 +156,35 @@ impl Response {
         unsafe { external::set_response_is_error() }
     }
 
+    /// Sets response's HTTP status code. Codes outside of the `100..=599`
+    /// range abort the module.
+    #[inline]
+    pub fn set_status_code(status_code: u16) {
+        unsafe { external::set_response_status_code(status_code.into()) }
+    }
+
+    /// Appends a response header. Hop-by-hop headers are dropped by the server.
+    #[inline]
+    pub fn add_header(name: &str, value: &[u8]) {
+        unsafe {
+            external::add_response_header(
+                Pointer::from(name.as_bytes()).into(),
+                name.len(),
+                Pointer::from(value).into(),
+                value.len(),
+            )
+        }
+    }
+
+    /// Switches response to streaming mode. Status code, headers and data
+    /// written so far are sent right away, after which every write is
+    /// forwarded to the client. Status code and headers can't be changed
+    /// afterwards.
+    #[inline]
+    pub fn start_stream() {
+        unsafe { external::start_response_stream() }
+    }
+
     #[inline]
     pub fn write(buf: &[u8]) {
         unsafe { external::write_response_data(Pointer::from(buf).into(), buf.len()) }
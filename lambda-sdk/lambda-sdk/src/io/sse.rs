@@ -0,0 +1,99 @@
+use super::Response;
+
+/// Server-Sent Events stream over a streamed response.
+pub struct EventStream;
+
+impl EventStream {
+    /// Sets event stream headers and switches response to streaming mode.
+    pub fn start() -> Self {
+        Response::add_header("Content-Type", b"text/event-stream");
+        Response::add_header("Cache-Control", b"no-cache");
+
+        Response::start_stream();
+
+        Self
+    }
+
+    /// Sends event with provided name, if any, and data. Multi-line data is
+    /// split into multiple `data` fields.
+    pub fn send(&mut self, event: Option<&str>, data: &str) {
+        Response::write(format_event(event, None, data).as_bytes());
+    }
+
+    /// Sends event with an ID, which clients report back when reconnecting.
+    pub fn send_with_id(&mut self, event: Option<&str>, id: &str, data: &str) {
+        Response::write(format_event(event, Some(id), data).as_bytes());
+    }
+
+    /// Sends comment, which clients ignore, commonly used as a keep-alive.
+    pub fn send_comment(&mut self, comment: &str) {
+        Response::write(
+            comment
+                .lines()
+                .map(|line: &str| format!(": {line}\n"))
+                .chain([String::from("\n")])
+                .collect::<String>()
+                .as_bytes(),
+        );
+    }
+}
+
+/// Formats event frame. Line breaks in the event name and ID are stripped, as
+/// they would otherwise start new fields, while data is split into multiple
+/// `data` fields on any line break.
+pub fn format_event(event: Option<&str>, id: Option<&str>, data: &str) -> String {
+    let mut frame: String = String::new();
+
+    if let Some(event) = event {
+        frame.push_str("event: ");
+        push_single_line(&mut frame, event);
+        frame.push('\n');
+    }
+
+    if let Some(id) = id {
+        frame.push_str("id: ");
+        push_single_line(&mut frame, id);
+        frame.push('\n');
+    }
+
+    data.replace("\r\n", "\n")
+        .split(['\r', '\n'])
+        .for_each(|line: &str| {
+            frame.push_str("data: ");
+            frame.push_str(line);
+            frame.push('\n');
+        });
+
+    frame.push('\n');
+
+    frame
+}
+
+fn push_single_line(frame: &mut String, value: &str) {
+    frame.extend(value.chars().filter(|&c: &char| !matches!(c, '\r' | '\n')));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_event;
+
+    #[test]
+    fn data_is_split_on_line_breaks() {
+        assert_eq!(
+            format_event(None, None, "first\nsecond\r\nthird\rfourth"),
+            "data: first\ndata: second\ndata: third\ndata: fourth\n\n",
+        );
+    }
+
+    #[test]
+    fn line_breaks_are_stripped_from_event_and_id() {
+        assert_eq!(
+            format_event(
+                Some("update\r\ndata: injected"),
+                Some("1\nevent: injected\r"),
+                "payload",
+            ),
+            "event: updatedata: injected\nid: 1event: injected\ndata: payload\n\n",
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use std::num::NonZeroU64;
+
+use crate::{abi::host_functions, interops::Pointer};
+
+host_functions! {
+    #[link(wasm_import_module = "sdk::kv")]
+    extern "C" {
+        #[pointer_sized]
+        pub(super) fn get(key: Pointer<'_, u8, false>, key_len: usize) -> u64;
+
+        pub(super) fn value_length(id: NonZeroU64) -> u64;
+
+        #[pointer_sized]
+        pub(super) fn read_value(
+            id: NonZeroU64,
+            buf: Pointer<'_, u8, true>,
+            buf_len: usize,
+        ) -> usize;
+
+        pub(super) fn drop_value(id: NonZeroU64);
+
+        #[pointer_sized]
+        pub(super) fn put(
+            key: Pointer<'_, u8, false>,
+            key_len: usize,
+            value: Pointer<'_, u8, false>,
+            value_len: usize,
+        );
+
+        #[pointer_sized]
+        pub(super) fn delete(key: Pointer<'_, u8, false>, key_len: usize) -> u32;
+
+        #[pointer_sized]
+        pub(super) fn compare_and_swap(
+            key: Pointer<'_, u8, false>,
+            key_len: usize,
+            expected: Option<Pointer<'_, u8, false>>,
+            expected_len: usize,
+            new: Pointer<'_, u8, false>,
+            new_len: usize,
+        ) -> u32;
+
+        #[pointer_sized]
+        pub(super) fn list_keys(prefix: Pointer<'_, u8, false>, prefix_len: usize) -> NonZeroU64;
+
+        pub(super) fn keys_count(id: NonZeroU64) -> u64;
+
+        pub(super) fn key_length(id: NonZeroU64, index: u64) -> u64;
+
+        #[pointer_sized]
+        pub(super) fn read_key(
+            id: NonZeroU64,
+            index: u64,
+            buf: Pointer<'_, u8, true>,
+            buf_len: usize,
+        ) -> usize;
+
+        pub(super) fn drop_keys(id: NonZeroU64);
+    }
+}
@@ -0,0 +1,129 @@
+use std::num::NonZeroU64;
+
+use crate::interops::{read_with_id, Pointer, SlicePointer};
+
+pub(crate) mod external;
+
+/// Value of a key-value storage entry. Only one value can be held at a time;
+/// it has to be dropped before fetching another one.
+pub struct Value {
+    id: NonZeroU64,
+    length: u64,
+}
+
+impl Value {
+    pub fn unread_length(&self) -> u64 {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    #[inline]
+    pub fn read<'r>(&'_ mut self, buf: &'r mut [u8]) -> &'r [u8] {
+        read_with_id(
+            external::read_value,
+            external::value_length,
+            self.id,
+            &mut self.length,
+            buf,
+        )
+    }
+
+    pub fn read_to_end(mut self) -> Vec<u8> {
+        let mut value: Vec<u8> = vec![
+            0;
+            self.length.try_into().expect(
+                "Expected value length to fit in native-width integer!"
+            )
+        ];
+
+        let mut offset: usize = 0;
+
+        while offset < value.len() {
+            let read_length: usize = self.read(&mut value[offset..]).len();
+
+            if read_length == 0 {
+                break;
+            }
+
+            offset += read_length;
+        }
+
+        value.truncate(offset);
+
+        value
+    }
+}
+
+impl Drop for Value {
+    fn drop(&mut self) {
+        unsafe { external::drop_value(self.id) }
+    }
+}
+
+fn pointer(bytes: &[u8]) -> Pointer<'_, u8, false> {
+    SlicePointer::from(bytes).into()
+}
+
+pub fn get(key: &[u8]) -> Option<Value> {
+    NonZeroU64::new(unsafe { external::get(pointer(key), key.len()) }).map(|id: NonZeroU64| Value {
+        id,
+        length: unsafe { external::value_length(id) },
+    })
+}
+
+pub fn put(key: &[u8], value: &[u8]) {
+    unsafe { external::put(pointer(key), key.len(), pointer(value), value.len()) }
+}
+
+/// Returns whether an entry was deleted.
+pub fn delete(key: &[u8]) -> bool {
+    unsafe { external::delete(pointer(key), key.len()) != 0 }
+}
+
+/// Sets entry's value to `new` only if its current value equals `expected`,
+/// with [`None`] expecting the entry not to exist. Returns whether the value
+/// was set.
+pub fn compare_and_swap(key: &[u8], expected: Option<&[u8]>, new: &[u8]) -> bool {
+    unsafe {
+        external::compare_and_swap(
+            pointer(key),
+            key.len(),
+            expected.map(pointer),
+            expected.map_or(0, <[u8]>::len),
+            pointer(new),
+            new.len(),
+        ) != 0
+    }
+}
+
+/// Returns keys starting with provided prefix, in ascending order. The host
+/// limits how many keys are returned by a single call.
+pub fn list_keys(prefix: &[u8]) -> Vec<Vec<u8>> {
+    let id: NonZeroU64 = unsafe { external::list_keys(pointer(prefix), prefix.len()) };
+
+    let keys: Vec<Vec<u8>> = (0..unsafe { external::keys_count(id) })
+        .map(|index: u64| {
+            let length: usize = unsafe { external::key_length(id, index) }
+                .try_into()
+                .expect("Expected key length to fit in native-width integer!");
+
+            let mut buf: Vec<u8> = vec![0; length];
+
+            if length != 0 {
+                let read_length: usize =
+                    unsafe { external::read_key(id, index, Pointer::from(&mut buf[0]), length) };
+
+                buf.truncate(read_length);
+            }
+
+            buf
+        })
+        .collect();
+
+    unsafe { external::drop_keys(id) }
+
+    keys
+}
@@ -1,11 +1,25 @@
 #![forbid(rust_2018_compatibility, deprecated_in_future)]
 #![deny(rust_2021_compatibility, warnings)]
 
+mod abi;
+
 pub mod context;
+pub mod crypto;
 pub mod debug;
 pub mod entry;
 pub mod interops;
+pub mod invoke;
 pub mod io;
+pub mod kv;
+pub mod log;
 pub mod net;
 pub mod panic;
+pub mod random;
+pub mod time;
 pub mod vault;
+
+/// Version of the SDK ABI targeted by the module, verified by the runtime
+/// before the module is accepted.
+#[cfg_attr(target_family = "wasm", link_section = "sdk_abi_version")]
+#[used]
+static SDK_ABI_VERSION: [u8; 4] = lambda_abi::VERSION.to_le_bytes();
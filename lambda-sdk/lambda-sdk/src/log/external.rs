@@ -0,0 +1,26 @@
+use crate::{
+    abi::host_functions,
+    interops::{Pointer, SlicePointer},
+};
+
+#[repr(C)]
+pub(super) struct Field<'r> {
+    pub(super) key: SlicePointer<'r, u8, false>,
+    pub(super) value: SlicePointer<'r, u8, false>,
+}
+
+host_functions! {
+    #[link(wasm_import_module = "sdk::log")]
+    extern "C" {
+        #[pointer_sized]
+        pub(super) fn log(
+            level: u32,
+            target: Pointer<'_, u8, false>,
+            target_len: usize,
+            message: Pointer<'_, u8, false>,
+            message_len: usize,
+            fields: Pointer<'_, Field<'_>, false>,
+            fields_len: usize,
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use crate::interops::SlicePointer;
+
+pub(crate) mod external;
+
+/// Severity of a log record. Records are filtered by the host according to the
+/// module's log policy.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(u32)]
+pub enum Level {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+/// Emits a log record with provided key-value fields. Records exceeding the
+/// module's per-request limits are dropped by the host.
+pub fn log(level: Level, target: &str, message: &str, fields: &[(&str, &str)]) {
+    let fields: Vec<external::Field<'_>> = fields
+        .iter()
+        .map(|&(key, value): &(&str, &str)| external::Field {
+            key: SlicePointer::from(key.as_bytes()),
+            value: SlicePointer::from(value.as_bytes()),
+        })
+        .collect();
+
+    unsafe {
+        external::log(
+            level as u32,
+            SlicePointer::from(target.as_bytes()).into(),
+            target.len(),
+            SlicePointer::from(message.as_bytes()).into(),
+            message.len(),
+            SlicePointer::from(fields.as_slice()).into(),
+            fields.len(),
+        )
+    }
+}
+
+pub fn error(message: &str, fields: &[(&str, &str)]) {
+    log(Level::Error, "", message, fields);
+}
+
+pub fn warn(message: &str, fields: &[(&str, &str)]) {
+    log(Level::Warn, "", message, fields);
+}
+
+pub fn info(message: &str, fields: &[(&str, &str)]) {
+    log(Level::Info, "", message, fields);
+}
+
+pub fn debug(message: &str, fields: &[(&str, &str)]) {
+    log(Level::Debug, "", message, fields);
+}
+
+pub fn trace(message: &str, fields: &[(&str, &str)]) {
+    log(Level::Trace, "", message, fields);
+}
@@ -1,32 +1,66 @@
 use std::num::NonZeroU64;
 
-use crate::interops::Pointer;
+use crate::{abi::host_functions, interops::Pointer};
 
 use super::Request;
 
-#[link(wasm_import_module = "sdk::net")]
-extern "C" {
-    #[cfg_attr(target_pointer_width = "32", link_name = "send_request~32")]
-    #[cfg_attr(target_pointer_width = "64", link_name = "send_request~64")]
-    pub(super) fn send_request(request: Pointer<'_, Request<'_>, false>) -> u64;
+host_functions! {
+    #[link(wasm_import_module = "sdk::net")]
+    extern "C" {
+        #[pointer_sized]
+        pub(super) fn send_request(request: Pointer<'_, Request<'_>, false>) -> u64;
 
-    #[link_name = "response_status_code"]
-    pub(super) fn response_status_code(id: NonZeroU64) -> u32;
+        #[pointer_sized]
+        pub(super) fn open_request(request: Pointer<'_, Request<'_>, false>) -> u64;
 
-    #[link_name = "response_data_length"]
-    pub(super) fn response_data_length(id: NonZeroU64) -> u64;
+        #[pointer_sized]
+        pub(super) fn write_request_body(
+            id: NonZeroU64,
+            buf: Pointer<'_, u8, false>,
+            buf_len: usize,
+        );
 
-    #[cfg_attr(target_pointer_width = "32", link_name = "response_data~32")]
-    #[cfg_attr(target_pointer_width = "64", link_name = "response_data~64")]
-    pub(super) fn response_data(
-        id: NonZeroU64,
-        buf: Pointer<'_, u8, true>,
-        buf_len: usize,
-    ) -> usize;
+        pub(super) fn finish_request(id: NonZeroU64) -> NonZeroU64;
 
-    #[link_name = "drop_some_response_data"]
-    pub(super) fn drop_some_response_data(id: NonZeroU64, length: u64);
+        pub(super) fn drop_request(id: NonZeroU64);
 
-    #[link_name = "drop_response"]
-    pub(super) fn drop_response(id: NonZeroU64);
+        pub(super) fn request_error() -> u32;
+
+        pub(super) fn response_status_code(id: NonZeroU64) -> u32;
+
+        pub(super) fn response_headers_count(id: NonZeroU64) -> u64;
+
+        pub(super) fn response_header_name_length(id: NonZeroU64, index: u64) -> u64;
+
+        #[pointer_sized]
+        pub(super) fn response_header_name(
+            id: NonZeroU64,
+            index: u64,
+            buf: Pointer<'_, u8, true>,
+            buf_len: usize,
+        ) -> usize;
+
+        pub(super) fn response_header_value_length(id: NonZeroU64, index: u64) -> u64;
+
+        #[pointer_sized]
+        pub(super) fn response_header_value(
+            id: NonZeroU64,
+            index: u64,
+            buf: Pointer<'_, u8, true>,
+            buf_len: usize,
+        ) -> usize;
+
+        pub(super) fn response_data_length(id: NonZeroU64) -> u64;
+
+        #[pointer_sized]
+        pub(super) fn response_data(
+            id: NonZeroU64,
+            buf: Pointer<'_, u8, true>,
+            buf_len: usize,
+        ) -> usize;
+
+        pub(super) fn drop_some_response_data(id: NonZeroU64, length: u64);
+
+        pub(super) fn drop_response(id: NonZeroU64);
+    }
 }
@@ -1,11 +1,15 @@
-use std::num::NonZeroU64;
-
-use anyhow::bail;
+use std::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+    io::{Result as IoResult, Write},
+    mem::forget,
+    num::NonZeroU64,
+};
 
 use crate::interops::read_with_id;
 use crate::interops::{Pointer, SlicePointer, StringPointer};
 
-mod external;
+pub(crate) mod external;
 
 #[repr(packed, C)]
 pub struct Header<'r> {
@@ -30,10 +34,129 @@ pub fn send_request(request: &Request<'_>) -> anyhow::Result<Response> {
             length: unsafe { external::response_data_length(id) },
         })
     } else {
-        bail!("There is a response to a previous request that has not been dropped!");
+        Err(RequestError::from_code(unsafe { external::request_error() }).into())
+    }
+}
+
+/// Request whose body is streamed in chunks, while the request is already
+/// being sent. The request's `body` field, if not empty, is sent as the first
+/// chunk.
+pub struct RequestWriter {
+    id: NonZeroU64,
+}
+
+impl RequestWriter {
+    pub fn open(request: &Request<'_>) -> anyhow::Result<Self> {
+        let id: u64 = unsafe { external::open_request(Pointer::from(request)) };
+
+        if let Some(id) = NonZeroU64::new(id) {
+            Ok(Self { id })
+        } else {
+            Err(RequestError::from_code(unsafe { external::request_error() }).into())
+        }
+    }
+
+    pub fn write_chunk(&mut self, buf: &[u8]) {
+        unsafe { external::write_request_body(self.id, Pointer::from(buf).into(), buf.len()) }
+    }
+
+    /// Ends the request's body and waits for the response.
+    pub fn finish(self) -> Response {
+        let id: NonZeroU64 = unsafe { external::finish_request(self.id) };
+
+        // Request is already released by the runtime.
+        forget(self);
+
+        Response {
+            id,
+            length: unsafe { external::response_data_length(id) },
+        }
+    }
+}
+
+impl Write for RequestWriter {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.write_chunk(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+impl Drop for RequestWriter {
+    fn drop(&mut self) {
+        unsafe { external::drop_request(self.id) }
+    }
+}
+
+/// Reason for the runtime refusing to send a request.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RequestError {
+    /// Destination host is not allowed by module's egress policy.
+    HostDenied,
+    /// URL scheme is not allowed by module's egress policy.
+    SchemeDenied,
+    /// Request method is not allowed by module's egress policy.
+    MethodDenied,
+    /// Destination port is not allowed by module's egress policy.
+    PortDenied,
+    /// Destination resolves to a private or loopback address.
+    PrivateDestinationDenied,
+    /// Too many responses are kept open. Dropping a [`Response`] frees its
+    /// slot.
+    TooManyResponses,
+    /// Destination host name couldn't be resolved.
+    UnresolvableHost,
+    Unknown(u32),
+}
+
+impl RequestError {
+    fn from_code(code: u32) -> Self {
+        match code {
+            1 => Self::HostDenied,
+            2 => Self::SchemeDenied,
+            3 => Self::MethodDenied,
+            4 => Self::PortDenied,
+            5 => Self::PrivateDestinationDenied,
+            6 => Self::TooManyResponses,
+            7 => Self::UnresolvableHost,
+            code => Self::Unknown(code),
+        }
+    }
+}
+
+impl Display for RequestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::HostDenied => f.write_str("Destination host is not allowed by egress policy!"),
+            Self::SchemeDenied => f.write_str("URL scheme is not allowed by egress policy!"),
+            Self::MethodDenied => f.write_str("Request method is not allowed by egress policy!"),
+            Self::PortDenied => f.write_str("Destination port is not allowed by egress policy!"),
+            Self::PrivateDestinationDenied => {
+                f.write_str("Private and loopback destinations are not allowed by egress policy!")
+            }
+            Self::TooManyResponses => {
+                f.write_str("Too many responses are kept open at the same time!")
+            }
+            Self::UnresolvableHost => f.write_str("Destination host name couldn't be resolved!"),
+            Self::Unknown(code) => write!(f, "Request was refused with unknown error code {code}!"),
+        }
     }
 }
 
+impl Error for RequestError {}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ResponseHeader {
+    pub name: String,
+    pub value: Vec<u8>,
+}
+
+/// Response to an outbound request. Multiple responses can be kept open at
+/// the same time, each one being released when dropped.
 pub struct Response {
     id: NonZeroU64,
     length: u64,
@@ -46,6 +169,68 @@ impl Response {
             .expect("Expected 16-bit integer, but got value that exceeds valid range!")
     }
 
+    /// Returns all response headers, in the order they were received.
+    pub fn headers(&self) -> Vec<ResponseHeader> {
+        (0..unsafe { external::response_headers_count(self.id) })
+            .map(|index: u64| ResponseHeader {
+                name: String::from_utf8(self.header_field(
+                    external::response_header_name_length,
+                    external::response_header_name,
+                    index,
+                ))
+                .expect("Expected header name to be valid UTF-8!"),
+                value: self.header_field(
+                    external::response_header_value_length,
+                    external::response_header_value,
+                    index,
+                ),
+            })
+            .collect()
+    }
+
+    /// Returns value of first header with provided name, compared
+    /// case-insensitively.
+    pub fn header(&self, name: &str) -> Option<Vec<u8>> {
+        (0..unsafe { external::response_headers_count(self.id) })
+            .find(|&index: &u64| {
+                self.header_field(
+                    external::response_header_name_length,
+                    external::response_header_name,
+                    index,
+                )
+                .eq_ignore_ascii_case(name.as_bytes())
+            })
+            .map(|index: u64| {
+                self.header_field(
+                    external::response_header_value_length,
+                    external::response_header_value,
+                    index,
+                )
+            })
+    }
+
+    fn header_field(
+        &self,
+        length_fn: unsafe extern "C" fn(NonZeroU64, u64) -> u64,
+        read_fn: unsafe extern "C" fn(NonZeroU64, u64, Pointer<'_, u8, true>, usize) -> usize,
+        index: u64,
+    ) -> Vec<u8> {
+        let length: usize = unsafe { length_fn(self.id, index) }
+            .try_into()
+            .expect("Expected header length to fit in native-width integer!");
+
+        let mut buf: Vec<u8> = vec![0; length];
+
+        if length != 0 {
+            let read_length: usize =
+                unsafe { read_fn(self.id, index, Pointer::from(&mut buf[0]), length) };
+
+            buf.truncate(read_length);
+        }
+
+        buf
+    }
+
     pub fn unread_length(&self) -> u64 {
         self.length
     }
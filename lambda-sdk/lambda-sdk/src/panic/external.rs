@@ -1,8 +1,9 @@
-use crate::interops::Pointer;
+use crate::{abi::host_functions, interops::Pointer};
 
-#[link(wasm_import_module = "sdk::panic")]
-extern "C" {
-    #[cfg_attr(target_pointer_width = "32", link_name = "panic~32")]
-    #[cfg_attr(target_pointer_width = "64", link_name = "panic~64")]
-    pub(super) fn panic(buf: Pointer<'_, u8, false>, buf_len: usize) -> !;
+host_functions! {
+    #[link(wasm_import_module = "sdk::panic")]
+    extern "C" {
+        #[pointer_sized]
+        pub(super) fn panic(buf: Pointer<'_, u8, false>, buf_len: usize) -> !;
+    }
 }
@@ -2,7 +2,7 @@ use std::panic::PanicInfo;
 
 use crate::interops::Pointer;
 
-mod external;
+pub(crate) mod external;
 
 pub fn install_handler() {
     std::panic::set_hook(Box::new(panic_handler))
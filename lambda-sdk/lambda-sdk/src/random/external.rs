@@ -0,0 +1,9 @@
+use crate::{abi::host_functions, interops::Pointer};
+
+host_functions! {
+    #[link(wasm_import_module = "sdk::random")]
+    extern "C" {
+        #[pointer_sized]
+        pub(super) fn fill(buf: Pointer<'_, u8, true>, buf_len: usize);
+    }
+}
@@ -0,0 +1,21 @@
+use crate::interops::SlicePointer;
+
+pub(crate) mod external;
+
+/// Fills provided buffer with bytes from the host's secure random number
+/// generator.
+pub fn fill(buf: &mut [u8]) {
+    let buf_len: usize = buf.len();
+
+    unsafe { external::fill(SlicePointer::from(buf).into(), buf_len) }
+}
+
+fn getrandom_backend(buf: &mut [u8]) -> Result<(), getrandom::Error> {
+    fill(buf);
+
+    Ok(())
+}
+
+// Lets crates depending on `getrandom`, like `rand` and `uuid`, draw entropy
+// from the host when built for `wasm32-unknown-unknown`.
+getrandom::register_custom_getrandom!(getrandom_backend);
@@ -0,0 +1,10 @@
+use crate::abi::host_functions;
+
+host_functions! {
+    #[link(wasm_import_module = "sdk::time")]
+    extern "C" {
+        pub(super) fn wall_clock() -> u64;
+
+        pub(super) fn monotonic() -> u64;
+    }
+}
@@ -0,0 +1,14 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub(crate) mod external;
+
+/// Returns current wall clock time, as reported by the host.
+pub fn now() -> SystemTime {
+    UNIX_EPOCH + Duration::from_nanos(unsafe { external::wall_clock() })
+}
+
+/// Returns time elapsed since the instance was created. Unlike [`now`], it
+/// never goes backwards, thus should be used for measuring durations.
+pub fn monotonic() -> Duration {
+    Duration::from_nanos(unsafe { external::monotonic() })
+}
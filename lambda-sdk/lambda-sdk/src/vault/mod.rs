@@ -4,7 +4,7 @@ use anyhow::bail;
 
 use crate::interops::{read_with_id, Pointer};
 
-mod external;
+pub(crate) mod external;
 
 pub struct Secret {
     id: NonZeroU64,
@@ -1,4 +1,4 @@
-CREATE TABLE "public"."vault" (
+CREATE TABLE IF NOT EXISTS "public"."vault" (
     "identifier" VARCHAR(255) NOT NULL,
     "secret"     bytea        NOT NULL,
     CONSTRAINT "vault_pkey"
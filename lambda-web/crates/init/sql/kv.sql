@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS "public"."kv" (
+    "module" VARCHAR(255) NOT NULL,
+    "key"    bytea        NOT NULL,
+    "value"  bytea        NOT NULL,
+    CONSTRAINT "kv_pkey"
+        PRIMARY KEY ("module", "key"),
+    CONSTRAINT "module_length_check"
+        CHECK ( LENGTH("public"."kv"."module") != 0 ),
+    CONSTRAINT "key_length_check"
+        CHECK ( LENGTH("public"."kv"."key") <= 1024 ),
+    CONSTRAINT "value_length_check"
+        CHECK ( LENGTH("public"."kv"."value") <= 1048576 )
+);
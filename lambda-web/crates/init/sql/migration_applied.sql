@@ -0,0 +1,5 @@
+SELECT EXISTS(
+    SELECT 1
+    FROM "public"."schema_migrations"
+    WHERE "public"."schema_migrations"."name" = $1
+);
@@ -0,0 +1,2 @@
+INSERT INTO "public"."schema_migrations" ("name")
+VALUES ($1);
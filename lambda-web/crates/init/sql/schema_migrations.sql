@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS "public"."schema_migrations" (
+    "name"       VARCHAR(255) NOT NULL,
+    "applied_at" TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    CONSTRAINT "schema_migrations_pkey"
+        PRIMARY KEY ("name")
+);
+
+LOCK TABLE "public"."schema_migrations" IN EXCLUSIVE MODE;
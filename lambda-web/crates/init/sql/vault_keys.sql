@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS "public"."vault_keys" (
+    "identifier" VARCHAR(255) NOT NULL,
+    "algorithm"  VARCHAR(32)  NOT NULL,
+    "key"        bytea        NOT NULL,
+    CONSTRAINT "vault_keys_pkey"
+        PRIMARY KEY ("identifier"),
+    CONSTRAINT "identifier_length_check"
+        CHECK ( LENGTH("public"."vault_keys"."identifier") != 0 ),
+    CONSTRAINT "algorithm_check"
+        CHECK ( "public"."vault_keys"."algorithm" IN ('ed25519', 'hmac_sha256', 'aes256_gcm') ),
+    CONSTRAINT "key_length_check"
+        CHECK ( LENGTH("public"."vault_keys"."key") != 0 )
+);
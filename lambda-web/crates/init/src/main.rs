@@ -1,9 +1,11 @@
 #![forbid(rust_2018_compatibility, deprecated_in_future)]
 #![deny(rust_2021_compatibility, warnings)]
 
+use anyhow::{Context as _, Result as AnyResult};
 use clap::Parser as _;
 use sqlx::{
-    postgres::PgConnectOptions, Connection as _, Executor as _, PgConnection, Postgres, Transaction,
+    postgres::PgConnectOptions, query, query_scalar, Connection as _, Executor as _, PgConnection,
+    Postgres, Transaction,
 };
 use tokio::runtime::Builder as RuntimeBuilder;
 
@@ -11,7 +13,18 @@ use self::args::Args;
 
 mod args;
 
-fn main() -> anyhow::Result<()> {
+/// Schema migrations, applied in order. Each one is applied only once and
+/// recorded by name, so they must never be renamed or changed once released;
+/// changes to the schema are added as new migrations instead. Migrations
+/// create tables only if they don't exist, letting databases initialized
+/// before migrations were recorded adopt them.
+const MIGRATIONS: [(&str, &str); 3] = [
+    ("initialize", include_str!("../sql/initialize.sql")),
+    ("kv", include_str!("../sql/kv.sql")),
+    ("vault_keys", include_str!("../sql/vault_keys.sql")),
+];
+
+fn main() -> AnyResult<()> {
     RuntimeBuilder::new_current_thread()
         .enable_all()
         .build()?
@@ -30,15 +43,51 @@ fn main() -> anyhow::Result<()> {
 
             let mut transaction: Transaction<'_, Postgres> = database_connection.begin().await?;
 
-            if let Err(error) = transaction
-                .execute(include_str!("../sql/initialize.sql"))
-                .await
-            {
+            if let Err(error) = apply_migrations(&mut transaction).await {
                 transaction.rollback().await?;
 
-                return Err(error.into());
+                return Err(error);
             }
 
             transaction.commit().await.map_err(Into::into)
         })
 }
+
+/// Applies migrations which weren't applied yet. Runs in provided transaction,
+/// which holds a lock on the migrations' table, so concurrent runs wait for
+/// each other.
+async fn apply_migrations(transaction: &mut Transaction<'_, Postgres>) -> AnyResult<()> {
+    transaction
+        .execute(include_str!("../sql/schema_migrations.sql"))
+        .await
+        .context("Failed to prepare table of applied migrations!")?;
+
+    for (name, script) in MIGRATIONS {
+        let applied: bool = query_scalar(include_str!("../sql/migration_applied.sql"))
+            .bind(name)
+            .fetch_one(&mut **transaction)
+            .await
+            .with_context(|| {
+                format!(r#"Failed to check whether migration "{name}" is applied!"#)
+            })?;
+
+        if applied {
+            continue;
+        }
+
+        transaction
+            .execute(script)
+            .await
+            .with_context(|| format!(r#"Failed to apply migration "{name}"!"#))?;
+
+        query(include_str!("../sql/record_migration.sql"))
+            .bind(name)
+            .execute(&mut **transaction)
+            .await
+            .with_context(|| format!(r#"Failed to record migration "{name}"!"#))?;
+
+        println!(r#"Applied migration "{name}"."#);
+    }
+
+    Ok(())
+}
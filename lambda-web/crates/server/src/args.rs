@@ -2,21 +2,38 @@ use std::path::PathBuf;
 
 use clap::{
     error::{Error, ErrorKind},
-    Parser,
+    Parser, Subcommand,
 };
 use zeroize::Zeroizing;
 
 #[derive(Debug, Parser)]
-#[clap(version, about)]
+#[clap(version, about, subcommand_negates_reqs = true)]
 pub struct Args {
-    #[clap(short = 'u', long = "db-user")]
-    pub db_user: String,
-    #[clap(short = 'p', long = "db-pass")]
-    pub db_pass: Zeroizing<String>,
-    #[clap(short = 'k', long, default_value = "verifying.key", value_parser = file_path_parser)]
+    #[clap(short = 'u', long = "db-user", required = true)]
+    pub db_user: Option<String>,
+    #[clap(short = 'p', long = "db-pass", required = true)]
+    pub db_pass: Option<Zeroizing<String>>,
+    // Not required to exist when compiling modules, thus checked on startup.
+    #[clap(short = 'k', long, default_value = "verifying.key")]
     pub verify_key: PathBuf,
-    #[clap(short = 'c', long, default_value = "config.toml", value_parser = file_path_parser)]
+    #[clap(short = 'c', long, global = true, default_value = "config.toml", value_parser = file_path_parser)]
     pub config: PathBuf,
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Compiles configured modules with the server's engine settings and
+    /// writes them as ".cwasm" files, which can be configured as modules'
+    /// paths instead of the binaries.
+    Compile {
+        #[clap(short = 'o', long, default_value = ".")]
+        output_dir: PathBuf,
+    },
+    /// Replays execution recorded by a module configured with recording,
+    /// answering its host calls from the recording, and logs the response.
+    Replay { recording: PathBuf },
 }
 
 fn file_path_parser(path: &str) -> Result<PathBuf, Error> {
@@ -1,5 +1,6 @@
 use std::{
-    num::NonZeroU16,
+    collections::BTreeMap,
+    num::{NonZeroU16, NonZeroU32, NonZeroU64},
     ops::{Deref, DerefMut},
     path::{Path as StdPath, PathBuf},
 };
@@ -21,6 +22,31 @@ pub struct Config {
     pub routes: Vec<Route>,
 }
 
+impl Config {
+    pub fn cpu_budgets(&self) -> impl Iterator<Item = CpuBudget> + '_ {
+        self.modules
+            .iter()
+            .filter_map(|module: &Module| module.cpu_budget)
+            .chain(
+                self.routes
+                    .iter()
+                    .filter_map(|route: &Route| route.cpu_budget),
+            )
+    }
+
+    /// Returns whether any module or route has a fuel budget, which requires
+    /// fuel consumption, and its overhead, to be enabled engine-wide.
+    pub fn uses_fuel(&self) -> bool {
+        self.cpu_budgets()
+            .any(|cpu_budget: CpuBudget| matches!(cpu_budget, CpuBudget::Fuel(_)))
+    }
+
+    pub fn uses_timeouts(&self) -> bool {
+        self.cpu_budgets()
+            .any(|cpu_budget: CpuBudget| matches!(cpu_budget, CpuBudget::TimeoutMs(_)))
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Database {
     pub host: String,
@@ -28,21 +54,35 @@ pub struct Database {
     pub database: String,
 }
 
-#[derive(Debug, Copy, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Global {
     pub requests: GlobalRequests,
     pub instances: GlobalInstances,
+    #[serde(default)]
+    pub pooling: Option<GlobalPooling>,
+    #[serde(default)]
+    pub module_cache: Option<GlobalModuleCache>,
 }
 
 #[derive(Debug, Copy, Clone, Deserialize)]
 pub struct GlobalRequests {
     pub max_concurrent: NonZeroU16,
+    /// Whether metrics of each execution are logged, tagged with the module
+    /// and route.
+    #[serde(default)]
+    pub export_metrics: bool,
 }
 
 #[derive(Debug, Copy, Clone)]
 pub struct GlobalInstances {
     pub init_pool_size: u16,
     pub max_idle_pool_size: u16,
+    /// Whether instances are discarded after each request and replaced by
+    /// fresh ones, so every request starts with pristine memory. Replacement
+    /// happens once the response is sent, within the handling request's
+    /// concurrency permit.
+    pub reset_between_requests: bool,
+    pub limits: InstanceLimits,
 }
 
 impl<'de> Deserialize<'de> for GlobalInstances {
@@ -55,17 +95,25 @@ impl<'de> Deserialize<'de> for GlobalInstances {
         pub struct Unchecked {
             pub min_pool_size: u16,
             pub max_idle_pool_size: u16,
+            #[serde(default)]
+            pub reset_between_requests: bool,
+            #[serde(flatten)]
+            pub limits: InstanceLimits,
         }
 
         let Unchecked {
             min_pool_size,
             max_idle_pool_size,
+            reset_between_requests,
+            limits,
         }: Unchecked = Unchecked::deserialize(deserializer)?;
 
         if min_pool_size <= max_idle_pool_size {
             Ok(Self {
                 init_pool_size: min_pool_size,
                 max_idle_pool_size,
+                reset_between_requests,
+                limits,
             })
         } else {
             Err(Error::custom(
@@ -75,6 +123,46 @@ impl<'de> Deserialize<'de> for GlobalInstances {
     }
 }
 
+/// Settings of the pooling instance allocator, which preallocates slots for
+/// instances up front so creating one doesn't require mapping fresh memory.
+#[derive(Debug, Copy, Clone, Deserialize)]
+pub struct GlobalPooling {
+    /// Total number of instances, across all modules, which can exist at once.
+    pub total_instances: NonZeroU32,
+    /// Address space reserved for each instance's linear memory. Defaults to
+    /// the engine's static memory maximum size.
+    #[serde(default)]
+    pub memory_reservation_bytes: Option<NonZeroU64>,
+}
+
+/// Directory compiled modules are cached in, sparing recompilation on
+/// startup. Must only be writable by the server.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GlobalModuleCache {
+    pub directory: PathBuf,
+}
+
+#[derive(Debug, Copy, Clone, Default, Deserialize)]
+pub struct InstanceLimits {
+    #[serde(rename = "max_memory_bytes")]
+    pub memory_bytes: Option<usize>,
+    #[serde(rename = "max_table_elements")]
+    pub table_elements: Option<u32>,
+    #[serde(rename = "max_instances")]
+    pub instances: Option<usize>,
+}
+
+impl InstanceLimits {
+    #[must_use]
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            memory_bytes: self.memory_bytes.or(fallback.memory_bytes),
+            table_elements: self.table_elements.or(fallback.table_elements),
+            instances: self.instances.or(fallback.instances),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Bind {
     pub host: String,
@@ -85,12 +173,105 @@ pub struct Bind {
 pub struct Module {
     pub id: Id,
     pub path: Path,
+    #[serde(default)]
+    pub cpu_budget: Option<CpuBudget>,
+    #[serde(flatten)]
+    pub limits: InstanceLimits,
+    #[serde(default)]
+    pub egress: Egress,
+    #[serde(default)]
+    pub log: Log,
+    /// Allows module to import WASI when set.
+    #[serde(default)]
+    pub wasi: Option<Wasi>,
+    #[serde(default)]
+    pub record: Option<Record>,
+}
+
+/// WASI settings of a module. Its instances get a sandboxed filesystem, holding
+/// only the preopened directories.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Wasi {
+    /// Environment variables exposed through WASI.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub preopens: Vec<WasiPreopen>,
+}
+
+/// Host directory exposed read-only to a module under the guest path.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WasiPreopen {
+    pub host: PathBuf,
+    pub guest: String,
+}
+
+/// Directory each execution of a module is recorded into, along with the
+/// module's host calls, for replaying it with the "replay" command.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Record {
+    pub directory: PathBuf,
+    /// Whether fetched secrets, decrypted plaintext and credentials in headers
+    /// are recorded instead of being redacted.
+    #[serde(default)]
+    pub include_secrets: bool,
+    /// Maximum number of the module's recordings kept in the directory.
+    #[serde(default)]
+    pub max_recordings: Option<NonZeroU64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Egress {
+    #[serde(default)]
+    pub allowed_hosts: Option<Vec<String>>,
+    #[serde(default)]
+    pub allowed_schemes: Option<Vec<String>>,
+    #[serde(default)]
+    pub allowed_methods: Option<Vec<String>>,
+    #[serde(default)]
+    pub allowed_ports: Option<Vec<u16>>,
+    #[serde(default)]
+    pub allow_private_destinations: bool,
+}
+
+#[derive(Debug, Copy, Clone, Default, Deserialize)]
+pub struct Log {
+    #[serde(default)]
+    pub level: Option<LogLevel>,
+    #[serde(default)]
+    pub max_records_per_request: Option<u32>,
+    #[serde(default)]
+    pub max_bytes_per_request: Option<usize>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Route {
     pub path: RoutePath,
     pub module: Id,
+    /// Handler exported by the module serving the route, with the module's
+    /// default entry point serving it when unset.
+    #[serde(default)]
+    pub handler: Option<String>,
+    #[serde(default)]
+    pub cpu_budget: Option<CpuBudget>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CpuBudget {
+    Fuel(NonZeroU64),
+    TimeoutMs(NonZeroU64),
 }
 
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Default, Hash)]
@@ -142,7 +323,9 @@ impl<'r> Deserialize<'r> for Id {
     {
         let id = String::deserialize(deserializer)?;
 
-        if id
+        if id.is_empty() {
+            Err(Error::custom("Module ID can't be empty!"))
+        } else if id
             .chars()
             .all(|c| c.is_ascii_alphanumeric() || ['-', '_'].contains(&c))
         {
@@ -277,3 +460,38 @@ impl<'r> Deserialize<'r> for RoutePath {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::{
+        de::{value::Error as ValueError, IntoDeserializer as _},
+        Deserialize as _,
+    };
+
+    use super::Id;
+
+    fn parse_id(id: &str) -> Result<Id, ValueError> {
+        Id::deserialize(id.into_deserializer())
+    }
+
+    #[test]
+    fn module_ids_are_validated() {
+        assert_eq!(
+            parse_id("lambda_lib-2").ok(),
+            Some(Id(String::from("lambda_lib-2"))),
+        );
+
+        for id in [
+            "",
+            ".",
+            "..",
+            "../module",
+            "a/b",
+            "a\\b",
+            "module.cwasm",
+            "módulo",
+        ] {
+            assert!(parse_id(id).is_err(), "{id:?} was accepted!");
+        }
+    }
+}
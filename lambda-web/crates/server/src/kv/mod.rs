@@ -0,0 +1,154 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use anyhow::{Context as _, Result as AnyResult};
+use sqlx::{postgres::PgQueryResult, query, query_scalar, PgPool};
+
+use lambda_rt::KvProvider;
+
+#[derive(Debug, Clone)]
+pub struct Kv {
+    pool: Arc<PgPool>,
+}
+
+impl Kv {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool: Arc::new(pool),
+        }
+    }
+}
+
+impl KvProvider for Kv {
+    type Result<'r, T>
+        = Pin<Box<dyn Future<Output = AnyResult<T>> + Send + 'r>>
+    where
+        T: Send + 'r;
+
+    fn get(&mut self, namespace: String, key: Vec<u8>) -> Self::Result<'_, Option<Vec<u8>>> {
+        Box::pin(async {
+            query_scalar(include_str!("sql/get.sql"))
+                .bind(namespace)
+                .bind(key)
+                .fetch_optional(&*self.pool)
+                .await
+                .map_err(Into::into)
+        })
+    }
+
+    fn put(&mut self, namespace: String, key: Vec<u8>, value: Vec<u8>) -> Self::Result<'_, ()> {
+        Box::pin(async {
+            query(include_str!("sql/put.sql"))
+                .bind(namespace)
+                .bind(key)
+                .bind(value)
+                .execute(&*self.pool)
+                .await
+                .map(|_: PgQueryResult| ())
+                .map_err(Into::into)
+        })
+    }
+
+    fn delete(&mut self, namespace: String, key: Vec<u8>) -> Self::Result<'_, bool> {
+        Box::pin(async {
+            query(include_str!("sql/delete.sql"))
+                .bind(namespace)
+                .bind(key)
+                .execute(&*self.pool)
+                .await
+                .map(|result: PgQueryResult| result.rows_affected() != 0)
+                .map_err(Into::into)
+        })
+    }
+
+    fn compare_and_swap(
+        &mut self,
+        namespace: String,
+        key: Vec<u8>,
+        expected: Option<Vec<u8>>,
+        new: Vec<u8>,
+    ) -> Self::Result<'_, bool> {
+        Box::pin(async {
+            if let Some(expected) = expected {
+                query(include_str!("sql/compare_and_swap.sql"))
+                    .bind(namespace)
+                    .bind(key)
+                    .bind(expected)
+                    .bind(new)
+            } else {
+                query(include_str!("sql/insert_if_absent.sql"))
+                    .bind(namespace)
+                    .bind(key)
+                    .bind(new)
+            }
+            .execute(&*self.pool)
+            .await
+            .map(|result: PgQueryResult| result.rows_affected() != 0)
+            .map_err(Into::into)
+        })
+    }
+
+    fn list_keys(
+        &mut self,
+        namespace: String,
+        prefix: Vec<u8>,
+        limit: usize,
+    ) -> Self::Result<'_, Vec<Vec<u8>>> {
+        Box::pin(async move {
+            let limit: i64 = limit
+                .try_into()
+                .context("Keys listing limit doesn't fit in database's integer!")?;
+
+            let upper_bound: Option<Vec<u8>> = prefix_upper_bound(prefix.clone());
+
+            query_scalar(include_str!("sql/list_keys.sql"))
+                .bind(namespace)
+                .bind(prefix)
+                .bind(upper_bound)
+                .bind(limit)
+                .fetch_all(&*self.pool)
+                .await
+                .map_err(Into::into)
+        })
+    }
+}
+
+/// Returns the smallest key greater than every key starting with provided
+/// prefix, which together with the prefix bounds a range scan of the primary
+/// key's index. Returns [`None`] when no such key exists, i.e. when the prefix
+/// consists only of `0xFF` bytes.
+fn prefix_upper_bound(mut prefix: Vec<u8>) -> Option<Vec<u8>> {
+    while let Some(last) = prefix.pop() {
+        if let Some(incremented) = last.checked_add(1) {
+            prefix.push(incremented);
+
+            return Some(prefix);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prefix_upper_bound;
+
+    #[test]
+    fn prefix_upper_bound_increments_last_byte() {
+        assert_eq!(
+            prefix_upper_bound(b"user:".to_vec()),
+            Some(b"user;".to_vec())
+        );
+    }
+
+    #[test]
+    fn prefix_upper_bound_skips_trailing_max_bytes() {
+        assert_eq!(prefix_upper_bound(vec![1, 0xFF, 0xFF]), Some(vec![2]));
+    }
+
+    #[test]
+    fn prefix_upper_bound_is_unbounded_for_max_bytes() {
+        assert_eq!(prefix_upper_bound(Vec::new()), None);
+
+        assert_eq!(prefix_upper_bound(vec![0xFF, 0xFF]), None);
+    }
+}
@@ -0,0 +1,5 @@
+UPDATE "public"."kv"
+SET "value" = $4
+WHERE "public"."kv"."module" = $1
+  AND "public"."kv"."key" = $2
+  AND "public"."kv"."value" = $3;
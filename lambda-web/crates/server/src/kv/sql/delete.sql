@@ -0,0 +1,4 @@
+DELETE
+FROM "public"."kv"
+WHERE "public"."kv"."module" = $1
+  AND "public"."kv"."key" = $2;
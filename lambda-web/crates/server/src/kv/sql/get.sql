@@ -0,0 +1,5 @@
+SELECT "public"."kv"."value"
+FROM "public"."kv"
+WHERE "public"."kv"."module" = $1
+  AND "public"."kv"."key" = $2
+LIMIT 1;
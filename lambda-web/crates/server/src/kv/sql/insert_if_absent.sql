@@ -0,0 +1,3 @@
+INSERT INTO "public"."kv" ("module", "key", "value")
+VALUES ($1, $2, $3)
+ON CONFLICT ("module", "key") DO NOTHING;
@@ -0,0 +1,7 @@
+SELECT "public"."kv"."key"
+FROM "public"."kv"
+WHERE "public"."kv"."module" = $1
+  AND "public"."kv"."key" >= $2
+  AND ($3::bytea IS NULL OR "public"."kv"."key" < $3)
+ORDER BY "public"."kv"."key"
+LIMIT $4;
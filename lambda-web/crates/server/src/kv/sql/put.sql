@@ -0,0 +1,4 @@
+INSERT INTO "public"."kv" ("module", "key", "value")
+VALUES ($1, $2, $3)
+ON CONFLICT ("module", "key") DO UPDATE
+    SET "value" = EXCLUDED."value";
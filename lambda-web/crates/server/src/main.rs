@@ -6,40 +6,76 @@
 )]
 #![deny(rust_2021_compatibility, warnings)]
 
-use std::{collections::BTreeMap, fs, sync::Arc};
+use std::{
+    collections::BTreeMap, fs, iter, num::NonZeroU64, path::Path, sync::Arc, thread, time::Duration,
+};
 
 use actix_web::{
     guard,
     web::{self, Bytes},
-    App, HttpServer, Scope,
+    App, HttpRequest, HttpServer, Scope,
 };
-use anyhow::{Context as _, Result as AnyResult};
+use anyhow::{bail, Context as _, Result as AnyResult};
 use clap::Parser;
 use ed25519_dalek::VerifyingKey;
 use sqlx::{postgres::PgConnectOptions, PgPool};
+use tracing::{info, level_filters::LevelFilter, warn};
+use tracing_subscriber::{
+    filter::Targets, layer::SubscriberExt as _, util::SubscriberInitExt as _,
+};
 use wasmtime::{
-    Engine as WasmEngine, Linker as WasmLinker, OptLevel as WasmOptLevel, WasmBacktraceDetails,
+    Engine as WasmEngine, InstanceAllocationStrategy, Linker as WasmLinker,
+    OptLevel as WasmOptLevel, PoolingAllocationConfig, WasmBacktraceDetails,
 };
+use zeroize::Zeroizing;
 
 use lambda_auth::middleware::{Auth as AuthMiddleware, AuthenticatedUser};
-use lambda_rt::{LinkerWithSdk, SdkContext, SdkUser};
+use lambda_rt::{
+    cache::ModuleCache,
+    network::ReqwestNetwork,
+    record::{self, Recording, Replay},
+    LinkerWithSdk, ResponseBody, SdkContext, SdkUser,
+};
 
 use self::{
-    args::Args,
-    config::{Bind, Config, RoutePath as ConfigRoutePath},
-    service::{modules, workers, RequestSender},
+    args::{Args, Command},
+    config::{
+        Bind, Config, GlobalModuleCache as ConfigGlobalModuleCache,
+        GlobalPooling as ConfigGlobalPooling, InstanceLimits as ConfigInstanceLimits,
+        Module as ConfigModule, RoutePath as ConfigRoutePath,
+    },
+    kv::Kv,
+    service::{
+        invoker::Invoker,
+        modules,
+        workers::{self, ModuleWorkers},
+        RouteHandler,
+    },
     vault::Vault,
 };
 
 mod args;
 mod config;
+mod kv;
 mod service;
 mod vault;
 
+/// Interval at which the engine's epoch is incremented, which is the
+/// granularity of time-based CPU budgets.
+pub const EPOCH_TICK_MILLIS: u64 = 10;
+
+/// Address space reserved for each linear memory by the engine when not
+/// configured otherwise.
+const DEFAULT_MEMORY_RESERVATION_BYTES: u64 = 1 << 32;
+
+const WASM_PAGE_SIZE: u64 = 1 << 16;
+
 #[actix_web::main]
 async fn main() -> AnyResult<()> {
     let args: Args = Args::parse();
 
+    init_tracing().context("Failed to initialize tracing!")?;
+
     let config: Config = fs::read(args.config)
         .context("Failed to read configuration file!")
         .and_then(|content: Vec<u8>| {
@@ -49,47 +85,94 @@ async fn main() -> AnyResult<()> {
             toml::from_str(&content).context("Failed to parse configuration!")
         })?;
 
+    match args.command {
+        Some(Command::Compile { output_dir }) => {
+            let engine: WasmEngine =
+                new_engine(&config).context("Failed to create WASM engine!")?;
+
+            return modules::compile(&engine, config.modules, &output_dir)
+                .context("Failed to compile modules!");
+        }
+        Some(Command::Replay { recording }) => return replay(config, &recording).await,
+        None => {}
+    }
+
+    let (Some(db_user), Some(db_pass)): (Option<String>, Option<Zeroizing<String>>) =
+        (args.db_user, args.db_pass)
+    else {
+        bail!("Database credentials are required to run the server!");
+    };
+
+    let verifying_key: VerifyingKey = fs::read(args.verify_key)
+        .context("Failed to read verifying key for authentication from file!")
+        .and_then(|bytes: Vec<u8>| {
+            VerifyingKey::try_from(bytes.as_slice())
+                .context("Failed to load verifying key for authentication!")
+        })?;
+
     let database_pool: PgPool = PgPool::connect_with(
         PgConnectOptions::new()
             .host(&config.database.host)
             .port(config.database.port.get())
             .database(&config.database.database)
-            .username(&args.db_user)
-            .password(&args.db_pass),
+            .username(&db_user)
+            .password(&db_pass),
     )
     .await?;
 
-    let engine: WasmEngine = new_engine().context("Failed to create WASM engine!")?;
+    let engine: WasmEngine = new_engine(&config).context("Failed to create WASM engine!")?;
+
+    if config.uses_timeouts() {
+        spawn_epoch_ticker(engine.clone());
+    }
+
+    let module_cache: Option<ModuleCache> = config
+        .global
+        .module_cache
+        .as_ref()
+        .map(|module_cache: &ConfigGlobalModuleCache| {
+            ModuleCache::new(module_cache.directory.clone(), &engine_fingerprint(&config))
+        })
+        .transpose()
+        .context("Failed to open module cache!")?;
 
     let modules: modules::Precompiled =
-        modules::precompile(&engine, config.modules).context("Failed to precompile modules!")?;
+        modules::precompile(&engine, module_cache.as_ref(), config.modules)
+            .context("Failed to precompile modules!")?;
 
-    let linker: Arc<LinkerWithSdk<SdkContext<Vault>>> =
-        LinkerWithSdk::new(WasmLinker::new(&engine), Vault::new(database_pool))
-            .map(Arc::new)
-            .context("Failed to create linker with SDK!")?;
+    let invoker: Invoker = Invoker::new();
 
-    let make_handler: fn(RequestSender<SdkUser>) -> _ = |sender: RequestSender<SdkUser>| {
-        move |user: AuthenticatedUser, body: Bytes| {
+    let linker: Arc<LinkerWithSdk<SdkContext<Vault, ReqwestNetwork, Kv, Invoker>>> =
+        LinkerWithSdk::new(
+            WasmLinker::new(&engine),
+            Vault::new(database_pool.clone()),
+            ReqwestNetwork::new()?,
+            Kv::new(database_pool),
+            invoker.clone(),
+        )
+        .map(Arc::new)
+        .context("Failed to create linker with SDK!")?;
+
+    let make_handler: fn(RouteHandler<SdkUser>) -> _ = |handler: RouteHandler<SdkUser>| {
+        move |request: HttpRequest, user: AuthenticatedUser, body: Bytes| {
             service::request_handler(
+                request,
                 SdkUser::new(String::from(user.username())),
                 body,
-                sender.clone(),
+                handler.clone(),
             )
         }
     };
 
-    let routes_to_handlers: BTreeMap<ConfigRoutePath, RequestSender<SdkUser>> =
-        workers::generate_route_handlers(config.global, config.routes, modules, linker)
+    let module_workers: ModuleWorkers<SdkUser> =
+        workers::generate_module_workers(config.global, modules, linker)
             .await
-            .context("Failed to generate route handlers!")?;
+            .context("Failed to spawn module workers!")?;
 
-    let verifying_key: VerifyingKey = fs::read(args.verify_key)
-        .context("Failed to read verifying key for authentication from file!")
-        .and_then(|bytes: Vec<u8>| {
-            VerifyingKey::try_from(bytes.as_slice())
-                .context("Failed to load verifying key for authentication!")
-        })?;
+    invoker.set_handlers(workers::generate_invoke_handlers(&module_workers))?;
+
+    let routes_to_handlers: BTreeMap<ConfigRoutePath, RouteHandler<SdkUser>> =
+        workers::generate_route_handlers(config.routes, &module_workers)?;
 
     let server: HttpServer<_, _, _, _> = HttpServer::new(move || {
         App::new()
@@ -97,10 +180,10 @@ async fn main() -> AnyResult<()> {
             .service(routes_to_handlers.iter().fold(
             web::scope("/service").guard(guard::Post()),
             |scope: Scope<_>,
-             (route_path, request_sender): (&ConfigRoutePath, &RequestSender<SdkUser>)| {
+             (route_path, route_handler): (&ConfigRoutePath, &RouteHandler<SdkUser>)| {
                 scope.route(
                     route_path,
-                    web::route().to(make_handler(request_sender.clone())),
+                    web::route().to(make_handler(route_handler.clone())),
                 )
             },
         ))
@@ -120,23 +203,208 @@ async fn main() -> AnyResult<()> {
         .context("Failed to run server!")
 }
 
-pub fn new_engine() -> AnyResult<WasmEngine> {
-    WasmEngine::new(
-        wasmtime::Config::new()
-            .async_support(true)
-            .cranelift_opt_level(WasmOptLevel::Speed)
-            .consume_fuel(false)
-            .wasm_backtrace_details(WasmBacktraceDetails::Enable)
-            .wasm_multi_value(true)
-            .wasm_multi_memory(false)
-            .async_support(true)
-            .cranelift_nan_canonicalization(true)
-            .native_unwind_info(true)
-            .parallel_compilation(true)
-            .wasm_bulk_memory(true)
-            .wasm_threads(false)
-            .wasm_simd(true)
-            .wasm_memory64(true)
-            .wasm_reference_types(true),
+pub fn new_engine(config: &Config) -> AnyResult<WasmEngine> {
+    let mut wasm_config: wasmtime::Config = wasmtime::Config::new();
+
+    wasm_config
+        .async_support(true)
+        .cranelift_opt_level(WasmOptLevel::Speed)
+        .consume_fuel(config.uses_fuel())
+        .epoch_interruption(config.uses_timeouts())
+        .wasm_backtrace_details(WasmBacktraceDetails::Enable)
+        .wasm_multi_value(true)
+        .wasm_multi_memory(false)
+        .async_support(true)
+        .cranelift_nan_canonicalization(true)
+        .native_unwind_info(true)
+        .parallel_compilation(true)
+        .wasm_bulk_memory(true)
+        .wasm_threads(false)
+        .wasm_simd(true)
+        .wasm_memory64(true)
+        .wasm_reference_types(true)
+        .memory_init_cow(true);
+
+    if let Some(pooling) = config.global.pooling {
+        if let Some(memory_reservation_bytes) = pooling.memory_reservation_bytes {
+            wasm_config.static_memory_maximum_size(memory_reservation_bytes.get());
+        }
+
+        wasm_config.allocation_strategy(InstanceAllocationStrategy::Pooling(
+            pooling_allocation_config(pooling, &instance_limits(config)),
+        ));
+    }
+
+    WasmEngine::new(&wasm_config)
+}
+
+/// Fingerprint of the engine settings `new_engine` derives from configuration,
+/// under which compiled modules are cached. Settings fixed in code change along
+/// with the server's version, while remaining incompatibilities are caught by
+/// the engine when loading cached modules.
+fn engine_fingerprint(config: &Config) -> String {
+    let pooling: Option<PoolingAllocationConfig> =
+        config.global.pooling.map(|pooling: ConfigGlobalPooling| {
+            pooling_allocation_config(pooling, &instance_limits(config))
+        });
+
+    format!(
+        "{}/fuel={}/epochs={}/memory_reservation={:?}/pooling={pooling:?}",
+        env!("CARGO_PKG_VERSION"),
+        config.uses_fuel(),
+        config.uses_timeouts(),
+        config
+            .global
+            .pooling
+            .and_then(|pooling: ConfigGlobalPooling| pooling.memory_reservation_bytes),
+    )
+}
+
+/// Global instance limits followed by each module's effective limits.
+fn instance_limits(config: &Config) -> Vec<ConfigInstanceLimits> {
+    let global_limits: ConfigInstanceLimits = config.global.instances.limits;
+
+    iter::once(global_limits)
+        .chain(
+            config
+                .modules
+                .iter()
+                .map(|module: &ConfigModule| module.limits.or(global_limits)),
+        )
+        .collect()
+}
+
+/// Sizes pool slots after the highest of provided instance limits, as memory
+/// and tables can't grow beyond what their slots provide. Slots of unlimited
+/// memory span the whole reservation, while unlimited tables keep the engine's
+/// default slot size.
+fn pooling_allocation_config(
+    pooling: ConfigGlobalPooling,
+    limits: &[ConfigInstanceLimits],
+) -> PoolingAllocationConfig {
+    let memory_reservation_bytes: u64 = pooling
+        .memory_reservation_bytes
+        .map_or(DEFAULT_MEMORY_RESERVATION_BYTES, NonZeroU64::get);
+
+    let memory_bytes: u64 = highest_limit(
+        limits
+            .iter()
+            .map(|limits: &ConfigInstanceLimits| limits.memory_bytes),
     )
+    .and_then(|memory_bytes: usize| u64::try_from(memory_bytes).ok())
+    .map_or(memory_reservation_bytes, |memory_bytes: u64| {
+        memory_bytes.min(memory_reservation_bytes)
+    });
+
+    let mut pooling_config: PoolingAllocationConfig = PoolingAllocationConfig::default();
+
+    pooling_config
+        .instance_count(pooling.total_instances.get())
+        .instance_memory_pages(memory_bytes / WASM_PAGE_SIZE);
+
+    if let Some(table_elements) = highest_limit(
+        limits
+            .iter()
+            .map(|limits: &ConfigInstanceLimits| limits.table_elements),
+    ) {
+        pooling_config.instance_table_elements(table_elements);
+    }
+
+    pooling_config
+}
+
+/// Returns the highest of provided limits, or [`None`] when any of them is
+/// unset, i.e. unlimited.
+fn highest_limit<T, Limits>(limits: Limits) -> Option<T>
+where
+    T: Copy + Ord,
+    Limits: IntoIterator<Item = Option<T>>,
+{
+    limits
+        .into_iter()
+        .try_fold(None, |highest: Option<T>, limit: Option<T>| {
+            limit.map(|limit: T| Some(highest.map_or(limit, |highest: T| highest.max(limit))))
+        })
+        .flatten()
+}
+
+/// Replays recorded execution against the recorded module's configuration,
+/// logging the response.
+async fn replay(config: Config, recording_path: &Path) -> AnyResult<()> {
+    let recording: Recording = Recording::read(recording_path)?;
+
+    let engine: WasmEngine = new_engine(&config).context("Failed to create WASM engine!")?;
+
+    let global_limits: ConfigInstanceLimits = config.global.instances.limits;
+
+    let modules: modules::Precompiled = modules::precompile(
+        &engine,
+        None,
+        config
+            .modules
+            .into_iter()
+            .filter(|module: &ConfigModule| module.id.0 == recording.module_id),
+    )
+    .context("Failed to precompile recorded module!")?;
+
+    let Some((module_id, module)) = modules.iter().next() else {
+        bail!(
+            r#"Recorded module with ID "{}" is not configured!"#,
+            recording.module_id
+        );
+    };
+
+    let replay: Replay = record::replay(
+        &engine,
+        &module.module,
+        recording,
+        &workers::instance_config(module_id, module, global_limits),
+    )
+    .await
+    .context("Failed to replay recording!")?;
+
+    if !replay.unreplayed_calls.is_empty() {
+        warn!(
+            calls = ?replay.unreplayed_calls,
+            "Module didn't make some of the recorded host calls!",
+        );
+    }
+
+    let ResponseBody::Buffered(data) = replay.response.body else {
+        bail!("Replayed response's body isn't buffered!");
+    };
+
+    info!(
+        is_error = replay.response.is_error,
+        status_code = ?replay.response.status_code,
+        headers = ?replay.response.headers,
+        body = %String::from_utf8_lossy(&data),
+        "Replayed recording.",
+    );
+
+    Ok(())
+}
+
+fn spawn_epoch_ticker(engine: WasmEngine) {
+    // Safe to drop as thread runs for the whole lifetime of the process.
+    drop(thread::spawn(move || loop {
+        thread::sleep(Duration::from_millis(EPOCH_TICK_MILLIS));
+
+        engine.increment_epoch();
+    }));
+}
+
+/// Installs a subscriber printing records to standard output. Modules' records
+/// are already filtered by their log policies, thus are passed through at any
+/// level.
+fn init_tracing() -> AnyResult<()> {
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer())
+        .with(
+            Targets::new()
+                .with_default(LevelFilter::INFO)
+                .with_target(lambda_rt::log::TARGET, LevelFilter::TRACE),
+        )
+        .try_init()
+        .map_err(Into::into)
 }
@@ -0,0 +1,105 @@
+use std::{collections::BTreeMap, future::Future, pin::Pin, sync::Arc};
+
+use anyhow::{anyhow, Result as AnyResult};
+use tokio::sync::{oneshot::channel as oneshot_channel, OnceCell};
+
+use lambda_rt::{
+    invoke::{Invocation, InvokeError},
+    CpuBudget, ExecutionError, InvokeProvider, RequestMeta, Response as LambdaResponse, SdkUser,
+    User as LambdaUser,
+};
+
+use super::{Request, RequestSender, ResponseReceiver, ResponseSender};
+
+pub struct InvokeHandler<User>
+where
+    User: LambdaUser,
+{
+    pub sender: RequestSender<User>,
+    pub cpu_budget: CpuBudget,
+    /// Invocations are rejected up front when unset, as they always execute
+    /// the default entry point.
+    pub has_default_entry_point: bool,
+}
+
+/// Invokes modules through the same request channels as routes, bypassing the
+/// global concurrency limit, as the invoking request already holds a permit.
+/// Handlers are set once module workers are spawned, as spawning them requires
+/// the invoker to already be part of the linker.
+#[derive(Clone, Default)]
+pub struct Invoker {
+    handlers: Arc<OnceCell<BTreeMap<String, InvokeHandler<SdkUser>>>>,
+}
+
+impl Invoker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_handlers(
+        &self,
+        handlers: BTreeMap<String, InvokeHandler<SdkUser>>,
+    ) -> AnyResult<()> {
+        self.handlers
+            .set(handlers)
+            .map_err(|_| anyhow!("Invoke handlers are already set!"))
+    }
+}
+
+impl InvokeProvider for Invoker {
+    type Result<'r> =
+        Pin<Box<dyn Future<Output = AnyResult<Result<LambdaResponse, InvokeError>>> + Send + 'r>>;
+
+    fn invoke(&mut self, invocation: Invocation) -> Self::Result<'_> {
+        Box::pin(async move {
+            let Some(handler) = self.handlers.get().and_then(
+                |handlers: &BTreeMap<String, InvokeHandler<SdkUser>>| {
+                    handlers.get(&invocation.module_id)
+                },
+            ) else {
+                return Ok(Err(InvokeError::UnknownModule));
+            };
+
+            if !handler.has_default_entry_point {
+                return Ok(Err(InvokeError::NoDefaultEntryPoint));
+            }
+
+            let Some(sender) = invocation.sender else {
+                return Ok(Err(InvokeError::NoSender));
+            };
+
+            let (response_sender, response_receiver): (ResponseSender, ResponseReceiver) =
+                oneshot_channel();
+
+            if handler
+                .sender
+                .send(Request {
+                    externally_sourced: false,
+                    user: SdkUser::new(sender),
+                    handler: None,
+                    data: invocation.data,
+                    meta: RequestMeta {
+                        call_chain: invocation.call_chain,
+                        ..RequestMeta::default()
+                    },
+                    cpu_budget: handler.cpu_budget,
+                    response_sender,
+                })
+                .await
+                .is_err()
+            {
+                return Ok(Err(InvokeError::Failed));
+            }
+
+            Ok(match response_receiver.await {
+                Ok(Ok(response)) => Ok(response),
+                Ok(Err(error))
+                    if error.downcast_ref() == Some(&ExecutionError::CpuBudgetExhausted) =>
+                {
+                    Err(InvokeError::CpuBudgetExhausted)
+                }
+                Ok(Err(_)) | Err(_) => Err(InvokeError::Failed),
+            })
+        })
+    }
+}
@@ -1,4 +1,21 @@
-use actix_web::{web::Bytes, HttpResponse};
+use std::{
+    convert::Infallible,
+    pin::Pin,
+    task::{Context as TaskContext, Poll},
+};
+
+use actix_web::{
+    body::{BodySize, MessageBody},
+    http::{
+        header::{
+            HeaderName, HeaderValue, AUTHORIZATION, CONNECTION, CONTENT_LENGTH, PROXY_AUTHENTICATE,
+            PROXY_AUTHORIZATION, TE, TRAILER, TRANSFER_ENCODING, UPGRADE,
+        },
+        StatusCode,
+    },
+    web::Bytes,
+    HttpRequest, HttpResponse, HttpResponseBuilder,
+};
 use anyhow::Result as AnyResult;
 use tokio::sync::{
     mpsc::{Receiver as MpscReceiver, Sender as MpscSender},
@@ -8,8 +25,12 @@ use tokio::sync::{
     },
 };
 
-use lambda_rt::{Response as LambdaResponse, User as LambdaUser};
+use lambda_rt::{
+    CpuBudget, ExecutionError, RequestMeta, Response as LambdaResponse,
+    ResponseBody as LambdaResponseBody, User as LambdaUser,
+};
 
+pub mod invoker;
 pub mod modules;
 pub mod workers;
 
@@ -22,17 +43,132 @@ where
 {
     externally_sourced: bool,
     user: User,
+    handler: Option<String>,
     data: Vec<u8>,
+    meta: RequestMeta,
+    cpu_budget: CpuBudget,
     response_sender: ResponseSender,
 }
 
 pub type RequestSender<User> = MpscSender<Request<User>>;
 pub type RequestReceiver<User> = MpscReceiver<Request<User>>;
 
+pub struct RouteHandler<User>
+where
+    User: LambdaUser,
+{
+    pub sender: RequestSender<User>,
+    pub route: String,
+    pub handler: Option<String>,
+    pub cpu_budget: CpuBudget,
+}
+
+impl<User> Clone for RouteHandler<User>
+where
+    User: LambdaUser,
+{
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            route: self.route.clone(),
+            handler: self.handler.clone(),
+            cpu_budget: self.cpu_budget,
+        }
+    }
+}
+
+/// Collects metadata of an inbound request for the module serving it. The
+/// `Authorization` header is withheld, as the sender is already authenticated
+/// by the server and exposed to modules separately.
+fn request_meta(request: &HttpRequest, route: String) -> RequestMeta {
+    RequestMeta {
+        method: request.method().to_string(),
+        path: String::from(request.path()),
+        route,
+        query: String::from(request.query_string()),
+        headers: request
+            .headers()
+            .iter()
+            .filter(|&(name, _): &(&HeaderName, &HeaderValue)| name != AUTHORIZATION)
+            .map(|(name, value): (&HeaderName, &HeaderValue)| {
+                (String::from(name.as_str()), value.as_bytes().to_vec())
+            })
+            .collect(),
+        call_chain: Vec::new(),
+    }
+}
+
+/// Body forwarding chunks of a streamed module response as they are written.
+struct StreamedBody(MpscReceiver<Vec<u8>>);
+
+impl MessageBody for StreamedBody {
+    type Error = Infallible;
+
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        self.0
+            .poll_recv(cx)
+            .map(|chunk: Option<Vec<u8>>| chunk.map(|chunk: Vec<u8>| Ok(Bytes::from(chunk))))
+    }
+}
+
+/// Headers which modules aren't allowed to set, as they are either hop-by-hop
+/// headers or are managed by the server itself.
+const DENIED_RESPONSE_HEADERS: [HeaderName; 9] = [
+    CONNECTION,
+    CONTENT_LENGTH,
+    HeaderName::from_static("keep-alive"),
+    PROXY_AUTHENTICATE,
+    PROXY_AUTHORIZATION,
+    TE,
+    TRAILER,
+    TRANSFER_ENCODING,
+    UPGRADE,
+];
+
+fn module_response(response: LambdaResponse) -> HttpResponse {
+    let status_code: StatusCode = response
+        .status_code
+        .and_then(|status_code: u16| StatusCode::from_u16(status_code).ok())
+        .unwrap_or(if response.is_error {
+            StatusCode::UNPROCESSABLE_ENTITY
+        } else {
+            StatusCode::OK
+        });
+
+    let mut builder: HttpResponseBuilder = HttpResponse::build(status_code);
+
+    response
+        .headers
+        .into_iter()
+        .filter_map(|(name, value): (String, Vec<u8>)| {
+            Some((
+                HeaderName::try_from(name).ok()?,
+                HeaderValue::try_from(value).ok()?,
+            ))
+        })
+        .filter(|(name, _): &(HeaderName, HeaderValue)| !DENIED_RESPONSE_HEADERS.contains(name))
+        .for_each(|header: (HeaderName, HeaderValue)| {
+            builder.append_header(header);
+        });
+
+    match response.body {
+        LambdaResponseBody::Buffered(data) => builder.body(data),
+        LambdaResponseBody::Streamed(chunks) => builder.body(StreamedBody(chunks)),
+    }
+}
+
 pub async fn request_handler<User>(
+    request: HttpRequest,
     user: User,
     body: Bytes,
-    sender: RequestSender<User>,
+    handler: RouteHandler<User>,
 ) -> HttpResponse
 where
     User: LambdaUser,
@@ -40,11 +176,15 @@ where
     let (response_sender, response_receiver): (ResponseSender, ResponseReceiver) =
         oneshot_channel();
 
-    if sender
+    if handler
+        .sender
         .send(Request {
             externally_sourced: true,
             user,
+            handler: handler.handler,
             data: body.to_vec(),
+            meta: request_meta(&request, handler.route),
+            cpu_budget: handler.cpu_budget,
             response_sender,
         })
         .await
@@ -56,12 +196,10 @@ where
         };
 
         match response {
-            Ok(response) => match response {
-                LambdaResponse::Success(response) => HttpResponse::Ok().body(response),
-                LambdaResponse::Error(response) => {
-                    HttpResponse::UnprocessableEntity().body(response)
-                }
-            },
+            Ok(response) => module_response(response),
+            Err(error) if error.downcast_ref() == Some(&ExecutionError::CpuBudgetExhausted) => {
+                HttpResponse::GatewayTimeout().body("Module exhausted its CPU budget!")
+            }
             Err(error) => HttpResponse::InternalServerError().body(
                 format!(
                     "Error occurred!\nContext: {}\nRoot cause: {}\nDebug version: {:?}",
@@ -1,70 +1,267 @@
 use std::{
     collections::{HashMap, VecDeque},
-    sync::Arc,
+    ffi::OsStr,
+    fs::{create_dir_all, read, write},
+    num::NonZeroU64,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 
-use anyhow::{bail, Context as _, Result as AnyResult};
+use anyhow::{Context as _, Result as AnyResult};
 use tokio::{
     spawn,
     sync::{Mutex, MutexGuard, OwnedSemaphorePermit, Semaphore},
 };
-use wasmtime::{Engine, Module as WasmModule};
+use tracing::{info, warn};
+use wasmtime::Engine;
 
-use lambda_rt::{Context as LambdaContext, LinkerWithSdk, SdkInstance, VerifiedModule};
+use lambda_rt::{
+    cache::ModuleCache, metrics::ExecutionMetrics, record::RecordConfig, wasi::WasiConfig,
+    Context as LambdaContext, InstanceConfig, InstanceState, LinkerWithSdk, SdkInstance,
+    VerifiedModule,
+};
+
+use crate::config::{
+    CpuBudget as ConfigCpuBudget, Egress as ConfigEgress, GlobalInstances as ConfigGlobalInstances,
+    Id as ModuleId, InstanceLimits as ConfigInstanceLimits, Log as ConfigLog,
+    Module as ConfigModule, Record as ConfigRecord, Wasi as ConfigWasi,
+    WasiPreopen as ConfigWasiPreopen,
+};
 
-use crate::config::{Id as ModuleId, Module as ConfigModule};
+use super::{Request, RequestReceiver};
 
-use super::{Request, RequestReceiver, ResponseSender};
+pub type Precompiled = HashMap<ModuleId, PrecompiledModule>;
 
-pub type Precompiled = HashMap<ModuleId, VerifiedModule>;
+/// Extension of modules compiled ahead of time, which are loaded as such
+/// instead of being compiled.
+const COMPILED_EXTENSION: &str = "cwasm";
+
+/// Target of the records metrics of executions are exported as.
+pub const METRICS_TARGET: &str = "lambda_web_server::metrics";
+
+pub struct PrecompiledModule {
+    pub module: VerifiedModule,
+    pub cpu_budget: Option<ConfigCpuBudget>,
+    pub limits: ConfigInstanceLimits,
+    pub egress: ConfigEgress,
+    pub log: ConfigLog,
+    pub wasi: Option<WasiConfig>,
+    pub record: Option<RecordConfig>,
+}
 
-pub fn precompile<Modules>(engine: &Engine, modules: Modules) -> AnyResult<Precompiled>
+pub fn precompile<Modules>(
+    engine: &Engine,
+    cache: Option<&ModuleCache>,
+    modules: Modules,
+) -> AnyResult<Precompiled>
 where
     Modules: IntoIterator<Item = ConfigModule>,
 {
     modules
         .into_iter()
         .map(|module: ConfigModule| -> AnyResult<_> {
+            let binary: Vec<u8> = read(&*module.path)
+                .with_context(|| format!(r#"Failed to read module with ID "{}"!"#, module.id.0))?;
+
+            let verified_module: VerifiedModule = if module.path.extension()
+                == Some(OsStr::new(COMPILED_EXTENSION))
+            {
+                VerifiedModule::deserialize(engine, &binary)
+            } else if let Some(cache) = cache {
+                cache.load_or_compile(engine, &binary)
+            } else {
+                VerifiedModule::new(engine, &binary)
+            }
+            .with_context(|| format!(r#"Failed to verify module with ID "{}"!"#, module.id.0))?;
+
             Ok((
                 module.id,
-                VerifiedModule::new(WasmModule::from_file(&engine, module.path.into_inner())?)?,
+                PrecompiledModule {
+                    module: verified_module,
+                    cpu_budget: module.cpu_budget,
+                    limits: module.limits,
+                    egress: module.egress,
+                    log: module.log,
+                    wasi: module.wasi.map(|wasi: ConfigWasi| WasiConfig {
+                        env: wasi.env.into_iter().collect(),
+                        preopens: wasi
+                            .preopens
+                            .into_iter()
+                            .map(|preopen: ConfigWasiPreopen| (preopen.host, preopen.guest))
+                            .collect(),
+                    }),
+                    record: module.record.map(|record: ConfigRecord| RecordConfig {
+                        directory: record.directory,
+                        include_secrets: record.include_secrets,
+                        max_recordings: record.max_recordings.map(NonZeroU64::get),
+                    }),
+                },
             ))
         })
         .collect()
 }
 
+/// Compiles modules and writes them serialized into provided directory, named
+/// after their IDs.
+pub fn compile<Modules>(engine: &Engine, modules: Modules, output_dir: &Path) -> AnyResult<()>
+where
+    Modules: IntoIterator<Item = ConfigModule>,
+{
+    create_dir_all(output_dir).context("Failed to create output directory!")?;
+
+    modules
+        .into_iter()
+        .try_for_each(|module: ConfigModule| -> AnyResult<()> {
+            let binary: Vec<u8> = read(module.path.into_inner())
+                .with_context(|| format!(r#"Failed to read module with ID "{}"!"#, module.id.0))?;
+
+            let serialized: Vec<u8> = VerifiedModule::new(engine, &binary)
+                .with_context(|| format!(r#"Failed to verify module with ID "{}"!"#, module.id.0))?
+                .serialize()
+                .with_context(|| {
+                    format!(r#"Failed to serialize module with ID "{}"!"#, module.id.0)
+                })?;
+
+            let path: PathBuf = output_dir.join(format!("{}.{COMPILED_EXTENSION}", module.id.0));
+
+            write(&path, serialized).with_context(|| {
+                format!(
+                    r#"Failed to write compiled module with ID "{}" to {}!"#,
+                    module.id.0,
+                    path.display()
+                )
+            })?;
+
+            info!(
+                module = module.id.0,
+                path = %path.display(),
+                "Compiled module.",
+            );
+
+            Ok(())
+        })
+}
+
+/// Pool of a module's idle instances, along with counters of instances
+/// discarded after being tainted by an execution and of replacements of
+/// discarded instances.
+pub struct InstancePool<Ctx>
+where
+    Ctx: LambdaContext,
+{
+    instances: Mutex<VecDeque<SdkInstance<Ctx>>>,
+    max_size: usize,
+    reset_between_requests: bool,
+    tainted: AtomicU64,
+    replaced: AtomicU64,
+    failed_replacements: AtomicU64,
+}
+
+/// Snapshot of an instance pool's counters.
+#[derive(Debug, Copy, Clone)]
+pub struct InstancePoolCounters {
+    pub tainted: u64,
+    pub replaced: u64,
+    pub failed_replacements: u64,
+}
+
+impl<Ctx> InstancePool<Ctx>
+where
+    Ctx: LambdaContext,
+{
+    fn new(
+        instances: VecDeque<SdkInstance<Ctx>>,
+        max_size: usize,
+        reset_between_requests: bool,
+    ) -> Self {
+        Self {
+            instances: Mutex::new(instances),
+            max_size,
+            reset_between_requests,
+            tainted: AtomicU64::new(0),
+            replaced: AtomicU64::new(0),
+            failed_replacements: AtomicU64::new(0),
+        }
+    }
+
+    pub fn counters(&self) -> InstancePoolCounters {
+        InstancePoolCounters {
+            tainted: self.tainted.load(Ordering::Relaxed),
+            replaced: self.replaced.load(Ordering::Relaxed),
+            failed_replacements: self.failed_replacements.load(Ordering::Relaxed),
+        }
+    }
+
+    async fn pop(&self) -> Option<SdkInstance<Ctx>> {
+        self.instances.lock().await.pop_front()
+    }
+
+    async fn is_full(&self) -> bool {
+        self.max_size <= self.instances.lock().await.len()
+    }
+
+    /// Returns instance to the pool unless it is full, returning whether it
+    /// was kept.
+    async fn push(&self, instance: SdkInstance<Ctx>) -> bool {
+        let mut instances_guard: MutexGuard<'_, VecDeque<SdkInstance<Ctx>>> =
+            self.instances.lock().await;
+
+        let kept: bool = instances_guard.len() < self.max_size;
+
+        if kept {
+            instances_guard.push_back(instance);
+        }
+
+        drop(instances_guard);
+
+        kept
+    }
+}
+
 pub async fn spawn_module_worker<Ctx>(
     mut request_receiver: RequestReceiver<Ctx::User>,
     linker: Arc<LinkerWithSdk<Ctx>>,
     module: VerifiedModule,
+    instance_config: Arc<InstanceConfig>,
     global_request_semaphore: Arc<Semaphore>,
-    min_instances_pool_size: usize,
-    max_instances_pool_size: usize,
+    pool_config: ConfigGlobalInstances,
+    export_metrics: bool,
 ) -> AnyResult<()>
 where
     Ctx: LambdaContext<ConstructorContext = ()>,
     Ctx::Vault: Clone,
+    Ctx::Network: Clone,
+    Ctx::Kv: Clone,
+    Ctx::Invoker: Clone,
 {
-    let instance_pool: Arc<Mutex<VecDeque<SdkInstance<Ctx>>>> = Arc::new(Mutex::new({
-        let mut deque: VecDeque<SdkInstance<Ctx>> =
-            VecDeque::with_capacity(min_instances_pool_size);
+    let instance_pool: Arc<InstancePool<Ctx>> = Arc::new(InstancePool::new(
+        {
+            let mut deque: VecDeque<SdkInstance<Ctx>> =
+                VecDeque::with_capacity(pool_config.init_pool_size.into());
 
-        for _ in 0..min_instances_pool_size {
-            deque.push_back(SdkInstance::new(&linker, &module, ()).await?);
-        }
+            for _ in 0..pool_config.init_pool_size {
+                deque.push_back(SdkInstance::new(&linker, &module, (), &instance_config).await?);
+            }
 
-        deque
-    }));
+            deque
+        },
+        pool_config.max_idle_pool_size.into(),
+        pool_config.reset_between_requests,
+    ));
 
     drop(spawn(async move {
         while let Some(request) = request_receiver.recv().await {
             spawn_request_handling_task(
                 linker.clone(),
                 module.clone(),
+                instance_config.clone(),
                 &global_request_semaphore,
                 instance_pool.clone(),
-                max_instances_pool_size,
                 request,
+                export_metrics,
             );
         }
     }));
@@ -76,13 +273,17 @@ where
 fn spawn_request_handling_task<Ctx>(
     linker: Arc<LinkerWithSdk<Ctx>>,
     module: VerifiedModule,
+    instance_config: Arc<InstanceConfig>,
     global_request_semaphore: &Arc<Semaphore>,
-    instance_pool: Arc<Mutex<VecDeque<SdkInstance<Ctx>>>>,
-    max_instances_pool_size: usize,
+    instance_pool: Arc<InstancePool<Ctx>>,
     request: Request<Ctx::User>,
+    export_metrics: bool,
 ) where
     Ctx: LambdaContext<ConstructorContext = ()>,
     Ctx::Vault: Clone,
+    Ctx::Network: Clone,
+    Ctx::Kv: Clone,
+    Ctx::Invoker: Clone,
 {
     const CONST_OK: anyhow::Result<()> = Ok(());
 
@@ -102,11 +303,11 @@ fn spawn_request_handling_task<Ctx>(
             if let Err(error) = handle_request(
                 linker,
                 module,
+                instance_config,
                 instance_pool,
-                request.response_sender,
-                max_instances_pool_size,
-                request.data,
-                Some(request.user),
+                request,
+                permit,
+                export_metrics,
             )
             .await
             {
@@ -117,9 +318,6 @@ fn spawn_request_handling_task<Ctx>(
                 );
             }
 
-            // Explicitly drop permit to ensure proper drop order.
-            drop(permit);
-
             CONST_OK
         }
     }));
@@ -128,37 +326,140 @@ fn spawn_request_handling_task<Ctx>(
 async fn handle_request<Ctx>(
     linker: Arc<LinkerWithSdk<Ctx>>,
     module: VerifiedModule,
-    instance_pool: Arc<Mutex<VecDeque<SdkInstance<Ctx>>>>,
-    response_sender: ResponseSender,
-    max_instances_pool_size: usize,
-    request_data: Vec<u8>,
-    sender: Option<Ctx::User>,
+    instance_config: Arc<InstanceConfig>,
+    instance_pool: Arc<InstancePool<Ctx>>,
+    request: Request<Ctx::User>,
+    permit: Option<OwnedSemaphorePermit>,
+    export_metrics: bool,
 ) -> AnyResult<()>
 where
     Ctx: LambdaContext<ConstructorContext = ()>,
     Ctx::Vault: Clone,
+    Ctx::Network: Clone,
+    Ctx::Kv: Clone,
+    Ctx::Invoker: Clone,
 {
-    let mut instance: SdkInstance<Ctx> =
-        if let Some(instance) = instance_pool.lock().await.pop_front() {
-            instance
-        } else {
-            SdkInstance::new(&linker, &module, ())
-                .await
-                .context("Failed to create new module instance!")?
-        };
-
-    let Ok(()) = response_sender.send(instance.execute(request_data, sender).await) else {
-        bail!("Failed to send response!");
+    let mut instance: SdkInstance<Ctx> = if let Some(instance) = instance_pool.pop().await {
+        instance
+    } else {
+        SdkInstance::new(&linker, &module, (), &instance_config)
+            .await
+            .context("Failed to create new module instance!")?
     };
 
-    let mut instance_pool_guard: MutexGuard<'_, VecDeque<SdkInstance<Ctx>>> =
-        instance_pool.lock().await;
+    let route: String = request.meta.route.clone();
+
+    let result: AnyResult<InstanceState> = instance
+        .execute(
+            request.handler.as_deref(),
+            request.data,
+            request.meta,
+            Some(request.user),
+            request.cpu_budget,
+            request.response_sender,
+        )
+        .await;
 
-    if instance_pool_guard.len() < max_instances_pool_size {
-        instance_pool_guard.push_back(instance);
+    if export_metrics {
+        log_metrics(
+            &instance_config.module_id,
+            &route,
+            instance.metrics(),
+            instance_pool.counters(),
+        );
     }
 
-    drop(instance_pool_guard);
+    let clean: bool = matches!(result, Ok(InstanceState::Clean));
 
-    Ok(())
+    if clean && !instance_pool.reset_between_requests {
+        instance_pool.push(instance).await;
+    } else {
+        // Released slot is restored to pristine memory when the pooling
+        // allocator is used, making replacement cheap.
+        drop(instance);
+
+        if !clean {
+            let tainted: u64 = instance_pool.tainted.fetch_add(1, Ordering::Relaxed) + 1;
+
+            warn!(
+                module = instance_config.module_id,
+                tainted, "Discarded module instance tainted by execution!",
+            );
+        }
+
+        // Request's permit is held until the replacement is created, so
+        // instances are only ever created within the request limit.
+        // Safe to drop as future is managed by runtime.
+        drop(spawn(async move {
+            replace_instance(linker, module, instance_config, instance_pool).await;
+
+            drop(permit);
+        }));
+    }
+
+    result.map(drop)
+}
+
+fn log_metrics(
+    module_id: &str,
+    route: &str,
+    metrics: &ExecutionMetrics,
+    pool_counters: InstancePoolCounters,
+) {
+    info!(
+        target: METRICS_TARGET,
+        module = module_id,
+        route,
+        wall_time = ?metrics.wall_time,
+        host_time = ?metrics.host_time,
+        fuel_consumed = metrics.fuel_consumed,
+        peak_memory_bytes = metrics.peak_memory_bytes,
+        memory_growth_bytes = metrics.memory_growth_bytes,
+        bytes_in = metrics.bytes_in,
+        bytes_out = metrics.bytes_out,
+        outbound_requests = metrics.outbound_requests,
+        tainted_instances = pool_counters.tainted,
+        replaced_instances = pool_counters.replaced,
+        failed_replacements = pool_counters.failed_replacements,
+        "Module executed.",
+    );
+}
+
+/// Creates instance in place of a discarded one, unless the pool is already
+/// full.
+async fn replace_instance<Ctx>(
+    linker: Arc<LinkerWithSdk<Ctx>>,
+    module: VerifiedModule,
+    instance_config: Arc<InstanceConfig>,
+    instance_pool: Arc<InstancePool<Ctx>>,
+) where
+    Ctx: LambdaContext<ConstructorContext = ()>,
+    Ctx::Vault: Clone,
+    Ctx::Network: Clone,
+    Ctx::Kv: Clone,
+    Ctx::Invoker: Clone,
+{
+    if instance_pool.is_full().await {
+        return;
+    }
+
+    match SdkInstance::new(&linker, &module, (), &instance_config).await {
+        Ok(instance) => {
+            if instance_pool.push(instance).await {
+                instance_pool.replaced.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        Err(error) => {
+            let failed_replacements: u64 = instance_pool
+                .failed_replacements
+                .fetch_add(1, Ordering::Relaxed)
+                + 1;
+
+            warn!(
+                module = instance_config.module_id,
+                failed_replacements,
+                "Failed to replace discarded module instance! Error: {error:#}",
+            );
+        }
+    }
 }
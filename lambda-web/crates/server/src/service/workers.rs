@@ -1,41 +1,64 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::create_dir_all,
+    num::NonZeroU64,
+    sync::Arc,
+};
 
-use anyhow::{anyhow, Context as _, Result as AnyResult};
+use anyhow::{anyhow, bail, Context as _, Result as AnyResult};
 use tokio::sync::{mpsc::channel as mpsc_channel, Semaphore};
+use tracing::level_filters::LevelFilter;
 
-use lambda_rt::{Context as LambdaContext, LinkerWithSdk};
+use lambda_rt::{
+    egress::EgressPolicy, log::LogPolicy, Context as LambdaContext, CpuBudget, InstanceConfig,
+    InstanceLimits, LinkerWithSdk, User as LambdaUser,
+};
 
-use crate::config::{
-    Global as GlobalConfig, Id as ModuleId, Route as ConfigRoute, RoutePath as ConfigRoutePath,
+use crate::{
+    config::{
+        CpuBudget as ConfigCpuBudget, Egress as ConfigEgress, Global as GlobalConfig,
+        Id as ModuleId, InstanceLimits as ConfigInstanceLimits, Log as ConfigLog,
+        LogLevel as ConfigLogLevel, Route as ConfigRoute, RoutePath as ConfigRoutePath,
+    },
+    EPOCH_TICK_MILLIS,
 };
 
 use super::{
-    modules::{spawn_module_worker, Precompiled as PrecompiledModules},
-    RequestReceiver, RequestSender,
+    invoker::InvokeHandler,
+    modules::{spawn_module_worker, Precompiled as PrecompiledModules, PrecompiledModule},
+    RequestReceiver, RequestSender, RouteHandler,
 };
 
-pub async fn generate_route_handlers<Ctx>(
-    config: GlobalConfig,
-    routes: Vec<ConfigRoute>,
-    modules: PrecompiledModules,
-    linker: Arc<LinkerWithSdk<Ctx>>,
-) -> AnyResult<BTreeMap<ConfigRoutePath, RequestSender<Ctx::User>>>
+/// Worker serving a module, along with the module's CPU budget and the entry
+/// points it exports.
+pub struct ModuleWorker<User>
 where
-    Ctx: LambdaContext<ConstructorContext = ()>,
-    Ctx::Vault: Clone,
+    User: LambdaUser,
 {
-    let request_handlers_senders: BTreeMap<ModuleId, RequestSender<Ctx::User>> =
-        generate_module_workers(config, modules, linker).await?;
+    pub sender: RequestSender<User>,
+    pub cpu_budget: Option<ConfigCpuBudget>,
+    pub has_default_entry_point: bool,
+    pub handlers: BTreeSet<String>,
+}
+
+/// Workers serving each module.
+pub type ModuleWorkers<User> = BTreeMap<ModuleId, ModuleWorker<User>>;
 
+pub fn generate_route_handlers<User>(
+    routes: Vec<ConfigRoute>,
+    module_workers: &ModuleWorkers<User>,
+) -> AnyResult<BTreeMap<ConfigRoutePath, RouteHandler<User>>>
+where
+    User: LambdaUser,
+{
     routes
         .into_iter()
         .try_fold(
             BTreeMap::new(),
-            |mut acc: BTreeMap<ConfigRoutePath, RequestSender<Ctx::User>>,
-             route: ConfigRoute| -> AnyResult<BTreeMap<ConfigRoutePath, RequestSender<Ctx::User>>> {
-                request_handlers_senders
+            |mut acc: BTreeMap<ConfigRoutePath, RouteHandler<User>>,
+             route: ConfigRoute| -> AnyResult<BTreeMap<ConfigRoutePath, RouteHandler<User>>> {
+                module_workers
                     .get(&route.module)
-                    .cloned()
                     .ok_or_else(
                         || anyhow!(
                             r#"Module with ID "{module}", required by route with path "{path}", not defined!"#,
@@ -43,9 +66,19 @@ where
                             path = route.path.0,
                         )
                     )
-                    .and_then(|request_handlers_sender: RequestSender<Ctx::User>| {
+                    .and_then(|worker: &ModuleWorker<User>| {
+                        check_route_entry_point(&route, worker)?;
+
+                        Ok(RouteHandler {
+                            sender: worker.sender.clone(),
+                            route: route.path.0.clone(),
+                            handler: route.handler.clone(),
+                            cpu_budget: resolve_cpu_budget(route.cpu_budget.or(worker.cpu_budget)),
+                        })
+                    })
+                    .and_then(|route_handler: RouteHandler<User>| {
                         acc
-                            .insert(route.path.clone(), request_handlers_sender)
+                            .insert(route.path.clone(), route_handler)
                             .is_none()
                             .then_some(acc)
                             .ok_or_else(|| anyhow!(
@@ -58,39 +91,242 @@ where
         .context("Failed to generate route handlers!")
 }
 
-async fn generate_module_workers<Ctx>(
+fn check_route_entry_point<User>(route: &ConfigRoute, worker: &ModuleWorker<User>) -> AnyResult<()>
+where
+    User: LambdaUser,
+{
+    match &route.handler {
+        None if !worker.has_default_entry_point => bail!(
+            r#"Module with ID "{module}", required by route with path "{path}", doesn't export default entry point!"#,
+            module = route.module.0,
+            path = route.path.0,
+        ),
+        Some(handler) if !worker.handlers.contains(handler) => bail!(
+            r#"Module with ID "{module}", required by route with path "{path}", doesn't export handler "{handler}"!"#,
+            module = route.module.0,
+            path = route.path.0,
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Generates handlers through which modules invoke each other, using each
+/// module's own CPU budget.
+pub fn generate_invoke_handlers<User>(
+    module_workers: &ModuleWorkers<User>,
+) -> BTreeMap<String, InvokeHandler<User>>
+where
+    User: LambdaUser,
+{
+    module_workers
+        .iter()
+        .map(|(module_id, worker): (&ModuleId, &ModuleWorker<User>)| {
+            (
+                module_id.0.clone(),
+                InvokeHandler {
+                    sender: worker.sender.clone(),
+                    cpu_budget: resolve_cpu_budget(worker.cpu_budget),
+                    has_default_entry_point: worker.has_default_entry_point,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Configuration of module's instances, with limits not set for the module
+/// falling back to global ones.
+pub fn instance_config(
+    module_id: &ModuleId,
+    module: &PrecompiledModule,
+    global_limits: ConfigInstanceLimits,
+) -> InstanceConfig {
+    InstanceConfig {
+        limits: resolve_instance_limits(module.limits, global_limits),
+        egress_policy: resolve_egress_policy(module.egress.clone()),
+        log_policy: resolve_log_policy(module.log),
+        module_id: module_id.0.clone(),
+        wasi: module.wasi.clone(),
+        record: module.record.clone(),
+    }
+}
+
+fn resolve_cpu_budget(cpu_budget: Option<ConfigCpuBudget>) -> CpuBudget {
+    match cpu_budget {
+        None => CpuBudget::Unlimited,
+        Some(ConfigCpuBudget::Fuel(fuel)) => CpuBudget::Fuel(fuel),
+        Some(ConfigCpuBudget::TimeoutMs(timeout)) => {
+            // Deadline is rounded up by one tick as the epoch may be
+            // incremented right after the deadline is set.
+            NonZeroU64::new(timeout.get() / EPOCH_TICK_MILLIS + 1)
+                .map_or(CpuBudget::Unlimited, CpuBudget::EpochDeadline)
+        }
+    }
+}
+
+fn resolve_instance_limits(
+    module_limits: ConfigInstanceLimits,
+    global_limits: ConfigInstanceLimits,
+) -> InstanceLimits {
+    let ConfigInstanceLimits {
+        memory_bytes,
+        table_elements,
+        instances,
+    }: ConfigInstanceLimits = module_limits.or(global_limits);
+
+    InstanceLimits {
+        max_memory_bytes: memory_bytes,
+        max_table_elements: table_elements,
+        max_instances: instances,
+    }
+}
+
+fn resolve_egress_policy(egress: ConfigEgress) -> EgressPolicy {
+    let default: EgressPolicy = EgressPolicy::default();
+
+    EgressPolicy {
+        allowed_hosts: egress.allowed_hosts.or(default.allowed_hosts),
+        allowed_schemes: egress.allowed_schemes.or(default.allowed_schemes),
+        allowed_methods: egress.allowed_methods.or(default.allowed_methods),
+        allowed_ports: egress.allowed_ports.or(default.allowed_ports),
+        allow_private_destinations: egress.allow_private_destinations,
+    }
+}
+
+fn resolve_log_policy(log: ConfigLog) -> LogPolicy {
+    let default: LogPolicy = LogPolicy::default();
+
+    LogPolicy {
+        max_level: log
+            .level
+            .map_or(default.max_level, |level: ConfigLogLevel| match level {
+                ConfigLogLevel::Off => LevelFilter::OFF,
+                ConfigLogLevel::Error => LevelFilter::ERROR,
+                ConfigLogLevel::Warn => LevelFilter::WARN,
+                ConfigLogLevel::Info => LevelFilter::INFO,
+                ConfigLogLevel::Debug => LevelFilter::DEBUG,
+                ConfigLogLevel::Trace => LevelFilter::TRACE,
+            }),
+        max_records_per_request: log
+            .max_records_per_request
+            .unwrap_or(default.max_records_per_request),
+        max_bytes_per_request: log
+            .max_bytes_per_request
+            .unwrap_or(default.max_bytes_per_request),
+    }
+}
+
+pub async fn generate_module_workers<Ctx>(
     config: GlobalConfig,
     modules: PrecompiledModules,
     linker: Arc<LinkerWithSdk<Ctx>>,
-) -> AnyResult<BTreeMap<ModuleId, RequestSender<Ctx::User>>>
+) -> AnyResult<ModuleWorkers<Ctx::User>>
 where
     Ctx: LambdaContext<ConstructorContext = ()>,
     Ctx::Vault: Clone,
+    Ctx::Network: Clone,
+    Ctx::Kv: Clone,
+    Ctx::Invoker: Clone,
 {
     let global_requests_semaphore: Arc<Semaphore> =
         Arc::new(Semaphore::new(config.requests.max_concurrent.get().into()));
 
-    let mut module_workers: BTreeMap<ModuleId, RequestSender<Ctx::User>> = BTreeMap::new();
+    let mut module_workers: ModuleWorkers<Ctx::User> = BTreeMap::new();
 
     for (module_id, module) in modules {
+        if let Some(record) = &module.record {
+            create_dir_all(&record.directory).with_context(|| {
+                format!(
+                    r#"Failed to create recording directory of module with ID "{}"!"#,
+                    module_id.0
+                )
+            })?;
+        }
+
         let (sender, receiver): (RequestSender<Ctx::User>, RequestReceiver<Ctx::User>) =
             mpsc_channel(config.requests.max_concurrent.get().into());
 
+        let instance_config: InstanceConfig =
+            instance_config(&module_id, &module, config.instances.limits);
+
+        let has_default_entry_point: bool = module.module.has_default_entry_point();
+
+        let handlers: BTreeSet<String> = module.module.handlers().map(String::from).collect();
+
         spawn_module_worker(
             receiver,
             linker.clone(),
-            module,
+            module.module,
+            Arc::new(instance_config),
             global_requests_semaphore.clone(),
-            config.instances.init_pool_size.into(),
-            config.instances.max_idle_pool_size.into(),
+            config.instances,
+            config.requests.export_metrics,
         )
         .await?;
 
-        let maybe_sender: Option<RequestSender<Ctx::User>> =
-            module_workers.insert(module_id, sender);
+        let maybe_worker: Option<ModuleWorker<Ctx::User>> = module_workers.insert(
+            module_id,
+            ModuleWorker {
+                sender,
+                cpu_budget: module.cpu_budget,
+                has_default_entry_point,
+                handlers,
+            },
+        );
 
-        debug_assert!(maybe_sender.is_none(), "Module ID repetition!");
+        debug_assert!(maybe_worker.is_none(), "Module ID repetition!");
     }
 
     Ok(module_workers)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use tokio::sync::mpsc::channel as mpsc_channel;
+
+    use lambda_rt::SdkUser;
+
+    use crate::config::{Id as ModuleId, Route as ConfigRoute, RoutePath as ConfigRoutePath};
+
+    use super::{check_route_entry_point, ModuleWorker};
+
+    fn worker(has_default_entry_point: bool, handlers: &[&str]) -> ModuleWorker<SdkUser> {
+        ModuleWorker {
+            sender: mpsc_channel(1).0,
+            cpu_budget: None,
+            has_default_entry_point,
+            handlers: handlers
+                .iter()
+                .copied()
+                .map(String::from)
+                .collect::<BTreeSet<_>>(),
+        }
+    }
+
+    fn route(handler: Option<&str>) -> ConfigRoute {
+        ConfigRoute {
+            path: ConfigRoutePath(String::from("/route")),
+            module: ModuleId(String::from("module")),
+            handler: handler.map(String::from),
+            cpu_budget: None,
+        }
+    }
+
+    #[test]
+    fn routes_require_default_entry_point_when_handler_is_unset() {
+        assert!(check_route_entry_point(&route(None), &worker(true, &[])).is_ok());
+        assert!(check_route_entry_point(&route(None), &worker(false, &["create"])).is_err());
+    }
+
+    #[test]
+    fn routes_require_exported_handler() {
+        assert!(
+            check_route_entry_point(&route(Some("create")), &worker(false, &["create"])).is_ok()
+        );
+        assert!(
+            check_route_entry_point(&route(Some("create")), &worker(true, &["delete"])).is_err()
+        );
+        assert!(check_route_entry_point(&route(Some("")), &worker(true, &["create"])).is_err());
+    }
+}
@@ -1,10 +1,10 @@
 use std::{future::Future, pin::Pin, sync::Arc};
 
-use anyhow::Result as AnyResult;
-use sqlx::{query_scalar, PgPool};
+use anyhow::{Context as _, Result as AnyResult};
+use sqlx::{query_as, query_scalar, PgPool};
 use zeroize::Zeroizing;
 
-use lambda_rt::VaultProvider;
+use lambda_rt::{crypto::VaultKey, VaultProvider};
 
 #[derive(Debug, Clone)]
 pub struct Vault {
@@ -23,6 +23,8 @@ impl VaultProvider for Vault {
     type Result<'r> =
         Pin<Box<dyn Future<Output = AnyResult<Option<Zeroizing<Vec<u8>>>>> + Send + 'r>>;
 
+    type KeyResult<'r> = Pin<Box<dyn Future<Output = AnyResult<Option<VaultKey>>> + Send + 'r>>;
+
     fn fetch_secret(&mut self, identifier: String) -> Self::Result<'_> {
         Box::pin(async {
             query_scalar(include_str!("sql/fetch_secret.sql"))
@@ -33,4 +35,24 @@ impl VaultProvider for Vault {
                 .map_err(Into::into)
         })
     }
+
+    fn fetch_key(&mut self, identifier: String) -> Self::KeyResult<'_> {
+        Box::pin(async {
+            let Some((algorithm, key)): Option<(String, Vec<u8>)> =
+                query_as(include_str!("sql/fetch_key.sql"))
+                    .bind(identifier)
+                    .fetch_optional(&*self.pool)
+                    .await?
+            else {
+                return Ok(None);
+            };
+
+            Ok(Some(VaultKey {
+                algorithm: algorithm
+                    .parse()
+                    .context("Vault key is bound to an unknown algorithm!")?,
+                material: Zeroizing::new(key),
+            }))
+        })
+    }
 }
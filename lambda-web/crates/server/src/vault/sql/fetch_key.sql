@@ -0,0 +1,4 @@
+SELECT "public"."vault_keys"."algorithm", "public"."vault_keys"."key"
+FROM "public"."vault_keys"
+WHERE "public"."vault_keys"."identifier" = $1
+LIMIT 1;